@@ -0,0 +1,3 @@
+-- Idempotency key of the submission that answered a session question.
+-- A retried or double-clicked submission carries the same key and is replayed instead of recorded twice.
+ALTER TABLE session_questions ADD COLUMN answer_key TEXT;
@@ -0,0 +1,2 @@
+-- Pinned options (e.g. "All of the above") keep their authored position when options are shuffled.
+ALTER TABLE options ADD COLUMN is_pinned BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+-- Learner's self-reported confidence for an answer (NULL when not rated).
+ALTER TABLE user_answers ADD COLUMN confidence TEXT
+    CHECK (confidence IN ('sure', 'unsure', 'guess'));
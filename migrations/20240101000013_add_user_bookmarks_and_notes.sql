@@ -0,0 +1,23 @@
+-- Bookmarks and notes belong to the user, so they persist across sessions
+CREATE TABLE IF NOT EXISTS user_bookmarks (
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, question_id)
+);
+
+CREATE TABLE IF NOT EXISTS question_notes (
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, question_id)
+);
+
+-- Carry over existing per-session bookmarks (session_questions.is_bookmarked is no longer read)
+INSERT INTO user_bookmarks (user_id, question_id)
+SELECT DISTINCT qs.user_id, sq.question_id
+FROM session_questions sq
+JOIN quiz_sessions qs ON qs.id = sq.session_id
+WHERE sq.is_bookmarked AND qs.user_id IS NOT NULL
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,6 @@
+-- Study mode: 'quiz' (pick options) or 'flashcard' (reveal the answer and self-grade)
+ALTER TABLE quiz_sessions ADD COLUMN study_mode TEXT NOT NULL DEFAULT 'quiz';
+
+-- Self-graded answers have no selected option
+ALTER TABLE user_answers ALTER COLUMN option_id DROP NOT NULL;
+ALTER TABLE user_answers ADD COLUMN self_grade TEXT CHECK (self_grade IN ('again', 'hard', 'good', 'easy'));
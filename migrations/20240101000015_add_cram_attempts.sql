@@ -0,0 +1,30 @@
+-- Cram sessions ask a missed question again later in the same session,
+-- so a session can hold several attempts at one question
+ALTER TABLE session_questions ADD COLUMN attempt INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE session_questions DROP CONSTRAINT uq_session_questions_session_question;
+ALTER TABLE session_questions ADD CONSTRAINT uq_session_questions_session_question_attempt
+    UNIQUE (session_id, question_id, attempt);
+
+ALTER TABLE user_answers ADD COLUMN attempt INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE user_answers DROP CONSTRAINT uq_user_answers_triplet;
+ALTER TABLE user_answers ADD CONSTRAINT uq_user_answers_attempt_option
+    UNIQUE (session_id, question_id, attempt, option_id);
+
+-- Scores count first attempts only; a session is complete once every attempt is answered
+CREATE OR REPLACE VIEW session_stats AS
+SELECT
+    s.id AS session_id,
+    s.name,
+    s.session_token,
+    s.quiz_id,
+    s.question_count,
+    s.selection_mode,
+    (SELECT COUNT(*)::INTEGER FROM session_questions WHERE session_id = s.id AND attempt = 1) AS total_questions,
+    (SELECT COUNT(*)::INTEGER FROM session_questions WHERE session_id = s.id AND attempt = 1 AND is_correct IS NOT NULL) AS answered_questions,
+    (SELECT COUNT(*)::INTEGER FROM session_questions WHERE session_id = s.id AND attempt = 1 AND is_correct IS TRUE) AS correct_answers,
+    CASE WHEN
+        (SELECT COUNT(*) FROM session_questions WHERE session_id = s.id AND is_correct IS NOT NULL)
+        >= (SELECT COUNT(*) FROM session_questions WHERE session_id = s.id)
+        AND (SELECT COUNT(*) FROM session_questions WHERE session_id = s.id) > 0
+    THEN TRUE ELSE FALSE END AS is_complete
+FROM quiz_sessions s;
@@ -0,0 +1,16 @@
+-- Answer timing is measured on the server, once per answered question: the clock starts
+-- when the question is shown and time beyond the idle threshold is kept apart
+ALTER TABLE session_questions ADD COLUMN served_at TIMESTAMPTZ;
+ALTER TABLE session_questions ADD COLUMN answered_at TIMESTAMPTZ;
+ALTER TABLE session_questions ADD COLUMN active_ms INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE session_questions ADD COLUMN idle_ms INTEGER NOT NULL DEFAULT 0;
+
+-- Carry over the client-reported durations (stored once per selected option)
+UPDATE session_questions sq
+SET active_ms = ua.duration_ms
+FROM (
+    SELECT session_id, question_id, attempt, MAX(duration_ms) AS duration_ms
+    FROM user_answers
+    GROUP BY session_id, question_id, attempt
+) ua
+WHERE ua.session_id = sq.session_id AND ua.question_id = sq.question_id AND ua.attempt = sq.attempt;
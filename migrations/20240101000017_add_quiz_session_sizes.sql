@@ -0,0 +1,4 @@
+-- Per-quiz session size defaults and limits chosen by the owner (NULL = instance default)
+ALTER TABLE quizzes ADD COLUMN default_session_size INTEGER CHECK (default_session_size > 0);
+ALTER TABLE quizzes ADD COLUMN min_session_size INTEGER CHECK (min_session_size > 0);
+ALTER TABLE quizzes ADD COLUMN max_session_size INTEGER CHECK (max_session_size > 0);
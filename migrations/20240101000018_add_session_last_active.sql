@@ -0,0 +1,14 @@
+-- Server-side "last active" tracking so in-progress sessions can be resumed from any device
+ALTER TABLE quiz_sessions ADD COLUMN last_active_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+UPDATE quiz_sessions s
+SET last_active_at = a.last_answered_at
+FROM (
+    SELECT session_id, MAX(answered_at) AS last_answered_at
+    FROM session_questions
+    WHERE answered_at IS NOT NULL
+    GROUP BY session_id
+) a
+WHERE a.session_id = s.id;
+
+CREATE INDEX idx_quiz_sessions_user_quiz_active ON quiz_sessions (user_id, quiz_id, last_active_at DESC);
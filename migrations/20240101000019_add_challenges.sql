@@ -0,0 +1,15 @@
+-- Shareable challenges: the same question set and option order, replayed by other users
+CREATE TABLE challenges (
+    id SERIAL PRIMARY KEY,
+    public_id TEXT NOT NULL UNIQUE,
+    quiz_id INTEGER NOT NULL REFERENCES quizzes(id) ON DELETE CASCADE,
+    source_session_id INTEGER UNIQUE REFERENCES quiz_sessions(id) ON DELETE SET NULL,
+    created_by INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    question_ids INTEGER[] NOT NULL,
+    shuffle_seed INTEGER NOT NULL,
+    study_mode TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE quiz_sessions ADD COLUMN challenge_id INTEGER REFERENCES challenges(id) ON DELETE SET NULL;
+CREATE INDEX idx_quiz_sessions_challenge ON quiz_sessions (challenge_id);
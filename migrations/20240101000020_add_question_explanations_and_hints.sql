@@ -0,0 +1,6 @@
+-- Question-level explanation, reference links and progressive hints; hint use is tracked per answer slot
+ALTER TABLE questions ADD COLUMN explanation TEXT;
+ALTER TABLE questions ADD COLUMN reference_links TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE questions ADD COLUMN hints TEXT[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE session_questions ADD COLUMN hints_used INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Templated questions: variable ranges per question, values drawn per session question
+ALTER TABLE questions ADD COLUMN template_variables TEXT;
+ALTER TABLE session_questions ADD COLUMN template_values TEXT;
@@ -0,0 +1,24 @@
+-- Cached answer distribution: first-attempt responses per question and picks per option
+CREATE TABLE question_response_counts (
+    question_id INTEGER PRIMARY KEY REFERENCES questions(id) ON DELETE CASCADE,
+    responses INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE option_pick_counts (
+    option_id INTEGER PRIMARY KEY REFERENCES options(id) ON DELETE CASCADE,
+    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+    picks INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX idx_option_pick_counts_question ON option_pick_counts (question_id);
+
+INSERT INTO question_response_counts (question_id, responses)
+SELECT question_id, COUNT(DISTINCT session_id)
+FROM user_answers
+WHERE attempt = 1 AND option_id IS NOT NULL
+GROUP BY question_id;
+
+INSERT INTO option_pick_counts (option_id, question_id, picks)
+SELECT option_id, question_id, COUNT(*)
+FROM user_answers
+WHERE attempt = 1 AND option_id IS NOT NULL
+GROUP BY option_id, question_id;
@@ -0,0 +1,4 @@
+-- Daily study goals (questions answered or minutes studied per day); activity per day
+-- comes from session_questions.answered_at
+ALTER TABLE users ADD COLUMN daily_goal_kind TEXT CHECK (daily_goal_kind IN ('questions', 'minutes'));
+ALTER TABLE users ADD COLUMN daily_goal_target INTEGER CHECK (daily_goal_target > 0);
@@ -0,0 +1,6 @@
+-- Per-quiz study plan of a learner: target exam date, pass threshold (percent), and the
+-- daily question count recommended when the plan was set, to track progress against it
+ALTER TABLE user_quizzes ADD COLUMN exam_date DATE;
+ALTER TABLE user_quizzes ADD COLUMN pass_threshold INTEGER CHECK (pass_threshold BETWEEN 1 AND 100);
+ALTER TABLE user_quizzes ADD COLUMN plan_daily_target INTEGER CHECK (plan_daily_target > 0);
+ALTER TABLE user_quizzes ADD COLUMN plan_started_on DATE;
@@ -0,0 +1,4 @@
+-- Opt-in weekly progress digest email, written in the language the user opted in with
+ALTER TABLE users ADD COLUMN weekly_digest BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN digest_locale TEXT NOT NULL DEFAULT 'en';
+ALTER TABLE users ADD COLUMN digest_sent_at TIMESTAMPTZ;
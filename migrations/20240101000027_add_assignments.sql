@@ -0,0 +1,22 @@
+-- Assignments: a quiz to finish by a due date with a minimum size and target score,
+-- for every member of a group or for one learner
+CREATE TABLE assignments (
+    id SERIAL PRIMARY KEY,
+    public_id TEXT NOT NULL UNIQUE,
+    quiz_id INTEGER NOT NULL REFERENCES quizzes(id) ON DELETE CASCADE,
+    created_by INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    group_id INTEGER REFERENCES groups(id) ON DELETE CASCADE,
+    assignee_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+    due_date DATE NOT NULL,
+    question_count INTEGER NOT NULL CHECK (question_count > 0),
+    selection_mode TEXT NOT NULL,
+    target_score INTEGER NOT NULL CHECK (target_score BETWEEN 1 AND 100),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((group_id IS NULL) <> (assignee_id IS NULL))
+);
+CREATE INDEX idx_assignments_created_by ON assignments (created_by);
+CREATE INDEX idx_assignments_group ON assignments (group_id);
+CREATE INDEX idx_assignments_assignee ON assignments (assignee_id);
+
+ALTER TABLE quiz_sessions ADD COLUMN assignment_id INTEGER REFERENCES assignments(id) ON DELETE SET NULL;
+CREATE INDEX idx_quiz_sessions_assignment ON quiz_sessions (assignment_id);
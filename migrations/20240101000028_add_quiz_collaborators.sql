@@ -0,0 +1,33 @@
+-- Quiz collaborators: editors rename a quiz and change its session sizes, sharing managers
+-- share and unshare it
+CREATE TABLE quiz_collaborators (
+    quiz_id INTEGER NOT NULL REFERENCES quizzes(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role TEXT NOT NULL CHECK (role IN ('editor', 'sharing')),
+    added_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (quiz_id, user_id)
+);
+CREATE INDEX idx_quiz_collaborators_user ON quiz_collaborators (user_id);
+
+-- Pending invitations by email; the invitee becomes a collaborator only by accepting
+CREATE TABLE quiz_invitations (
+    id SERIAL PRIMARY KEY,
+    quiz_id INTEGER NOT NULL REFERENCES quizzes(id) ON DELETE CASCADE,
+    email TEXT NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('editor', 'sharing')),
+    invited_by INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (quiz_id, email)
+);
+CREATE INDEX idx_quiz_invitations_email ON quiz_invitations (email);
+
+-- Who changed what on a quiz
+CREATE TABLE quiz_changes (
+    id SERIAL PRIMARY KEY,
+    quiz_id INTEGER NOT NULL REFERENCES quizzes(id) ON DELETE CASCADE,
+    user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    action TEXT NOT NULL,
+    detail TEXT NOT NULL DEFAULT '',
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX idx_quiz_changes_quiz ON quiz_changes (quiz_id, changed_at DESC);
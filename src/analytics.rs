@@ -0,0 +1,1021 @@
+//! Statistics computed from answers: item analysis of a quiz for its owner, and pace
+//! analytics, session comparison, exam readiness and study plans of one learner.
+//!
+//! Item analysis follows classical test theory: difficulty is the share of correct first
+//! attempts, and discrimination compares how the top and bottom scorers of the quiz did
+//! on each question. A question the strongest learners get wrong more often than the
+//! weakest ones, or whose most popular answer is a wrong option, is likely miskeyed.
+//!
+//! Readiness estimates the share of the whole quiz a learner would answer correctly
+//! today. Answers lose weight as they age, each category's accuracy is shrunk toward
+//! the learner's overall accuracy until it has enough answers, and questions never seen
+//! are expected to go halfway between that estimate and a coin flip.
+//!
+//! Group progress lays out the same first-attempt counts for an instructor, one table
+//! per assigned quiz with a row per member and a column per category.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::db::{
+    AssignmentStatus, CategoryComparison, CategoryPace, CategorySize, ComparedSession,
+    DistractorQuestion, FlippedQuestion, GroupMember, GroupProgressRow, GroupQuiz,
+    GroupQuizProgress, ItemAnalysis, ItemResponse, MemberProgress, PaceReport, PaceResponse,
+    ProgressCell, Readiness, ReadinessResponse, SessionCategoryStats, SessionComparison,
+    SessionOutcome, SessionPace, SlowQuestion, StudyPlan, StudyPlanInputs, WeakCategory,
+};
+
+/// Share of learners at each end of the score ranking compared for discrimination.
+const GROUP_SHARE: f64 = 0.27;
+
+/// Learners needed before the top and bottom scorers are compared.
+const MIN_LEARNERS: usize = 4;
+
+/// First attempts a question needs before it can be flagged as miskeyed.
+const MIN_RESPONSES: i32 = 5;
+
+/// Discrimination below this means top scorers clearly miss the question more often.
+const MISKEY_DISCRIMINATION: f64 = -0.1;
+
+/// Questions listed in a learner's slowest questions.
+const SLOWEST_QUESTIONS: usize = 10;
+
+/// Sessions needed before a pace trend is fitted.
+const MIN_TREND_SESSIONS: usize = 3;
+
+/// Days after which an answer counts half as much toward readiness.
+const READINESS_HALF_LIFE_DAYS: f64 = 14.0;
+
+/// Weight of the prior when shrinking an accuracy, in answers.
+const READINESS_PRIOR_WEIGHT: f64 = 3.0;
+
+/// Expected accuracy with nothing known about the learner.
+const READINESS_PRIOR: f64 = 0.5;
+
+/// Extra half-width of the confidence band when nothing of the quiz has been seen.
+const READINESS_UNSEEN_SPREAD: f64 = 0.15;
+
+/// Answers counted in the recent accuracy.
+const READINESS_RECENT_ANSWERS: usize = 50;
+
+/// Categories listed as holding the estimate back.
+const READINESS_WEAK_CATEGORIES: usize = 3;
+
+/// Fewest questions a study plan recommends per day, so practice keeps going once
+/// everything has been seen.
+const MIN_DAILY_QUESTIONS: i32 = 10;
+
+/// Share of the planned answers below which a learner counts as falling behind.
+const PLAN_BEHIND_SHARE: f64 = 0.8;
+
+#[derive(Default)]
+struct Tally {
+    correct: i32,
+    total: i32,
+}
+
+impl Tally {
+    fn add(&mut self, is_correct: bool) {
+        self.total += 1;
+        if is_correct {
+            self.correct += 1;
+        }
+    }
+
+    fn rate(&self) -> Option<f64> {
+        (self.total > 0).then(|| f64::from(self.correct) / f64::from(self.total))
+    }
+}
+
+/// Top and bottom scorers of the quiz, by accuracy over all their first attempts.
+fn score_groups(responses: &[ItemResponse]) -> Option<(HashSet<i32>, HashSet<i32>)> {
+    let mut learners: HashMap<i32, Tally> = HashMap::new();
+    for r in responses {
+        learners.entry(r.user_id).or_default().add(r.is_correct);
+    }
+    if learners.len() < MIN_LEARNERS {
+        return None;
+    }
+
+    let mut ranked: Vec<(i32, f64)> = learners
+        .iter()
+        .map(|(user_id, tally)| (*user_id, tally.rate().unwrap_or(0.0)))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let size = ((ranked.len() as f64 * GROUP_SHARE).round() as usize).max(1);
+    let upper = ranked.iter().take(size).map(|(id, _)| *id).collect();
+    let lower = ranked.iter().rev().take(size).map(|(id, _)| *id).collect();
+    Some((upper, lower))
+}
+
+/// Median of some durations, `None` when there are none.
+pub fn median(mut values: Vec<i64>) -> Option<i64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    })
+}
+
+/// Item statistics for every question, likely miskeyed and poorly discriminating
+/// questions first.
+///
+/// `questions` carries each question's options with their first-attempt pick counts;
+/// `responses` holds every learner's first attempts.
+pub fn item_analysis(
+    questions: Vec<DistractorQuestion>,
+    responses: &[ItemResponse],
+) -> Vec<ItemAnalysis> {
+    let groups = score_groups(responses);
+
+    let mut items: Vec<ItemAnalysis> = questions
+        .into_iter()
+        .map(|q| {
+            let mut overall = Tally::default();
+            let mut upper = Tally::default();
+            let mut lower = Tally::default();
+            let mut times = Vec::new();
+            for r in responses.iter().filter(|r| r.question_id == q.question_id) {
+                overall.add(r.is_correct);
+                if r.active_ms > 0 {
+                    times.push(i64::from(r.active_ms));
+                }
+                if let Some((top, bottom)) = &groups {
+                    if top.contains(&r.user_id) {
+                        upper.add(r.is_correct);
+                    }
+                    if bottom.contains(&r.user_id) {
+                        lower.add(r.is_correct);
+                    }
+                }
+            }
+
+            let discrimination = upper.rate().zip(lower.rate()).map(|(u, l)| u - l);
+
+            let key_picks = q
+                .options
+                .iter()
+                .filter(|o| o.is_answer)
+                .map(|o| o.picks)
+                .max()
+                .unwrap_or(0);
+            let top_distractor = q
+                .options
+                .iter()
+                .filter(|o| !o.is_answer && o.picks > 0)
+                .max_by_key(|o| o.picks);
+            let distractor_beats_key = top_distractor.is_some_and(|o| o.picks > key_picks);
+
+            let likely_miskeyed = overall.total >= MIN_RESPONSES
+                && (discrimination.is_some_and(|d| d < MISKEY_DISCRIMINATION)
+                    || (distractor_beats_key && discrimination.is_none_or(|d| d <= 0.0)));
+
+            ItemAnalysis {
+                question_id: q.question_id,
+                question: q.question,
+                category: q.category,
+                responses: overall.total,
+                difficulty: overall.rate(),
+                discrimination,
+                median_ms: median(times),
+                top_distractor: top_distractor.filter(|_| q.responses > 0).map(|o| {
+                    (
+                        o.option.clone(),
+                        f64::from(o.picks) / f64::from(q.responses),
+                    )
+                }),
+                likely_miskeyed,
+            }
+        })
+        .collect();
+
+    items.sort_by(|a, b| {
+        b.likely_miskeyed.cmp(&a.likely_miskeyed).then(
+            a.discrimination
+                .unwrap_or(f64::INFINITY)
+                .total_cmp(&b.discrimination.unwrap_or(f64::INFINITY)),
+        )
+    });
+    items
+}
+
+/// Least-squares slope of values taken at evenly spaced steps.
+fn slope(values: &[i64]) -> Option<f64> {
+    if values.len() < MIN_TREND_SESSIONS {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<i64>() as f64 / n;
+    let (mut num, mut den) = (0.0, 0.0);
+    for (x, y) in values.iter().enumerate() {
+        let dx = x as f64 - mean_x;
+        num += dx * (*y as f64 - mean_y);
+        den += dx * dx;
+    }
+    Some(num / den)
+}
+
+/// Where a learner's time goes: median time per session with its trend, the slowest
+/// questions, and time on correct against incorrect answers per category.
+///
+/// `responses` must be ordered by session, oldest first.
+pub fn pace_report(responses: &[PaceResponse]) -> PaceReport {
+    let mut sessions: Vec<(SessionPace, Vec<i64>)> = Vec::new();
+    for r in responses {
+        if sessions.last().map(|(s, _)| s.session_id) != Some(r.session_id) {
+            sessions.push((
+                SessionPace {
+                    session_id: r.session_id,
+                    session_name: r.session_name.clone(),
+                    answered: 0,
+                    median_ms: 0,
+                },
+                Vec::new(),
+            ));
+        }
+        if let Some((session, times)) = sessions.last_mut() {
+            session.answered += 1;
+            times.push(i64::from(r.active_ms));
+        }
+    }
+    let sessions: Vec<SessionPace> = sessions
+        .into_iter()
+        .map(|(mut session, times)| {
+            session.median_ms = median(times).unwrap_or(0);
+            session
+        })
+        .collect();
+    let medians: Vec<i64> = sessions.iter().map(|s| s.median_ms).collect();
+
+    let mut questions: HashMap<i32, (SlowQuestion, Vec<i64>)> = HashMap::new();
+    for r in responses {
+        let (question, times) = questions.entry(r.question_id).or_insert_with(|| {
+            (
+                SlowQuestion {
+                    question_id: r.question_id,
+                    question: r.question.clone(),
+                    category: r.category.clone(),
+                    attempts: 0,
+                    correct: 0,
+                    median_ms: 0,
+                },
+                Vec::new(),
+            )
+        });
+        question.attempts += 1;
+        question.correct += i32::from(r.is_correct);
+        times.push(i64::from(r.active_ms));
+    }
+    let mut slowest: Vec<SlowQuestion> = questions
+        .into_values()
+        .map(|(mut question, times)| {
+            question.median_ms = median(times).unwrap_or(0);
+            question
+        })
+        .collect();
+    slowest.sort_by(|a, b| {
+        b.median_ms
+            .cmp(&a.median_ms)
+            .then(a.question_id.cmp(&b.question_id))
+    });
+    slowest.truncate(SLOWEST_QUESTIONS);
+
+    let mut by_category: BTreeMap<&str, (Vec<i64>, Vec<i64>)> = BTreeMap::new();
+    for r in responses {
+        if let Some(category) = &r.category {
+            let (correct, incorrect) = by_category.entry(category).or_default();
+            if r.is_correct {
+                correct.push(i64::from(r.active_ms));
+            } else {
+                incorrect.push(i64::from(r.active_ms));
+            }
+        }
+    }
+    let categories = by_category
+        .into_iter()
+        .map(|(category, (correct, incorrect))| CategoryPace {
+            category: category.to_string(),
+            correct: correct.len() as i32,
+            incorrect: incorrect.len() as i32,
+            correct_median_ms: median(correct),
+            incorrect_median_ms: median(incorrect),
+        })
+        .collect();
+
+    PaceReport {
+        trend_ms_per_session: slope(&medians),
+        sessions,
+        slowest,
+        categories,
+    }
+}
+
+/// Current and longest run of consecutive active days, from the days (counted back from
+/// today) with any activity, in ascending order. The current streak is still alive when
+/// nothing has been answered yet today.
+pub fn streaks(active_days_ago: &[i32]) -> (i32, i32) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous = None;
+    for &day in active_days_ago {
+        run = if previous == Some(day - 1) {
+            run + 1
+        } else {
+            1
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    let start = match active_days_ago.first() {
+        Some(&day) if day <= 1 => day,
+        _ => return (0, longest),
+    };
+    let current = active_days_ago
+        .iter()
+        .zip(start..)
+        .take_while(|(day, expected)| **day == *expected)
+        .count() as i32;
+    (current, longest)
+}
+
+/// Lines up the category accuracy and first-attempt outcomes of `sessions`, which must be
+/// ordered oldest first. Questions that regressed are listed before the ones that got
+/// fixed.
+pub fn compare_sessions(
+    sessions: Vec<ComparedSession>,
+    category_stats: &[SessionCategoryStats],
+    outcomes: &[SessionOutcome],
+) -> SessionComparison {
+    let position: HashMap<i32, usize> = sessions
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.id, i))
+        .collect();
+
+    let mut categories: BTreeMap<&str, Vec<Option<f64>>> = BTreeMap::new();
+    for stats in category_stats {
+        if let Some(&i) = position.get(&stats.session_id) {
+            if stats.total > 0 {
+                categories
+                    .entry(&stats.category)
+                    .or_insert_with(|| vec![None; sessions.len()])[i] =
+                    Some(stats.correct as f64 / stats.total as f64);
+            }
+        }
+    }
+
+    let mut questions: BTreeMap<i32, FlippedQuestion> = BTreeMap::new();
+    for outcome in outcomes {
+        if let Some(&i) = position.get(&outcome.session_id) {
+            questions
+                .entry(outcome.question_id)
+                .or_insert_with(|| FlippedQuestion {
+                    question_id: outcome.question_id,
+                    question: outcome.question.clone(),
+                    category: outcome.category.clone(),
+                    outcomes: vec![None; sessions.len()],
+                    was_correct: false,
+                    now_correct: false,
+                })
+                .outcomes[i] = Some(outcome.is_correct);
+        }
+    }
+    let mut flipped: Vec<FlippedQuestion> = questions
+        .into_values()
+        .filter_map(|mut question| {
+            let asked: Vec<bool> = question.outcomes.iter().flatten().copied().collect();
+            if !asked.iter().any(|&c| c) || asked.iter().all(|&c| c) {
+                return None;
+            }
+            question.was_correct = asked[0];
+            question.now_correct = asked[asked.len() - 1];
+            Some(question)
+        })
+        .collect();
+    flipped.sort_by_key(|q| q.now_correct);
+
+    SessionComparison {
+        sessions,
+        categories: categories
+            .into_iter()
+            .map(|(category, accuracy)| CategoryComparison {
+                category: category.to_string(),
+                accuracy,
+            })
+            .collect(),
+        flipped,
+    }
+}
+
+/// Exam readiness of one learner from their first attempts and the size of each category
+/// of the quiz, against a pass mark between 0 and 1. `None` until the learner has
+/// answered something.
+pub fn readiness(
+    sizes: &[CategorySize],
+    responses: &[ReadinessResponse],
+    pass_mark: f64,
+) -> Option<Readiness> {
+    let total_questions: i64 = sizes.iter().map(|s| s.questions).sum();
+    if responses.is_empty() || total_questions == 0 {
+        return None;
+    }
+
+    let weight =
+        |r: &ReadinessResponse| 0.5_f64.powf(r.days_ago.max(0.0) / READINESS_HALF_LIFE_DAYS);
+    let shrunk = |correct: f64, total: f64, prior: f64| {
+        (correct + prior * READINESS_PRIOR_WEIGHT) / (total + READINESS_PRIOR_WEIGHT)
+    };
+
+    let mut weighted_correct = 0.0;
+    let mut weighted_total = 0.0;
+    let mut by_category: HashMap<Option<&str>, (f64, f64, HashSet<i32>)> = HashMap::new();
+    for r in responses {
+        let w = weight(r);
+        let correct = if r.is_correct { w } else { 0.0 };
+        weighted_correct += correct;
+        weighted_total += w;
+        let (c, t, seen) = by_category.entry(r.category.as_deref()).or_default();
+        *c += correct;
+        *t += w;
+        seen.insert(r.question_id);
+    }
+    let overall = shrunk(weighted_correct, weighted_total, READINESS_PRIOR);
+
+    let mut score = 0.0;
+    let mut asked = 0;
+    let mut weak = Vec::new();
+    for size in sizes.iter().filter(|s| s.questions > 0) {
+        let (estimate, coverage) = match by_category.get(&size.category.as_deref()) {
+            Some((c, t, seen)) => {
+                let seen = (seen.len() as i64).min(size.questions);
+                asked += seen;
+                let accuracy = shrunk(*c, *t, overall);
+                let coverage = seen as f64 / size.questions as f64;
+                let unseen = (accuracy + READINESS_PRIOR) / 2.0;
+                (coverage * accuracy + (1.0 - coverage) * unseen, coverage)
+            }
+            None => ((overall + READINESS_PRIOR) / 2.0, 0.0),
+        };
+        let share = size.questions as f64 / total_questions as f64;
+        score += share * estimate;
+        if let Some(category) = &size.category {
+            if estimate < pass_mark {
+                weak.push((
+                    share * (1.0 - estimate),
+                    WeakCategory {
+                        category: category.clone(),
+                        estimate,
+                        coverage,
+                    },
+                ));
+            }
+        }
+    }
+    weak.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let holding_back = weak
+        .into_iter()
+        .take(READINESS_WEAK_CATEGORIES)
+        .map(|(_, category)| category)
+        .collect();
+
+    let coverage = asked as f64 / total_questions as f64;
+    let standard_error = (score * (1.0 - score) / (weighted_total + READINESS_PRIOR_WEIGHT)).sqrt();
+    let half_width = 1.96 * standard_error + (1.0 - coverage) * READINESS_UNSEEN_SPREAD;
+
+    let mut recent: Vec<&ReadinessResponse> = responses.iter().collect();
+    recent.sort_by(|a, b| a.days_ago.total_cmp(&b.days_ago));
+    recent.truncate(READINESS_RECENT_ANSWERS);
+    let recent_correct = recent.iter().filter(|r| r.is_correct).count();
+
+    Some(Readiness {
+        score,
+        pass_mark,
+        low: (score - half_width).max(0.0),
+        high: (score + half_width).min(1.0),
+        coverage,
+        recent_accuracy: recent_correct as f64 / recent.len() as f64,
+        days_since_practice: recent[0].days_ago.max(0.0).floor() as i32,
+        holding_back,
+    })
+}
+
+/// Daily recommendation for a study plan: unseen questions and questions last answered
+/// incorrectly spread over the days left, topped up with mixed practice on the weak
+/// categories of `readiness`.
+pub fn study_plan(inputs: StudyPlanInputs, readiness: Option<&Readiness>) -> StudyPlan {
+    // Exam day itself still counts as one day to study
+    let study_days = i64::from(inputs.days_left.max(1));
+    let per_day = |count: i64| ((count + study_days - 1) / study_days) as i32;
+    let unseen_per_day = per_day(inputs.unseen);
+    let review_per_day = per_day(inputs.to_review);
+    let daily_target = (unseen_per_day + review_per_day).max(MIN_DAILY_QUESTIONS);
+
+    let expected_by_now = i64::from(inputs.planned_daily) * i64::from(inputs.days_elapsed.max(0));
+    let behind = expected_by_now > 0
+        && (inputs.answered_since_start as f64) < expected_by_now as f64 * PLAN_BEHIND_SHARE;
+
+    StudyPlan {
+        exam_date: inputs.exam_date,
+        days_left: inputs.days_left,
+        pass_threshold: inputs.pass_threshold,
+        unseen_per_day,
+        review_per_day,
+        mixed_per_day: daily_target - unseen_per_day - review_per_day,
+        daily_target,
+        answered_today: inputs.answered_today,
+        expected_by_now,
+        answered_since_start: inputs.answered_since_start,
+        behind,
+        focus: readiness
+            .map(|r| r.holding_back.iter().map(|c| c.category.clone()).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Progress tables of a group, one per quiz in the order of `quizzes`, with members in
+/// the order of `members` and categories in the order of `rows`.
+pub fn group_progress(
+    quizzes: Vec<GroupQuiz>,
+    members: &[GroupMember],
+    rows: &[GroupProgressRow],
+) -> Vec<GroupQuizProgress> {
+    quizzes
+        .into_iter()
+        .map(|quiz| {
+            let quiz_rows: Vec<&GroupProgressRow> =
+                rows.iter().filter(|r| r.quiz_id == quiz.quiz_id).collect();
+            let mut categories: Vec<Option<String>> = Vec::new();
+            for row in &quiz_rows {
+                if !categories.contains(&row.category) {
+                    categories.push(row.category.clone());
+                }
+            }
+
+            let members = members
+                .iter()
+                .map(|member| {
+                    let mut cells = vec![ProgressCell::default(); categories.len()];
+                    let mut total = ProgressCell::default();
+                    let mut active_ms = 0;
+                    for row in quiz_rows.iter().filter(|r| r.user_id == member.user_id) {
+                        let cell = ProgressCell {
+                            questions: row.questions,
+                            seen: row.seen,
+                            answered: row.answered,
+                            correct: row.correct,
+                        };
+                        if let Some(i) = categories.iter().position(|c| *c == row.category) {
+                            cells[i] = cell;
+                        }
+                        total.questions += cell.questions;
+                        total.seen += cell.seen;
+                        total.answered += cell.answered;
+                        total.correct += cell.correct;
+                        active_ms += row.active_ms;
+                    }
+                    MemberProgress {
+                        user_id: member.user_id,
+                        display_name: member.display_name.clone(),
+                        categories: cells,
+                        total,
+                        active_ms,
+                    }
+                })
+                .collect();
+
+            GroupQuizProgress {
+                quiz,
+                categories,
+                members,
+            }
+        })
+        .collect()
+}
+
+/// Where a learner stands on an assignment due on `due_date` (ISO), `days_left` days
+/// from today, given the day they completed it if they did.
+pub fn assignment_status(
+    due_date: &str,
+    days_left: i32,
+    completed_on: Option<&str>,
+) -> AssignmentStatus {
+    match completed_on {
+        // ISO dates order like the days they name
+        Some(day) => AssignmentStatus::Completed {
+            late: day > due_date,
+        },
+        None if days_left < 0 => AssignmentStatus::Overdue,
+        None => AssignmentStatus::Pending,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::DistractorOption;
+
+    fn question(question_id: i32, picks: &[(bool, i32)]) -> DistractorQuestion {
+        DistractorQuestion {
+            question_id,
+            question: format!("Q{question_id}"),
+            category: None,
+            responses: picks.iter().map(|(_, p)| p).sum(),
+            options: picks
+                .iter()
+                .enumerate()
+                .map(|(i, (is_answer, picks))| DistractorOption {
+                    option: format!("O{i}"),
+                    is_answer: *is_answer,
+                    picks: *picks,
+                })
+                .collect(),
+        }
+    }
+
+    fn response(user_id: i32, question_id: i32, is_correct: bool) -> ItemResponse {
+        ItemResponse {
+            user_id,
+            question_id,
+            is_correct,
+            active_ms: 1000 * user_id,
+        }
+    }
+
+    #[test]
+    fn median_handles_odd_even_and_empty() {
+        assert_eq!(median(vec![]), None);
+        assert_eq!(median(vec![5, 1, 3]), Some(3));
+        assert_eq!(median(vec![4, 1, 3, 2]), Some(2));
+    }
+
+    #[test]
+    fn discrimination_needs_enough_learners() {
+        let responses = vec![response(1, 1, true), response(2, 1, false)];
+        let items = item_analysis(vec![question(1, &[(true, 1), (false, 1)])], &responses);
+        assert_eq!(items[0].difficulty, Some(0.5));
+        assert_eq!(items[0].discrimination, None);
+        assert!(!items[0].likely_miskeyed);
+    }
+
+    #[test]
+    fn question_missed_by_top_scorers_is_flagged() {
+        // Questions 1 and 3 follow overall ability; question 2 is only right for the weakest learner.
+        let mut responses = Vec::new();
+        for user in 1..=5 {
+            responses.push(response(user, 1, user <= 4));
+            responses.push(response(user, 3, user <= 3));
+            responses.push(response(user, 2, user == 5));
+        }
+        let items = item_analysis(
+            vec![
+                question(1, &[(true, 4), (false, 1)]),
+                question(2, &[(true, 1), (false, 4)]),
+                question(3, &[(true, 3), (false, 2)]),
+            ],
+            &responses,
+        );
+
+        assert_eq!(items[0].question_id, 2);
+        assert!(items[0].likely_miskeyed);
+        assert!(items[0].discrimination.is_some_and(|d| d < 0.0));
+        assert_eq!(items[0].median_ms, Some(3000));
+        let (text, share) = items[0].top_distractor.clone().expect("top distractor");
+        assert_eq!(text, "O1");
+        assert!((share - 0.8).abs() < 1e-9);
+
+        let item = items
+            .iter()
+            .find(|i| i.question_id == 1)
+            .expect("question 1");
+        assert!(!item.likely_miskeyed);
+        assert!(item.discrimination.is_some_and(|d| d > 0.0));
+    }
+
+    fn timed(
+        session_id: i32,
+        question_id: i32,
+        category: &str,
+        is_correct: bool,
+        secs: i32,
+    ) -> PaceResponse {
+        PaceResponse {
+            session_id,
+            session_name: format!("s{session_id}"),
+            question_id,
+            question: format!("Q{question_id}"),
+            category: Some(category.to_string()),
+            is_correct,
+            active_ms: secs * 1000,
+        }
+    }
+
+    #[test]
+    fn pace_report_tracks_sessions_questions_and_categories() {
+        let responses = vec![
+            timed(1, 1, "Math", true, 30),
+            timed(1, 2, "Math", false, 50),
+            timed(1, 3, "Art", true, 10),
+            timed(2, 1, "Math", true, 20),
+            timed(2, 2, "Math", false, 40),
+            timed(3, 1, "Math", true, 10),
+        ];
+        let report = pace_report(&responses);
+
+        let medians: Vec<i64> = report.sessions.iter().map(|s| s.median_ms).collect();
+        assert_eq!(medians, vec![30_000, 30_000, 10_000]);
+        assert_eq!(report.sessions[0].answered, 3);
+        assert!(report.trend_ms_per_session.is_some_and(|t| t < 0.0));
+
+        assert_eq!(report.slowest[0].question_id, 2);
+        assert_eq!(report.slowest[0].median_ms, 45_000);
+        assert_eq!(report.slowest[0].correct, 0);
+
+        let math = report
+            .categories
+            .iter()
+            .find(|c| c.category == "Math")
+            .expect("math category");
+        assert_eq!((math.correct, math.incorrect), (3, 2));
+        assert_eq!(math.correct_median_ms, Some(20_000));
+        assert_eq!(math.incorrect_median_ms, Some(45_000));
+    }
+
+    #[test]
+    fn pace_trend_needs_a_few_sessions() {
+        let responses = vec![timed(1, 1, "Math", true, 30), timed(2, 1, "Math", true, 20)];
+        assert_eq!(pace_report(&responses).trend_ms_per_session, None);
+    }
+
+    #[test]
+    fn streaks_count_consecutive_days() {
+        assert_eq!(streaks(&[]), (0, 0));
+        assert_eq!(streaks(&[0, 1, 2, 5, 6, 7, 8]), (3, 4));
+        // Nothing yet today keeps yesterday's streak alive
+        assert_eq!(streaks(&[1, 2]), (2, 2));
+        assert_eq!(streaks(&[2, 3, 4]), (0, 3));
+    }
+
+    fn answered(
+        question_id: i32,
+        category: &str,
+        is_correct: bool,
+        days_ago: f64,
+    ) -> ReadinessResponse {
+        ReadinessResponse {
+            question_id,
+            category: Some(category.to_string()),
+            is_correct,
+            days_ago,
+        }
+    }
+
+    fn sizes(categories: &[(&str, i64)]) -> Vec<CategorySize> {
+        categories
+            .iter()
+            .map(|(category, questions)| CategorySize {
+                category: Some(category.to_string()),
+                questions: *questions,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn readiness_needs_answers() {
+        assert!(readiness(&sizes(&[("Math", 10)]), &[], 0.7).is_none());
+    }
+
+    #[test]
+    fn readiness_rewards_coverage_and_flags_weak_categories() {
+        let sizes = sizes(&[("Math", 10), ("Art", 10)]);
+        let mut full = Vec::new();
+        let mut half = Vec::new();
+        for id in 0..10 {
+            full.push(answered(id, "Math", true, 0.0));
+            full.push(answered(100 + id, "Art", id < 3, 0.0));
+            if id < 5 {
+                half.push(answered(id, "Math", true, 0.0));
+                half.push(answered(100 + id, "Art", id < 3, 0.0));
+            }
+        }
+
+        let covered = readiness(&sizes, &full, 0.7).expect("answers given");
+        let partial = readiness(&sizes, &half, 0.7).expect("answers given");
+        assert!((covered.coverage - 1.0).abs() < 1e-9);
+        assert!((partial.coverage - 0.5).abs() < 1e-9);
+        assert!(covered.high - covered.low < partial.high - partial.low);
+        assert!(covered.low <= covered.score && covered.score <= covered.high);
+
+        let names: Vec<&str> = covered
+            .holding_back
+            .iter()
+            .map(|c| c.category.as_str())
+            .collect();
+        assert_eq!(names, vec!["Art"]);
+        assert_eq!(covered.days_since_practice, 0);
+    }
+
+    #[test]
+    fn readiness_fades_old_answers() {
+        let sizes = sizes(&[("Math", 5)]);
+        let fresh: Vec<_> = (0..5).map(|id| answered(id, "Math", true, 0.0)).collect();
+        let stale: Vec<_> = (0..5).map(|id| answered(id, "Math", true, 60.0)).collect();
+
+        let fresh = readiness(&sizes, &fresh, 0.7).expect("answers given");
+        let stale = readiness(&sizes, &stale, 0.7).expect("answers given");
+        assert!(stale.score < fresh.score);
+        assert!(stale.high - stale.low > fresh.high - fresh.low);
+        assert_eq!(stale.days_since_practice, 60);
+        assert!((stale.recent_accuracy - 1.0).abs() < 1e-9);
+    }
+
+    fn compared(id: i32) -> ComparedSession {
+        ComparedSession {
+            id,
+            name: format!("s{id}"),
+            score: 0,
+            answered_questions: 0,
+            total_questions: 0,
+            is_complete: true,
+            active_ms: 0,
+        }
+    }
+
+    fn outcome(session_id: i32, question_id: i32, is_correct: bool) -> SessionOutcome {
+        SessionOutcome {
+            session_id,
+            question_id,
+            question: format!("Q{question_id}"),
+            category: None,
+            is_correct,
+        }
+    }
+
+    #[test]
+    fn compare_sessions_lists_regressions_before_fixes() {
+        let outcomes = vec![
+            outcome(1, 1, false),
+            outcome(2, 1, true),
+            outcome(1, 2, true),
+            outcome(2, 2, false),
+            outcome(1, 3, true),
+            outcome(2, 3, true),
+            // Only asked once, so it cannot have flipped
+            outcome(2, 4, false),
+        ];
+        let stats = vec![SessionCategoryStats {
+            session_id: 2,
+            category: "Math".to_string(),
+            total: 4,
+            correct: 3,
+        }];
+        let comparison = compare_sessions(vec![compared(1), compared(2)], &stats, &outcomes);
+
+        let ids: Vec<i32> = comparison.flipped.iter().map(|q| q.question_id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert!(comparison.flipped[0].was_correct && !comparison.flipped[0].now_correct);
+        assert_eq!(
+            comparison.flipped[1].outcomes,
+            vec![Some(false), Some(true)]
+        );
+
+        assert_eq!(comparison.categories[0].accuracy, vec![None, Some(0.75)]);
+    }
+
+    fn plan_inputs(days_left: i32, unseen: i64, to_review: i64) -> StudyPlanInputs {
+        StudyPlanInputs {
+            exam_date: "2030-01-01".to_string(),
+            days_left,
+            pass_threshold: 70,
+            planned_daily: 0,
+            days_elapsed: 0,
+            unseen,
+            to_review,
+            answered_today: 0,
+            answered_since_start: 0,
+        }
+    }
+
+    #[test]
+    fn study_plan_spreads_work_over_days_left() {
+        let plan = study_plan(plan_inputs(10, 95, 21), None);
+        assert_eq!((plan.unseen_per_day, plan.review_per_day), (10, 3));
+        assert_eq!((plan.mixed_per_day, plan.daily_target), (0, 13));
+
+        // Everything seen: keep practicing a minimum every day
+        let plan = study_plan(plan_inputs(10, 0, 4), None);
+        assert_eq!((plan.review_per_day, plan.mixed_per_day), (1, 9));
+
+        // On exam day the rest is due today
+        let plan = study_plan(plan_inputs(0, 30, 0), None);
+        assert_eq!(plan.unseen_per_day, 30);
+    }
+
+    #[test]
+    fn study_plan_flags_falling_behind() {
+        let mut inputs = plan_inputs(10, 50, 0);
+        inputs.planned_daily = 10;
+        inputs.days_elapsed = 3;
+        inputs.answered_since_start = 25;
+        let plan = study_plan(inputs, None);
+        assert_eq!(plan.expected_by_now, 30);
+        assert!(!plan.behind);
+
+        let mut inputs = plan_inputs(10, 50, 0);
+        inputs.planned_daily = 10;
+        inputs.days_elapsed = 3;
+        inputs.answered_since_start = 20;
+        assert!(study_plan(inputs, None).behind);
+    }
+
+    fn progress_row(
+        user_id: i32,
+        quiz_id: i32,
+        category: Option<&str>,
+        seen: i64,
+        correct: i64,
+    ) -> GroupProgressRow {
+        GroupProgressRow {
+            user_id,
+            quiz_id,
+            category: category.map(str::to_string),
+            questions: 4,
+            seen,
+            answered: seen,
+            correct,
+            active_ms: seen * 1000,
+        }
+    }
+
+    #[test]
+    fn group_progress_builds_a_table_per_quiz() {
+        let quiz = |quiz_id| GroupQuiz {
+            quiz_id,
+            public_id: format!("Q{quiz_id}"),
+            name: format!("Quiz {quiz_id}"),
+            question_count: 8,
+        };
+        let members = [
+            GroupMember {
+                user_id: 1,
+                display_name: "Alice".to_string(),
+            },
+            GroupMember {
+                user_id: 2,
+                display_name: "Bob".to_string(),
+            },
+        ];
+        let rows = [
+            progress_row(1, 10, Some("Net"), 4, 3),
+            progress_row(2, 10, Some("Net"), 0, 0),
+            progress_row(1, 10, None, 2, 0),
+            progress_row(2, 10, None, 1, 1),
+            progress_row(1, 20, Some("Sec"), 0, 0),
+        ];
+
+        let tables = group_progress(vec![quiz(20), quiz(10)], &members, &rows);
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].quiz.quiz_id, 20);
+        assert_eq!(tables[0].categories, vec![Some("Sec".to_string())]);
+        // Bob has no rows for quiz 20 and still gets an empty row
+        assert_eq!(
+            tables[0].members[1].categories,
+            vec![ProgressCell::default()]
+        );
+
+        let table = &tables[1];
+        assert_eq!(table.categories, vec![Some("Net".to_string()), None]);
+        let alice = &table.members[0];
+        assert_eq!(alice.display_name, "Alice");
+        assert_eq!(alice.categories[0].accuracy(), Some(0.75));
+        assert_eq!(alice.categories[1].accuracy(), Some(0.0));
+        assert_eq!(alice.total.seen, 6);
+        assert_eq!(alice.total.questions, 8);
+        assert_eq!(alice.active_ms, 6000);
+        let bob = &table.members[1];
+        assert_eq!(bob.categories[0].accuracy(), None);
+        assert_eq!(bob.total.accuracy(), Some(1.0));
+    }
+
+    #[test]
+    fn assignment_status_tracks_deadline() {
+        let due = "2030-06-15";
+        assert_eq!(assignment_status(due, 3, None), AssignmentStatus::Pending);
+        assert_eq!(assignment_status(due, 0, None), AssignmentStatus::Pending);
+        assert_eq!(assignment_status(due, -1, None), AssignmentStatus::Overdue);
+        assert_eq!(
+            assignment_status(due, -1, Some("2030-06-15")),
+            AssignmentStatus::Completed { late: false }
+        );
+        assert_eq!(
+            assignment_status(due, -5, Some("2030-06-16")),
+            AssignmentStatus::Completed { late: true }
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use color_eyre::Result;
+
+use super::models::{ActivitySummary, DailyActivity, DailyGoal};
+use super::Db;
+use crate::analytics;
+
+impl Db {
+    /// Answers per day across all quizzes of a user over the last `window_days` days,
+    /// with streaks over the user's whole history and their daily goal. Days are UTC.
+    pub async fn get_activity_summary(
+        &self,
+        user_id: i32,
+        window_days: i32,
+    ) -> Result<ActivitySummary> {
+        let active_days: Vec<i32> = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT ((NOW() AT TIME ZONE 'UTC')::DATE - (sq.answered_at AT TIME ZONE 'UTC')::DATE) AS "days_ago!"
+            FROM session_questions sq
+            JOIN quiz_sessions s ON s.id = sq.session_id
+            WHERE s.user_id = $1 AND sq.answered_at IS NOT NULL
+            ORDER BY 1
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let days = sqlx::query_as!(
+            DailyActivity,
+            r#"
+            WITH days AS (
+                SELECT d::DATE AS day
+                FROM generate_series(
+                    (NOW() AT TIME ZONE 'UTC')::DATE - $2::INT,
+                    (NOW() AT TIME ZONE 'UTC')::DATE,
+                    INTERVAL '1 day'
+                ) AS d
+            ),
+            answered AS (
+                SELECT (sq.answered_at AT TIME ZONE 'UTC')::DATE AS day,
+                       COUNT(*)::INT AS questions,
+                       SUM(sq.active_ms)::BIGINT AS active_ms
+                FROM session_questions sq
+                JOIN quiz_sessions s ON s.id = sq.session_id
+                WHERE s.user_id = $1
+                  AND (sq.answered_at AT TIME ZONE 'UTC')::DATE >= (NOW() AT TIME ZONE 'UTC')::DATE - $2::INT
+                GROUP BY 1
+            )
+            SELECT ((NOW() AT TIME ZONE 'UTC')::DATE - days.day) AS "days_ago!",
+                   TO_CHAR(days.day, 'YYYY-MM-DD') AS "date_label!",
+                   EXTRACT(ISODOW FROM days.day)::INT AS "weekday!",
+                   COALESCE(a.questions, 0) AS "questions!",
+                   COALESCE(a.active_ms, 0) AS "active_ms!"
+            FROM days
+            LEFT JOIN answered a ON a.day = days.day
+            ORDER BY days.day
+            "#,
+            user_id,
+            window_days - 1
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let goal = sqlx::query!(
+            "SELECT daily_goal_kind, daily_goal_target FROM users WHERE id = $1",
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let (current_streak, longest_streak) = analytics::streaks(&active_days);
+        let goal = goal
+            .daily_goal_kind
+            .zip(goal.daily_goal_target)
+            .map(|(kind, target)| DailyGoal { kind, target });
+
+        Ok(ActivitySummary {
+            days,
+            current_streak,
+            longest_streak,
+            goal,
+        })
+    }
+
+    /// Set or clear (with `None`) a user's daily goal.
+    pub async fn set_daily_goal(&self, user_id: i32, goal: Option<&DailyGoal>) -> Result<()> {
+        sqlx::query!(
+            "UPDATE users SET daily_goal_kind = $1, daily_goal_target = $2 WHERE id = $3",
+            goal.map(|g| g.kind.as_str()),
+            goal.map(|g| g.target),
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
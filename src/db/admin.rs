@@ -70,9 +70,9 @@ impl Db {
                  WHERE uq2.user_id = u.id
                 ) AS "total_questions!",
                 COALESCE((
-                    SELECT SUM(ua.duration_ms)::BIGINT
-                    FROM user_answers ua
-                    JOIN quiz_sessions qs2 ON qs2.id = ua.session_id
+                    SELECT SUM(sq.active_ms)::BIGINT
+                    FROM session_questions sq
+                    JOIN quiz_sessions qs2 ON qs2.id = sq.session_id
                     WHERE qs2.user_id = u.id
                 ), 0) AS "total_study_time_ms!"
             FROM users u
@@ -1,7 +1,29 @@
-use color_eyre::Result;
+use std::collections::HashSet;
 
-use super::models::{AnswerModel, CategoryStats};
+use color_eyre::{eyre::OptionExt, Result};
+
+use super::models::{AnswerModel, AnswerOutcome, CategoryStats};
+use super::question::render_template;
 use super::Db;
+use crate::template;
+
+/// How many questions later a missed question comes back in a cram session.
+const CRAM_REQUEUE_GAP: i32 = 3;
+
+/// Time on one question beyond this is treated as an idle gap (a backgrounded tab, a break)
+/// rather than study time.
+const IDLE_AFTER_MS: i64 = 5 * 60 * 1000;
+
+/// Split the time since a question was shown into active study time and idle time.
+/// Questions that were never marked as shown count as neither.
+fn answer_timing(elapsed_ms: Option<i64>) -> (i32, i32) {
+    let Some(elapsed) = elapsed_ms else {
+        return (0, 0);
+    };
+    let elapsed = elapsed.clamp(0, i32::MAX as i64);
+    let active = elapsed.min(IDLE_AFTER_MS);
+    (active as i32, (elapsed - active) as i32)
+}
 
 impl Db {
     pub async fn is_question_answered(&self, session_id: i32, question_id: i32) -> Result<bool> {
@@ -21,11 +43,16 @@ impl Db {
         &self,
         session_id: i32,
         question_id: i32,
+        attempt: i32,
     ) -> Result<Vec<i32>> {
         let option_ids: Vec<i32> = sqlx::query_scalar!(
-            "SELECT option_id FROM user_answers WHERE session_id = $1 AND question_id = $2",
+            r#"
+            SELECT option_id AS "option_id!" FROM user_answers
+            WHERE session_id = $1 AND question_id = $2 AND attempt = $3 AND option_id IS NOT NULL
+            "#,
             session_id,
-            question_id
+            question_id,
+            attempt
         )
         .fetch_all(&self.pool)
         .await?;
@@ -60,76 +87,389 @@ impl Db {
         Ok(())
     }
 
-    /// Batch insert answers for multiple selected options in a single round-trip using UNNEST.
-    pub async fn create_answers_batch(
+    /// Grade and store an answer to one question of a session in a single transaction.
+    ///
+    /// The `session_questions` rows are locked before grading, so concurrent submissions for
+    /// the same question are serialized: only the first is recorded, and later ones report
+    /// whether they carried the same idempotency key (a retry) or not (another tab).
+    /// In a cram session a wrong answer queues another attempt a few questions later.
+    pub async fn record_answer(
         &self,
         session_id: i32,
         question_id: i32,
-        option_ids: &[i32],
-        is_correct: bool,
-        duration_ms: i32,
-    ) -> Result<()> {
-        if option_ids.is_empty() {
-            return Ok(());
+        selected_ids: &[i32],
+        idempotency_key: &str,
+        confidence: Option<&str>,
+    ) -> Result<AnswerOutcome> {
+        let mut seen = HashSet::new();
+        let selected_ids: Vec<i32> = selected_ids
+            .iter()
+            .copied()
+            .filter(|id| seen.insert(*id))
+            .collect();
+        if selected_ids.is_empty() {
+            return Ok(AnswerOutcome::InvalidSelection);
         }
 
-        let result = sqlx::query!(
+        let mut tx = self.pool.begin().await?;
+
+        let attempts = sqlx::query!(
+            r#"
+            SELECT sq.id, sq.question_number, sq.attempt, sq.is_correct, sq.answer_key,
+                   q.is_multiple_choice AS "is_multiple_choice!", s.study_mode,
+                   (EXTRACT(EPOCH FROM NOW() - sq.served_at) * 1000)::BIGINT AS elapsed_ms
+            FROM session_questions sq
+            JOIN questions q ON q.id = sq.question_id
+            JOIN quiz_sessions s ON s.id = sq.session_id
+            WHERE sq.session_id = $1 AND sq.question_id = $2
+            ORDER BY sq.attempt
+            FOR UPDATE OF sq
+            "#,
+            session_id,
+            question_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // A retry of an earlier attempt must not answer the attempt queued after it.
+        if attempts
+            .iter()
+            .any(|a| a.answer_key.as_deref() == Some(idempotency_key))
+        {
+            tracing::info!(
+                "answer for session={session_id} question={question_id} already recorded: Replayed"
+            );
+            return Ok(AnswerOutcome::Replayed);
+        }
+
+        let row = attempts
+            .last()
+            .ok_or_eyre("question is not part of this session")?;
+
+        if row.is_correct.is_some() {
+            tracing::info!(
+                "answer for session={session_id} question={question_id} already recorded: AlreadyAnswered"
+            );
+            return Ok(AnswerOutcome::AlreadyAnswered);
+        }
+
+        let options = sqlx::query!(
+            "SELECT id, is_answer FROM options WHERE question_id = $1",
+            question_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if selected_ids
+            .iter()
+            .any(|id| !options.iter().any(|o| o.id == *id))
+        {
+            tracing::info!(
+                "answer for session={session_id} question={question_id} selects a foreign option"
+            );
+            return Ok(AnswerOutcome::InvalidSelection);
+        }
+
+        let correct_ids: Vec<i32> = options
+            .iter()
+            .filter(|o| o.is_answer)
+            .map(|o| o.id)
+            .collect();
+        let is_correct = grade_answer(row.is_multiple_choice, &selected_ids, &correct_ids);
+        let (active_ms, idle_ms) = answer_timing(row.elapsed_ms);
+
+        sqlx::query!(
             r#"
-            INSERT INTO user_answers (is_correct, option_id, question_id, session_id, duration_ms)
-            SELECT $1, o, $3, $4, $5
+            INSERT INTO user_answers (is_correct, option_id, question_id, session_id, confidence, attempt)
+            SELECT $1, o, $3, $4, $5, $6
             FROM UNNEST($2::INT4[]) AS t(o)
             "#,
             is_correct,
-            option_ids,
+            &selected_ids,
             question_id,
             session_id,
-            duration_ms
+            confidence,
+            row.attempt
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
+        sqlx::query!(
+            r#"
+            UPDATE session_questions
+            SET is_correct = $1, answer_key = $2, answered_at = NOW(), active_ms = $3, idle_ms = $4
+            WHERE id = $5
+            "#,
+            is_correct,
+            idempotency_key,
+            active_ms,
+            idle_ms,
+            row.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        Self::touch_session_tx(&mut tx, session_id).await?;
+
+        if row.attempt == 1 {
+            Self::count_response_tx(&mut tx, question_id, &selected_ids).await?;
+        }
+
+        if !is_correct && row.study_mode == "cram" {
+            Self::requeue_question_tx(
+                &mut tx,
+                session_id,
+                question_id,
+                row.question_number,
+                row.attempt + 1,
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+
         tracing::info!(
-            "batch answers created for session={session_id} question={question_id}: {:?}",
-            result.rows_affected()
+            "answer recorded for session={session_id} question={question_id}: correct={is_correct}"
         );
+        Ok(AnswerOutcome::Recorded { is_correct })
+    }
+
+    /// Insert another attempt at a missed question a few positions after the answered one
+    /// (or at the end of the queue), shifting the questions behind it.
+    async fn requeue_question_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        session_id: i32,
+        question_id: i32,
+        answered_number: i32,
+        attempt: i32,
+    ) -> Result<()> {
+        // Serialize requeues within a session so positions are shifted consistently.
+        let total: i32 = sqlx::query_scalar!(
+            r#"
+            SELECT (SELECT COUNT(*)::INT FROM session_questions WHERE session_id = $1) AS "total!"
+            FROM quiz_sessions WHERE id = $1
+            FOR UPDATE
+            "#,
+            session_id
+        )
+        .fetch_one(&mut **tx)
+        .await?;
 
+        let position = (answered_number + 1 + CRAM_REQUEUE_GAP).min(total);
+
+        // Two steps, because (session_id, question_number) is checked row by row.
+        sqlx::query!(
+            "UPDATE session_questions SET question_number = -question_number - 1 WHERE session_id = $1 AND question_number >= $2",
+            session_id,
+            position
+        )
+        .execute(&mut **tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE session_questions SET question_number = -question_number WHERE session_id = $1 AND question_number < 0",
+            session_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO session_questions (session_id, question_id, question_number, attempt, template_values)
+            SELECT $1, $2, $3, $4, (
+                SELECT template_values FROM session_questions
+                WHERE session_id = $1 AND question_id = $2 AND attempt = 1
+            )
+            "#,
+            session_id,
+            question_id,
+            position,
+            attempt
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        tracing::info!(
+            "requeued question={question_id} in session={session_id} at {position} (attempt {attempt})"
+        );
         Ok(())
     }
 
-    pub async fn quiz_study_time(&self, quiz_id: i32) -> Result<i64> {
-        let ms: Option<i64> = sqlx::query_scalar!(
-            "SELECT SUM(ua.duration_ms)::BIGINT FROM user_answers ua JOIN quiz_sessions qs ON qs.id = ua.session_id WHERE qs.quiz_id = $1",
-            quiz_id
+    /// Add a first-attempt response to the cached answer distribution of a question.
+    async fn count_response_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        question_id: i32,
+        selected_ids: &[i32],
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO question_response_counts (question_id, responses) VALUES ($1, 1)
+            ON CONFLICT (question_id) DO UPDATE
+            SET responses = question_response_counts.responses + 1
+            "#,
+            question_id
         )
-        .fetch_one(&self.pool)
+        .execute(&mut **tx)
         .await?;
 
-        Ok(ms.unwrap_or(0))
+        sqlx::query!(
+            r#"
+            INSERT INTO option_pick_counts (option_id, question_id, picks)
+            SELECT o, $2, 1 FROM UNNEST($1::INT4[]) AS t(o)
+            ON CONFLICT (option_id) DO UPDATE
+            SET picks = option_pick_counts.picks + 1
+            "#,
+            selected_ids,
+            question_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
     }
 
-    pub async fn session_study_time(&self, session_id: i32) -> Result<i64> {
-        let ms: Option<i64> = sqlx::query_scalar!(
-            "SELECT SUM(duration_ms)::BIGINT FROM user_answers WHERE session_id = $1",
+    /// Take a session's first-attempt responses out of the cached answer distribution,
+    /// before the session and its answers are deleted.
+    pub(super) async fn uncount_session_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        session_id: i32,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE question_response_counts
+            SET responses = responses - 1
+            WHERE question_id IN (
+                SELECT question_id FROM user_answers
+                WHERE session_id = $1 AND attempt = 1 AND option_id IS NOT NULL
+            )
+            "#,
             session_id
         )
-        .fetch_one(&self.pool)
+        .execute(&mut **tx)
         .await?;
 
-        Ok(ms.unwrap_or(0))
+        sqlx::query!(
+            r#"
+            UPDATE option_pick_counts
+            SET picks = picks - 1
+            WHERE option_id IN (
+                SELECT option_id FROM user_answers
+                WHERE session_id = $1 AND attempt = 1
+            )
+            "#,
+            session_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
     }
 
-    /// session_questions の is_correct を更新
-    pub async fn update_question_result(
+    async fn touch_session_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        session_id: i32,
+    ) -> Result<()> {
+        sqlx::query!(
+            "UPDATE quiz_sessions SET last_active_at = NOW() WHERE id = $1",
+            session_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store a flashcard self-grade for one question of a session.
+    ///
+    /// "again" counts as an incorrect answer and every other grade as correct, so self-graded
+    /// cards feed the same stats and question selection as picked options. Concurrent
+    /// submissions are serialized like in [`Db::record_answer`].
+    pub async fn record_self_grade(
         &self,
         session_id: i32,
         question_id: i32,
-        is_correct: bool,
-    ) -> Result<()> {
+        grade: &str,
+        idempotency_key: &str,
+    ) -> Result<AnswerOutcome> {
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query!(
+            r#"
+            SELECT id, attempt, is_correct, answer_key,
+                   (EXTRACT(EPOCH FROM NOW() - served_at) * 1000)::BIGINT AS elapsed_ms
+            FROM session_questions
+            WHERE session_id = $1 AND question_id = $2
+            ORDER BY attempt DESC
+            LIMIT 1
+            FOR UPDATE
+            "#,
+            session_id,
+            question_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_eyre("question is not part of this session")?;
+
+        if row.is_correct.is_some() {
+            let outcome = if row.answer_key.as_deref() == Some(idempotency_key) {
+                AnswerOutcome::Replayed
+            } else {
+                AnswerOutcome::AlreadyAnswered
+            };
+            return Ok(outcome);
+        }
+
+        let is_correct = grade != "again";
+        let (active_ms, idle_ms) = answer_timing(row.elapsed_ms);
+
         sqlx::query!(
-            "UPDATE session_questions SET is_correct = $1 WHERE session_id = $2 AND question_id = $3",
+            r#"
+            INSERT INTO user_answers (is_correct, option_id, question_id, session_id, self_grade, attempt)
+            VALUES ($1, NULL, $2, $3, $4, $5)
+            "#,
             is_correct,
+            question_id,
             session_id,
-            question_id
+            grade,
+            row.attempt
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE session_questions
+            SET is_correct = $1, answer_key = $2, answered_at = NOW(), active_ms = $3, idle_ms = $4
+            WHERE id = $5
+            "#,
+            is_correct,
+            idempotency_key,
+            active_ms,
+            idle_ms,
+            row.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        Self::touch_session_tx(&mut tx, session_id).await?;
+
+        tx.commit().await?;
+
+        tracing::info!(
+            "self-grade recorded for session={session_id} question={question_id}: {grade}"
+        );
+        Ok(AnswerOutcome::Recorded { is_correct })
+    }
+
+    /// Start the clock for an unanswered question as it is shown. Showing it again
+    /// (navigating back, resuming later) restarts the clock, so time spent away is not counted.
+    pub async fn mark_question_served(&self, session_id: i32, question_idx: i32) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE session_questions SET served_at = NOW()
+            WHERE session_id = $1 AND question_number = $2 AND is_correct IS NULL
+            "#,
+            session_id,
+            question_idx
         )
         .execute(&self.pool)
         .await?;
@@ -137,10 +477,88 @@ impl Db {
         Ok(())
     }
 
+    /// Reveal the next hint for an unanswered question. Returns the question's hints and how
+    /// many of them are now revealed; answered questions and exhausted hints are left as they are.
+    pub async fn reveal_hint(
+        &self,
+        session_id: i32,
+        question_idx: i32,
+    ) -> Result<(Vec<String>, i32)> {
+        sqlx::query!(
+            r#"
+            UPDATE session_questions sq SET hints_used = sq.hints_used + 1
+            FROM questions q
+            WHERE q.id = sq.question_id
+              AND sq.session_id = $1 AND sq.question_number = $2 AND sq.is_correct IS NULL
+              AND sq.hints_used < CARDINALITY(q.hints)
+            "#,
+            session_id,
+            question_idx
+        )
+        .execute(&self.pool)
+        .await?;
+
+        let row = sqlx::query!(
+            r#"
+            SELECT q.hints, sq.hints_used, sq.template_values
+            FROM session_questions sq
+            JOIN questions q ON q.id = sq.question_id
+            WHERE sq.session_id = $1 AND sq.question_number = $2
+            "#,
+            session_id,
+            question_idx
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_eyre("question not found in session")?;
+
+        let values = template::parse_values(row.template_values.as_deref());
+        let hints = row
+            .hints
+            .into_iter()
+            .map(|h| render_template(h, values.as_ref()))
+            .collect();
+        Ok((hints, row.hints_used))
+    }
+
+    /// Idle time detected across a session's answers.
+    pub async fn session_idle_time(&self, session_id: i32) -> Result<i64> {
+        let ms: Option<i64> = sqlx::query_scalar!(
+            "SELECT SUM(idle_ms)::BIGINT FROM session_questions WHERE session_id = $1",
+            session_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ms.unwrap_or(0))
+    }
+
+    pub async fn quiz_study_time(&self, quiz_id: i32) -> Result<i64> {
+        let ms: Option<i64> = sqlx::query_scalar!(
+            "SELECT SUM(sq.active_ms)::BIGINT FROM session_questions sq JOIN quiz_sessions qs ON qs.id = sq.session_id WHERE qs.quiz_id = $1",
+            quiz_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ms.unwrap_or(0))
+    }
+
+    pub async fn session_study_time(&self, session_id: i32) -> Result<i64> {
+        let ms: Option<i64> = sqlx::query_scalar!(
+            "SELECT SUM(active_ms)::BIGINT FROM session_questions WHERE session_id = $1",
+            session_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ms.unwrap_or(0))
+    }
+
     /// 正解数カウント（問題単位で正確）
     pub async fn correct_answers(&self, session_id: i32) -> Result<i32> {
         let count: i32 = sqlx::query_scalar!(
-            r#"SELECT COUNT(*)::INT AS "count!" FROM session_questions WHERE session_id = $1 AND is_correct = TRUE"#,
+            r#"SELECT COUNT(*)::INT AS "count!" FROM session_questions WHERE session_id = $1 AND attempt = 1 AND is_correct = TRUE"#,
             session_id
         )
         .fetch_one(&self.pool)
@@ -150,12 +568,19 @@ impl Db {
     }
 
     pub async fn get_answers(&self, session_id: i32) -> Result<Vec<AnswerModel>> {
-        let answers = sqlx::query_as!(
-            AnswerModel,
+        let rows = sqlx::query!(
             r#"
             SELECT q.question AS question, sq.is_correct AS "is_correct!", sq.question_number AS question_idx,
-                   sq.is_bookmarked AS "is_bookmarked!"
+                   sq.attempt,
+                   EXISTS(
+                       SELECT 1 FROM user_bookmarks ub
+                       WHERE ub.user_id = s.user_id AND ub.question_id = sq.question_id
+                   ) AS "is_bookmarked!",
+                   sq.hints_used,
+                   sq.active_ms,
+                   sq.template_values
             FROM session_questions sq
+            JOIN quiz_sessions s ON s.id = sq.session_id
             JOIN questions q ON sq.question_id = q.id
             WHERE sq.session_id = $1 AND sq.is_correct IS NOT NULL
             ORDER BY sq.question_number
@@ -165,6 +590,22 @@ impl Db {
         .fetch_all(&self.pool)
         .await?;
 
+        let answers = rows
+            .into_iter()
+            .map(|r| {
+                let values = template::parse_values(r.template_values.as_deref());
+                AnswerModel {
+                    question: render_template(r.question, values.as_ref()),
+                    is_correct: r.is_correct,
+                    question_idx: r.question_idx,
+                    attempt: r.attempt,
+                    is_bookmarked: r.is_bookmarked,
+                    hints_used: r.hints_used,
+                    active_ms: r.active_ms,
+                }
+            })
+            .collect();
+
         Ok(answers)
     }
 
@@ -190,7 +631,8 @@ impl Db {
                 ROUND(SUM(CASE WHEN sq.is_correct THEN 1 ELSE 0 END)::NUMERIC * 100.0 / COUNT(*), 1)::FLOAT8 AS "accuracy!"
             FROM session_questions sq
             JOIN questions q ON sq.question_id = q.id
-            WHERE sq.session_id = $1 AND q.category IS NOT NULL AND sq.is_correct IS NOT NULL
+            WHERE sq.session_id = $1 AND sq.attempt = 1
+              AND q.category IS NOT NULL AND sq.is_correct IS NOT NULL
             GROUP BY q.category
             "#,
             session_id
@@ -201,3 +643,15 @@ impl Db {
         Ok(stats)
     }
 }
+
+/// Multiple choice questions need the exact set of correct options;
+/// single choice questions need their one selected option to be correct.
+fn grade_answer(is_multiple_choice: bool, selected_ids: &[i32], correct_ids: &[i32]) -> bool {
+    if is_multiple_choice {
+        let selected: HashSet<i32> = selected_ids.iter().copied().collect();
+        let correct: HashSet<i32> = correct_ids.iter().copied().collect();
+        selected == correct
+    } else {
+        selected_ids.len() == 1 && correct_ids.contains(&selected_ids[0])
+    }
+}
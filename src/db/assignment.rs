@@ -0,0 +1,256 @@
+use color_eyre::Result;
+use ulid::Ulid;
+
+use super::models::{AssignmentLearner, AssignmentModel, AssignmentSettings, AssignmentTarget};
+use super::Db;
+
+impl Db {
+    /// Assign a quiz and put it in the learners' libraries. Returns the assignment's public
+    /// id, or `None` when the due date is in the past.
+    pub async fn create_assignment(
+        &self,
+        created_by: i32,
+        target: &AssignmentTarget,
+        settings: &AssignmentSettings,
+    ) -> Result<Option<String>> {
+        let (group_id, assignee_id) = match *target {
+            AssignmentTarget::Group(group_id) => (Some(group_id), None),
+            AssignmentTarget::User(user_id) => (None, Some(user_id)),
+        };
+        let public_id = Ulid::new().to_string();
+
+        let mut tx = self.pool.begin().await?;
+
+        let created = sqlx::query!(
+            r#"
+            INSERT INTO assignments
+                (public_id, quiz_id, created_by, group_id, assignee_id, due_date, question_count,
+                 selection_mode, target_score)
+            SELECT $1, $2, $3, $4, $5, $6::TEXT::DATE, $7, $8, $9
+            WHERE $6::TEXT::DATE >= (NOW() AT TIME ZONE 'UTC')::DATE
+            "#,
+            public_id,
+            settings.quiz_id,
+            created_by,
+            group_id,
+            assignee_id,
+            settings.due_date,
+            settings.question_count,
+            settings.selection_mode,
+            settings.target_score
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        if !created {
+            return Ok(None);
+        }
+
+        match *target {
+            AssignmentTarget::Group(group_id) => {
+                Self::assign_group_quiz_tx(&mut tx, group_id, settings.quiz_id).await?
+            }
+            AssignmentTarget::User(user_id) => {
+                Self::add_quiz_to_library_tx(&mut tx, user_id, settings.quiz_id).await?
+            }
+        }
+
+        tx.commit().await?;
+
+        tracing::info!("assignment {public_id} created by user={created_by}");
+        Ok(Some(public_id))
+    }
+
+    pub async fn get_assignment(&self, public_id: &str) -> Result<Option<AssignmentModel>> {
+        Ok(self
+            .find_assignments(Some(public_id), None, None)
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    /// Assignments given to the user directly or through a group, soonest due first.
+    pub async fn assignments_for_learner(&self, user_id: i32) -> Result<Vec<AssignmentModel>> {
+        self.find_assignments(None, Some(user_id), None).await
+    }
+
+    /// Assignments the user gave, soonest due first.
+    pub async fn assignments_created_by(&self, user_id: i32) -> Result<Vec<AssignmentModel>> {
+        self.find_assignments(None, None, Some(user_id)).await
+    }
+
+    /// Assignments matching every given filter: public id, learner (directly or through a
+    /// group) and instructor.
+    async fn find_assignments(
+        &self,
+        public_id: Option<&str>,
+        learner_id: Option<i32>,
+        created_by: Option<i32>,
+    ) -> Result<Vec<AssignmentModel>> {
+        let assignments = sqlx::query_as!(
+            AssignmentModel,
+            r#"
+            SELECT a.id, a.public_id, a.quiz_id, q.public_id AS "quiz_public_id!", q.name AS quiz_name,
+                   a.created_by, u.display_name AS creator_name,
+                   a.group_id, g.name AS "group_name?", au.display_name AS "assignee_name?",
+                   TO_CHAR(a.due_date, 'YYYY-MM-DD') AS "due_date!",
+                   (a.due_date - (NOW() AT TIME ZONE 'UTC')::DATE) AS "days_left!",
+                   a.question_count, a.selection_mode, a.target_score
+            FROM assignments a
+            JOIN quizzes q ON q.id = a.quiz_id
+            JOIN users u ON u.id = a.created_by
+            LEFT JOIN groups g ON g.id = a.group_id
+            LEFT JOIN users au ON au.id = a.assignee_id
+            WHERE ($1::TEXT IS NULL OR a.public_id = $1)
+              AND ($2::INT IS NULL
+                   OR a.assignee_id = $2
+                   OR EXISTS (SELECT 1 FROM group_members gm WHERE gm.group_id = a.group_id AND gm.user_id = $2))
+              AND ($3::INT IS NULL OR a.created_by = $3)
+            ORDER BY a.due_date, a.id
+            "#,
+            public_id,
+            learner_id,
+            created_by
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(assignments)
+    }
+
+    /// Every learner of the given assignments (or only `user_id`) with their sessions
+    /// started from them. A session completes an assignment once every question is answered,
+    /// it has at least the assigned number of questions (or the whole quiz, if smaller) and
+    /// its first-attempt score reaches the target.
+    pub async fn assignment_learners(
+        &self,
+        assignment_ids: &[i32],
+        user_id: Option<i32>,
+    ) -> Result<Vec<AssignmentLearner>> {
+        let learners = sqlx::query_as!(
+            AssignmentLearner,
+            r#"
+            WITH learners AS (
+                SELECT a.id AS assignment_id, gm.user_id
+                FROM assignments a
+                JOIN group_members gm ON gm.group_id = a.group_id
+                WHERE a.id = ANY($1)
+                UNION
+                SELECT a.id, a.assignee_id
+                FROM assignments a
+                WHERE a.id = ANY($1) AND a.assignee_id IS NOT NULL
+            ),
+            requirements AS (
+                SELECT a.id,
+                       LEAST(a.question_count, (SELECT COUNT(*) FROM questions q WHERE q.quiz_id = a.quiz_id))
+                           AS required,
+                       a.target_score
+                FROM assignments a
+                WHERE a.id = ANY($1)
+            ),
+            attempts AS (
+                SELECT s.assignment_id, s.user_id, ss.total_questions, ss.correct_answers, ss.is_complete,
+                       (SELECT MAX(sq.answered_at) FROM session_questions sq WHERE sq.session_id = s.id)
+                           AS finished_at
+                FROM quiz_sessions s
+                JOIN session_stats ss ON ss.session_id = s.id
+                WHERE s.assignment_id = ANY($1)
+            )
+            SELECT l.assignment_id AS "assignment_id!", l.user_id AS "user_id!", u.display_name,
+                   COUNT(t.assignment_id) AS "attempts!",
+                   MAX(t.correct_answers * 100 / NULLIF(t.total_questions, 0)) FILTER (WHERE t.is_complete)
+                       AS best_score,
+                   TO_CHAR(MIN((t.finished_at AT TIME ZONE 'UTC')::DATE) FILTER (
+                       WHERE t.is_complete AND t.total_questions >= r.required
+                         AND t.correct_answers * 100 >= r.target_score * t.total_questions
+                   ), 'YYYY-MM-DD') AS completed_on
+            FROM learners l
+            JOIN requirements r ON r.id = l.assignment_id
+            JOIN users u ON u.id = l.user_id
+            LEFT JOIN attempts t ON t.assignment_id = l.assignment_id AND t.user_id = l.user_id
+            WHERE $2::INT IS NULL OR l.user_id = $2
+            GROUP BY l.assignment_id, l.user_id, u.display_name
+            ORDER BY l.assignment_id, u.display_name, l.user_id
+            "#,
+            assignment_ids,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(learners)
+    }
+
+    /// Whether the assignment is for the user, directly or through a group.
+    pub async fn is_assignment_learner(&self, assignment_id: i32, user_id: i32) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM assignments a
+                WHERE a.id = $1
+                  AND (a.assignee_id = $2
+                       OR EXISTS (SELECT 1 FROM group_members gm
+                                  WHERE gm.group_id = a.group_id AND gm.user_id = $2))
+            )
+            "#,
+            assignment_id,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .unwrap_or(false);
+
+        Ok(exists)
+    }
+
+    pub async fn delete_assignment(&self, assignment_id: i32) -> Result<()> {
+        sqlx::query!("DELETE FROM assignments WHERE id = $1", assignment_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Start a session with the assignment's settings that counts toward it. Returns
+    /// `(session_token, session_id)`.
+    pub async fn create_assignment_session(
+        &self,
+        name: &str,
+        assignment: &AssignmentModel,
+        study_mode: &str,
+        user_id: i32,
+    ) -> Result<(String, i32)> {
+        self.insert_session(
+            name,
+            assignment.quiz_id,
+            assignment.question_count,
+            &assignment.selection_mode,
+            study_mode,
+            user_id,
+            Some(assignment.id),
+        )
+        .await
+    }
+
+    /// The member with this email in one of the groups the instructor leads. Individual
+    /// assignments are limited to them, so a quiz cannot be pushed to an arbitrary account.
+    /// `email` is lowercase; accounts keep the case they registered with.
+    pub async fn find_led_group_member(&self, owner_id: i32, email: &str) -> Result<Option<i32>> {
+        let user_id = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT u.id
+            FROM users u
+            JOIN group_members gm ON gm.user_id = u.id
+            JOIN groups g ON g.id = gm.group_id
+            WHERE g.owner_id = $1 AND LOWER(u.email) = $2 AND u.id <> $1
+            "#,
+            owner_id,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user_id)
+    }
+}
@@ -0,0 +1,148 @@
+use color_eyre::Result;
+
+use super::models::BookmarkedQuestion;
+use super::Db;
+
+impl Db {
+    pub async fn is_question_bookmarked(&self, user_id: i32, question_id: i32) -> Result<bool> {
+        let bookmarked: bool = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM user_bookmarks WHERE user_id = $1 AND question_id = $2) AS "bookmarked!""#,
+            user_id,
+            question_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(bookmarked)
+    }
+
+    /// ブックマーク状態をトグルし、新しい状態を返す
+    pub async fn toggle_bookmark(&self, user_id: i32, question_id: i32) -> Result<bool> {
+        let removed = sqlx::query!(
+            "DELETE FROM user_bookmarks WHERE user_id = $1 AND question_id = $2",
+            user_id,
+            question_id
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        if removed > 0 {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            "INSERT INTO user_bookmarks (user_id, question_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            user_id,
+            question_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Questions of a session that the session's user has bookmarked
+    pub async fn get_bookmarked_questions(&self, session_id: i32) -> Result<Vec<i32>> {
+        let ids: Vec<i32> = sqlx::query_scalar!(
+            r#"
+            SELECT sq.question_id
+            FROM session_questions sq
+            JOIN quiz_sessions s ON s.id = sq.session_id
+            JOIN user_bookmarks ub ON ub.user_id = s.user_id AND ub.question_id = sq.question_id
+            WHERE sq.session_id = $1 AND sq.attempt = 1
+            ORDER BY sq.question_number
+            "#,
+            session_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// All bookmarks of a user across the quizzes in their library
+    pub async fn get_user_bookmarks(&self, user_id: i32) -> Result<Vec<BookmarkedQuestion>> {
+        let bookmarks = sqlx::query_as!(
+            BookmarkedQuestion,
+            r#"
+            SELECT qz.id AS quiz_id, qz.public_id AS "quiz_public_id!", qz.name AS quiz_name,
+                   q.id AS question_id, q.question, q.category,
+                   n.body AS "note?"
+            FROM user_bookmarks ub
+            JOIN questions q ON q.id = ub.question_id
+            JOIN quizzes qz ON qz.id = q.quiz_id
+            JOIN user_quizzes uq ON uq.quiz_id = qz.id AND uq.user_id = ub.user_id
+            LEFT JOIN question_notes n ON n.user_id = ub.user_id AND n.question_id = q.id
+            WHERE ub.user_id = $1
+            ORDER BY qz.name, qz.id, q.id
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(bookmarks)
+    }
+
+    pub async fn get_quiz_bookmarks(&self, user_id: i32, quiz_id: i32) -> Result<Vec<i32>> {
+        let ids: Vec<i32> = sqlx::query_scalar!(
+            r#"
+            SELECT q.id
+            FROM user_bookmarks ub
+            JOIN questions q ON q.id = ub.question_id
+            WHERE ub.user_id = $1 AND q.quiz_id = $2
+            ORDER BY q.id
+            "#,
+            user_id,
+            quiz_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    pub async fn get_note(&self, user_id: i32, question_id: i32) -> Result<Option<String>> {
+        let note = sqlx::query_scalar!(
+            "SELECT body FROM question_notes WHERE user_id = $1 AND question_id = $2",
+            user_id,
+            question_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(note)
+    }
+
+    /// Save a private note on a question. A blank note deletes it.
+    pub async fn save_note(&self, user_id: i32, question_id: i32, body: &str) -> Result<()> {
+        let body = body.trim();
+        if body.is_empty() {
+            sqlx::query!(
+                "DELETE FROM question_notes WHERE user_id = $1 AND question_id = $2",
+                user_id,
+                question_id
+            )
+            .execute(&self.pool)
+            .await?;
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO question_notes (user_id, question_id, body)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, question_id)
+            DO UPDATE SET body = EXCLUDED.body, updated_at = CURRENT_TIMESTAMP
+            "#,
+            user_id,
+            question_id,
+            body
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+use color_eyre::{eyre::OptionExt, Result};
+use ulid::Ulid;
+
+use super::models::{ChallengeModel, ChallengeStanding};
+use super::Db;
+
+impl Db {
+    /// Question ids of a session in the order they were asked (first attempts only).
+    pub async fn get_session_question_ids(&self, session_id: i32) -> Result<Vec<i32>> {
+        let ids = sqlx::query_scalar!(
+            "SELECT question_id FROM session_questions WHERE session_id = $1 AND attempt = 1 ORDER BY question_number",
+            session_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    pub async fn get_session_shuffle_seed(&self, session_id: i32) -> Result<i32> {
+        let seed: Option<i32> = sqlx::query_scalar!(
+            "SELECT shuffle_seed FROM quiz_sessions WHERE id = $1",
+            session_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(seed.unwrap_or(0))
+    }
+
+    /// The challenge created from a session, creating it on first use.
+    /// Returns the challenge's public id.
+    pub async fn get_or_create_challenge(&self, session_id: i32, user_id: i32) -> Result<String> {
+        if let Some(public_id) = sqlx::query_scalar!(
+            "SELECT public_id FROM challenges WHERE source_session_id = $1",
+            session_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        {
+            return Ok(public_id);
+        }
+
+        let question_ids = self.get_session_question_ids(session_id).await?;
+        let public_id = Ulid::new().to_string();
+
+        let mut tx = self.pool.begin().await?;
+
+        let challenge_id: Option<i32> = sqlx::query_scalar!(
+            r#"
+            INSERT INTO challenges (public_id, quiz_id, source_session_id, created_by, question_ids, shuffle_seed, study_mode)
+            SELECT $1, s.quiz_id, s.id, $3, $4, COALESCE(s.shuffle_seed, 0), s.study_mode
+            FROM quiz_sessions s
+            WHERE s.id = $2
+            ON CONFLICT (source_session_id) DO NOTHING
+            RETURNING id
+            "#,
+            public_id,
+            session_id,
+            user_id,
+            &question_ids
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(challenge_id) = challenge_id else {
+            // Another request created it first
+            tx.rollback().await?;
+            let public_id = sqlx::query_scalar!(
+                "SELECT public_id FROM challenges WHERE source_session_id = $1",
+                session_id
+            )
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_eyre("session not found")?;
+            return Ok(public_id);
+        };
+
+        // The source session takes part in its own challenge.
+        sqlx::query!(
+            "UPDATE quiz_sessions SET challenge_id = $1 WHERE id = $2",
+            challenge_id,
+            session_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!("challenge {public_id} created from session={session_id}");
+        Ok(public_id)
+    }
+
+    pub async fn get_challenge(&self, public_id: &str) -> Result<Option<ChallengeModel>> {
+        let challenge = sqlx::query_as!(
+            ChallengeModel,
+            r#"
+            SELECT
+                c.id,
+                c.public_id,
+                c.quiz_id,
+                q.public_id AS "quiz_public_id!",
+                q.name AS quiz_name,
+                q.is_shared AS "is_joinable!",
+                c.created_by,
+                u.display_name AS creator_name,
+                c.question_ids,
+                c.shuffle_seed,
+                c.study_mode
+            FROM challenges c
+            JOIN quizzes q ON q.id = c.quiz_id
+            JOIN users u ON u.id = c.created_by
+            WHERE c.public_id = $1
+            "#,
+            public_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(challenge)
+    }
+
+    pub async fn get_session_challenge_public_id(&self, session_id: i32) -> Result<Option<String>> {
+        let public_id = sqlx::query_scalar!(
+            r#"
+            SELECT c.public_id
+            FROM quiz_sessions s
+            JOIN challenges c ON c.id = s.challenge_id
+            WHERE s.id = $1
+            "#,
+            session_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(public_id)
+    }
+
+    /// Everyone's sessions in a challenge, ranked by first-attempt score.
+    pub async fn get_challenge_standings(
+        &self,
+        challenge_id: i32,
+    ) -> Result<Vec<ChallengeStanding>> {
+        let standings = sqlx::query_as!(
+            ChallengeStanding,
+            r#"
+            SELECT
+                s.id AS session_id,
+                u.id AS user_id,
+                u.display_name,
+                (s.id = c.source_session_id) AS "is_creator!",
+                ss.correct_answers AS "correct!",
+                ss.answered_questions AS "answered!",
+                ss.total_questions AS "total!",
+                ss.is_complete AS "is_complete!"
+            FROM quiz_sessions s
+            JOIN challenges c ON c.id = s.challenge_id
+            JOIN users u ON u.id = s.user_id
+            JOIN session_stats ss ON ss.session_id = s.id
+            WHERE s.challenge_id = $1
+            ORDER BY ss.correct_answers DESC, ss.is_complete DESC, s.id
+            "#,
+            challenge_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(standings)
+    }
+}
@@ -0,0 +1,249 @@
+use color_eyre::Result;
+
+use super::models::{QuizAccess, QuizChange, QuizCollaborator, QuizInvitation, ReceivedInvitation};
+use super::Db;
+
+impl Db {
+    /// The user's author access to a quiz: owner, collaborator role, or `None`.
+    pub async fn quiz_access(&self, public_id: &str, user_id: i32) -> Result<Option<QuizAccess>> {
+        let role: Option<String> = sqlx::query_scalar!(
+            r#"
+            SELECT CASE WHEN q.owner_id = $2 THEN 'owner' ELSE c.role END AS role
+            FROM quizzes q
+            LEFT JOIN quiz_collaborators c ON c.quiz_id = q.id AND c.user_id = $2
+            WHERE q.public_id = $1
+            "#,
+            public_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten();
+
+        Ok(match role.as_deref() {
+            Some("owner") => Some(QuizAccess::Owner),
+            Some("editor") => Some(QuizAccess::Editor),
+            Some("sharing") => Some(QuizAccess::SharingManager),
+            _ => None,
+        })
+    }
+
+    pub async fn quiz_collaborators(&self, quiz_id: i32) -> Result<Vec<QuizCollaborator>> {
+        let collaborators = sqlx::query_as!(
+            QuizCollaborator,
+            r#"
+            SELECT u.id AS user_id, u.display_name, c.role
+            FROM quiz_collaborators c
+            JOIN users u ON u.id = c.user_id
+            WHERE c.quiz_id = $1
+            ORDER BY c.role, u.display_name, u.id
+            "#,
+            quiz_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(collaborators)
+    }
+
+    /// Invite an email address to collaborate, or change the role of a pending invitation.
+    /// Nothing is granted until the account with that email accepts. `email` is lowercase
+    /// and matches accounts whatever case they registered with.
+    pub async fn invite_quiz_collaborator(
+        &self,
+        quiz_id: i32,
+        email: &str,
+        role: &str,
+        invited_by: i32,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO quiz_invitations (quiz_id, email, role, invited_by) VALUES ($1, $2, $3, $4)
+            ON CONFLICT (quiz_id, email) DO UPDATE SET role = EXCLUDED.role, invited_by = EXCLUDED.invited_by
+            "#,
+            quiz_id,
+            email,
+            role,
+            invited_by
+        )
+        .execute(&self.pool)
+        .await?;
+
+        tracing::info!("invitation to quiz={quiz_id} as {role}, by user={invited_by}");
+        Ok(())
+    }
+
+    pub async fn quiz_invitations(&self, quiz_id: i32) -> Result<Vec<QuizInvitation>> {
+        let invitations = sqlx::query_as!(
+            QuizInvitation,
+            "SELECT id, email, role FROM quiz_invitations WHERE quiz_id = $1 ORDER BY email",
+            quiz_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(invitations)
+    }
+
+    /// Invitations addressed to the user's email, oldest first.
+    pub async fn received_invitations(&self, user_id: i32) -> Result<Vec<ReceivedInvitation>> {
+        let invitations = sqlx::query_as!(
+            ReceivedInvitation,
+            r#"
+            SELECT i.id, q.name AS quiz_name, iu.display_name AS invited_by, i.role
+            FROM quiz_invitations i
+            JOIN users u ON LOWER(u.email) = i.email
+            JOIN quizzes q ON q.id = i.quiz_id
+            JOIN users iu ON iu.id = i.invited_by
+            WHERE u.id = $1 AND q.owner_id <> $1
+            ORDER BY i.created_at, i.id
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(invitations)
+    }
+
+    /// Accept an invitation addressed to the user: make them a collaborator, put the quiz in
+    /// their library and record the change. Returns `false` when there is no such invitation
+    /// for them.
+    pub async fn accept_quiz_invitation(&self, invitation_id: i32, user_id: i32) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(invitation) = sqlx::query!(
+            r#"
+            DELETE FROM quiz_invitations i
+            USING users u, quizzes q
+            WHERE i.id = $1 AND u.id = $2 AND LOWER(u.email) = i.email
+              AND q.id = i.quiz_id AND q.owner_id <> $2
+            RETURNING i.quiz_id, i.role, i.invited_by
+            "#,
+            invitation_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(false);
+        };
+
+        sqlx::query!(
+            r#"
+            INSERT INTO quiz_collaborators (quiz_id, user_id, role) VALUES ($1, $2, $3)
+            ON CONFLICT (quiz_id, user_id) DO UPDATE SET role = EXCLUDED.role
+            "#,
+            invitation.quiz_id,
+            user_id,
+            invitation.role
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        Self::add_quiz_to_library_tx(&mut tx, user_id, invitation.quiz_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO quiz_changes (quiz_id, user_id, action, detail)
+            SELECT $1, $3, 'collaborator_' || $4, u.display_name
+            FROM users u WHERE u.id = $2
+            "#,
+            invitation.quiz_id,
+            user_id,
+            invitation.invited_by,
+            invitation.role
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(
+            "user={user_id} is {} of quiz={}, invited by user={}",
+            invitation.role,
+            invitation.quiz_id,
+            invitation.invited_by
+        );
+        Ok(true)
+    }
+
+    /// Decline an invitation addressed to the user.
+    pub async fn decline_quiz_invitation(&self, invitation_id: i32, user_id: i32) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM quiz_invitations i
+            USING users u
+            WHERE i.id = $1 AND u.id = $2 AND LOWER(u.email) = i.email
+            "#,
+            invitation_id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The owner withdraws a pending invitation.
+    pub async fn cancel_quiz_invitation(&self, quiz_id: i32, invitation_id: i32) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM quiz_invitations WHERE id = $1 AND quiz_id = $2",
+            invitation_id,
+            quiz_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Revoke a collaborator and record the change. The quiz stays in their library.
+    pub async fn remove_quiz_collaborator(
+        &self,
+        quiz_id: i32,
+        user_id: i32,
+        changed_by: i32,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            WITH removed AS (
+                DELETE FROM quiz_collaborators WHERE quiz_id = $1 AND user_id = $2
+                RETURNING quiz_id, user_id
+            )
+            INSERT INTO quiz_changes (quiz_id, user_id, action, detail)
+            SELECT r.quiz_id, $3, 'collaborator_removed', u.display_name
+            FROM removed r JOIN users u ON u.id = r.user_id
+            "#,
+            quiz_id,
+            user_id,
+            changed_by
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Most recent changes first.
+    pub async fn quiz_changes(&self, quiz_id: i32) -> Result<Vec<QuizChange>> {
+        let changes = sqlx::query_as!(
+            QuizChange,
+            r#"
+            SELECT u.display_name AS "display_name?", ch.action, ch.detail,
+                   TO_CHAR(ch.changed_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI') AS "changed_at!"
+            FROM quiz_changes ch
+            LEFT JOIN users u ON u.id = ch.user_id
+            WHERE ch.quiz_id = $1
+            ORDER BY ch.changed_at DESC, ch.id DESC
+            LIMIT $2
+            "#,
+            quiz_id,
+            crate::names::QUIZ_CHANGES_SHOWN
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(changes)
+    }
+}
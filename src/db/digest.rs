@@ -0,0 +1,160 @@
+use color_eyre::Result;
+
+use super::models::{DigestCategory, DigestRecipient, WeeklyDigest};
+use super::Db;
+use crate::services::digest::DigestRepository;
+
+impl DigestRepository for Db {
+    async fn users_due_for_digest(&self) -> Result<Vec<DigestRecipient>> {
+        self.users_due_for_digest().await
+    }
+
+    async fn get_weekly_digest(&self, user_id: i32) -> Result<WeeklyDigest> {
+        self.get_weekly_digest(user_id).await
+    }
+
+    async fn mark_digest_sent(&self, user_id: i32) -> Result<()> {
+        self.mark_digest_sent(user_id).await
+    }
+}
+
+impl Db {
+    /// Whether the user opted in to the weekly digest email.
+    pub async fn weekly_digest_enabled(&self, user_id: i32) -> Result<bool> {
+        let enabled = sqlx::query_scalar!("SELECT weekly_digest FROM users WHERE id = $1", user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .unwrap_or(false);
+
+        Ok(enabled)
+    }
+
+    /// Opt in to or out of the weekly digest, written in `locale`. Opting in counts as a
+    /// digest sent now, so the first one covers a full week.
+    pub async fn set_weekly_digest(&self, user_id: i32, enabled: bool, locale: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE users
+            SET digest_sent_at = CASE WHEN $2 AND NOT weekly_digest THEN NOW() ELSE digest_sent_at END,
+                weekly_digest = $2,
+                digest_locale = $3
+            WHERE id = $1
+            "#,
+            user_id,
+            enabled,
+            locale
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Verified, non-demo users who opted in and have not received a digest for a week.
+    pub async fn users_due_for_digest(&self) -> Result<Vec<DigestRecipient>> {
+        let recipients = sqlx::query_as!(
+            DigestRecipient,
+            r#"
+            SELECT id AS user_id, email, display_name, digest_locale AS locale
+            FROM users
+            WHERE weekly_digest AND email_verified AND NOT is_demo
+              AND (digest_sent_at IS NULL OR digest_sent_at <= NOW() - INTERVAL '7 days')
+            ORDER BY id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(recipients)
+    }
+
+    /// Remember that the user's digest went out now.
+    pub async fn mark_digest_sent(&self, user_id: i32) -> Result<()> {
+        sqlx::query!(
+            "UPDATE users SET digest_sent_at = NOW() WHERE id = $1",
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// A user's progress across all quizzes over the last seven days, compared with the
+    /// seven days before.
+    pub async fn get_weekly_digest(&self, user_id: i32) -> Result<WeeklyDigest> {
+        let week = sqlx::query!(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE sq.answered_at >= NOW() - INTERVAL '7 days') AS "questions_answered!",
+                   COALESCE(SUM(sq.active_ms) FILTER (WHERE sq.answered_at >= NOW() - INTERVAL '7 days'), 0)::BIGINT
+                       AS "active_ms!",
+                   (AVG(CASE WHEN sq.is_correct THEN 100.0 ELSE 0.0 END)
+                       FILTER (WHERE sq.attempt = 1 AND sq.answered_at >= NOW() - INTERVAL '7 days'))::FLOAT8
+                       AS accuracy,
+                   (AVG(CASE WHEN sq.is_correct THEN 100.0 ELSE 0.0 END)
+                       FILTER (WHERE sq.attempt = 1 AND sq.answered_at < NOW() - INTERVAL '7 days'))::FLOAT8
+                       AS previous_accuracy
+            FROM session_questions sq
+            JOIN quiz_sessions s ON s.id = sq.session_id
+            WHERE s.user_id = $1 AND sq.is_correct IS NOT NULL
+              AND sq.answered_at >= NOW() - INTERVAL '14 days'
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let weakest = sqlx::query_as!(
+            DigestCategory,
+            r#"
+            SELECT qz.name AS quiz_name,
+                   q.category AS "category!",
+                   COUNT(*) FILTER (WHERE sq.is_correct) AS "correct!",
+                   COUNT(*) AS "total!"
+            FROM session_questions sq
+            JOIN quiz_sessions s ON s.id = sq.session_id
+            JOIN questions q ON q.id = sq.question_id
+            JOIN quizzes qz ON qz.id = q.quiz_id
+            WHERE s.user_id = $1 AND sq.attempt = 1 AND sq.is_correct IS NOT NULL
+              AND sq.answered_at >= NOW() - INTERVAL '28 days'
+              AND q.category IS NOT NULL
+            GROUP BY qz.id, qz.name, q.category
+            HAVING COUNT(*) >= $2 AND COUNT(*) FILTER (WHERE NOT sq.is_correct) > 0
+            ORDER BY COUNT(*) FILTER (WHERE sq.is_correct)::FLOAT8 / COUNT(*), COUNT(*) DESC, qz.name, q.category
+            LIMIT $3
+            "#,
+            user_id,
+            crate::names::DIGEST_MIN_CATEGORY_ANSWERS,
+            crate::names::DIGEST_WEAKEST_CATEGORIES
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let due_reviews = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM (
+                SELECT DISTINCT ON (sq.question_id) sq.is_correct
+                FROM session_questions sq
+                JOIN quiz_sessions s ON s.id = sq.session_id
+                JOIN user_quizzes uq ON uq.quiz_id = s.quiz_id AND uq.user_id = s.user_id
+                WHERE s.user_id = $1 AND sq.is_correct IS NOT NULL
+                ORDER BY sq.question_id, sq.answered_at DESC NULLS LAST, sq.id DESC
+            ) latest
+            WHERE NOT latest.is_correct
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(WeeklyDigest {
+            questions_answered: week.questions_answered,
+            active_ms: week.active_ms,
+            accuracy: week.accuracy,
+            previous_accuracy: week.previous_accuracy,
+            weakest,
+            due_reviews,
+        })
+    }
+}
@@ -0,0 +1,270 @@
+use color_eyre::Result;
+use ulid::Ulid;
+
+use super::models::{GroupMember, GroupModel, GroupProgressRow, GroupQuiz, GroupSummary};
+use super::Db;
+
+impl Db {
+    /// Create a group led by `owner_id`. Returns the group's public id.
+    pub async fn create_group(&self, name: &str, owner_id: i32) -> Result<String> {
+        let public_id = Ulid::new().to_string();
+        let invite_code = Ulid::new().to_string();
+
+        sqlx::query!(
+            "INSERT INTO groups (public_id, invite_code, name, owner_id) VALUES ($1, $2, $3, $4)",
+            public_id,
+            invite_code,
+            name,
+            owner_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        tracing::info!("group {public_id} created by user={owner_id}");
+        Ok(public_id)
+    }
+
+    /// Groups the user leads, then groups they are a member of.
+    pub async fn groups_for_user(&self, user_id: i32) -> Result<Vec<GroupSummary>> {
+        let groups = sqlx::query_as!(
+            GroupSummary,
+            r#"
+            SELECT g.public_id, g.name, u.display_name AS owner_name,
+                   (g.owner_id = $1) AS "is_owner!",
+                   (SELECT COUNT(*) FROM group_members gm WHERE gm.group_id = g.id) AS "member_count!",
+                   (SELECT COUNT(*) FROM group_quizzes gq WHERE gq.group_id = g.id) AS "quiz_count!"
+            FROM groups g
+            JOIN users u ON u.id = g.owner_id
+            WHERE g.owner_id = $1
+               OR EXISTS (SELECT 1 FROM group_members gm WHERE gm.group_id = g.id AND gm.user_id = $1)
+            ORDER BY (g.owner_id = $1) DESC, g.name, g.id
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(groups)
+    }
+
+    pub async fn get_group(&self, public_id: &str) -> Result<Option<GroupModel>> {
+        let group = sqlx::query_as!(
+            GroupModel,
+            r#"
+            SELECT g.id, g.public_id, g.invite_code, g.name, g.owner_id, u.display_name AS owner_name
+            FROM groups g
+            JOIN users u ON u.id = g.owner_id
+            WHERE g.public_id = $1
+            "#,
+            public_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(group)
+    }
+
+    pub async fn get_group_by_invite(&self, invite_code: &str) -> Result<Option<GroupModel>> {
+        let group = sqlx::query_as!(
+            GroupModel,
+            r#"
+            SELECT g.id, g.public_id, g.invite_code, g.name, g.owner_id, u.display_name AS owner_name
+            FROM groups g
+            JOIN users u ON u.id = g.owner_id
+            WHERE g.invite_code = $1
+            "#,
+            invite_code
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(group)
+    }
+
+    pub async fn delete_group(&self, group_id: i32) -> Result<()> {
+        sqlx::query!("DELETE FROM groups WHERE id = $1", group_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn is_group_member(&self, group_id: i32, user_id: i32) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar!(
+            "SELECT EXISTS(SELECT 1 FROM group_members WHERE group_id = $1 AND user_id = $2)",
+            group_id,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .unwrap_or(false);
+
+        Ok(exists)
+    }
+
+    pub async fn group_members(&self, group_id: i32) -> Result<Vec<GroupMember>> {
+        let members = sqlx::query_as!(
+            GroupMember,
+            r#"
+            SELECT u.id AS user_id, u.display_name
+            FROM group_members gm
+            JOIN users u ON u.id = gm.user_id
+            WHERE gm.group_id = $1
+            ORDER BY u.display_name, u.id
+            "#,
+            group_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    /// Add a member (idempotent) and put the group's quizzes in their library.
+    pub async fn join_group(&self, group_id: i32, user_id: i32) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            "INSERT INTO group_members (group_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            group_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO user_quizzes (user_id, quiz_id)
+            SELECT $2, quiz_id FROM group_quizzes WHERE group_id = $1
+            ON CONFLICT DO NOTHING
+            "#,
+            group_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!("user={user_id} joined group={group_id}");
+        Ok(())
+    }
+
+    /// Remove a member. Quizzes stay in their library.
+    pub async fn remove_group_member(&self, group_id: i32, user_id: i32) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM group_members WHERE group_id = $1 AND user_id = $2",
+            group_id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn group_quizzes(&self, group_id: i32) -> Result<Vec<GroupQuiz>> {
+        let quizzes = sqlx::query_as!(
+            GroupQuiz,
+            r#"
+            SELECT q.id AS quiz_id, q.public_id AS "public_id!", q.name,
+                   (SELECT COUNT(*) FROM questions qu WHERE qu.quiz_id = q.id) AS "question_count!"
+            FROM group_quizzes gq
+            JOIN quizzes q ON q.id = gq.quiz_id
+            WHERE gq.group_id = $1
+            ORDER BY gq.assigned_at, q.id
+            "#,
+            group_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(quizzes)
+    }
+
+    /// Assign a quiz to a group (idempotent) and put it in every member's library.
+    pub async fn assign_group_quiz(&self, group_id: i32, quiz_id: i32) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        Self::assign_group_quiz_tx(&mut tx, group_id, quiz_id).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub(super) async fn assign_group_quiz_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        group_id: i32,
+        quiz_id: i32,
+    ) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO group_quizzes (group_id, quiz_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            group_id,
+            quiz_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO user_quizzes (user_id, quiz_id)
+            SELECT user_id, $2 FROM group_members WHERE group_id = $1
+            ON CONFLICT DO NOTHING
+            "#,
+            group_id,
+            quiz_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stop tracking a quiz in a group. It stays in the members' libraries.
+    pub async fn unassign_group_quiz(&self, group_id: i32, quiz_id: i32) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM group_quizzes WHERE group_id = $1 AND quiz_id = $2",
+            group_id,
+            quiz_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// First attempts of every member in every category of the group's quizzes, including
+    /// categories they have not answered yet. Uncategorized questions come last.
+    pub async fn get_group_progress(&self, group_id: i32) -> Result<Vec<GroupProgressRow>> {
+        let rows = sqlx::query_as!(
+            GroupProgressRow,
+            r#"
+            WITH answers AS (
+                SELECT s.user_id, sq.question_id, sq.is_correct, sq.active_ms
+                FROM session_questions sq
+                JOIN quiz_sessions s ON s.id = sq.session_id
+                JOIN group_quizzes gq ON gq.quiz_id = s.quiz_id AND gq.group_id = $1
+                JOIN group_members gm ON gm.user_id = s.user_id AND gm.group_id = $1
+                WHERE sq.attempt = 1 AND sq.is_correct IS NOT NULL
+            )
+            SELECT gm.user_id, q.quiz_id, q.category,
+                   COUNT(DISTINCT q.id) AS "questions!",
+                   COUNT(DISTINCT a.question_id) AS "seen!",
+                   COUNT(a.question_id) AS "answered!",
+                   COUNT(a.question_id) FILTER (WHERE a.is_correct) AS "correct!",
+                   COALESCE(SUM(a.active_ms), 0)::BIGINT AS "active_ms!"
+            FROM group_members gm
+            JOIN group_quizzes gq ON gq.group_id = gm.group_id
+            JOIN questions q ON q.quiz_id = gq.quiz_id
+            LEFT JOIN answers a ON a.user_id = gm.user_id AND a.question_id = q.id
+            WHERE gm.group_id = $1
+            GROUP BY gm.user_id, q.quiz_id, q.category
+            ORDER BY q.quiz_id, q.category NULLS LAST, gm.user_id
+            "#,
+            group_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
@@ -7,10 +7,18 @@ pub mod models;
 pub use models::*;
 
 // Internal modules
+mod activity;
 mod admin;
 mod answer;
+mod assignment;
+mod bookmark;
+mod challenge;
+mod collaborator;
+mod digest;
+mod group;
 pub mod helpers;
 mod migrations;
+mod plan;
 mod question;
 mod quiz;
 mod report;
@@ -1,5 +1,7 @@
 // Database model structs
 
+use serde::Serialize;
+
 #[derive(Clone)]
 pub struct AuthUser {
     pub id: i32,
@@ -17,6 +19,10 @@ pub struct Quiz {
     pub last_session_id: Option<i32>,
     pub is_shared: bool,
     pub is_owner: bool,
+    /// Owner or editor collaborator
+    pub can_edit: bool,
+    /// Owner or sharing manager collaborator
+    pub can_share: bool,
     pub owner_name: String,
     pub unique_asked: i64,
     pub total_correct: i64,
@@ -28,6 +34,9 @@ pub struct QuestionModel {
     pub question: String,
     pub is_multiple_choice: bool,
     pub options: Vec<QuestionOptionModel>,
+    pub explanation: Option<String>,
+    pub references: Vec<String>,
+    pub hints: Vec<String>,
 }
 
 pub struct QuestionOptionModel {
@@ -43,18 +52,39 @@ pub struct QuizSessionModel {
     pub name: String,
     pub question_count: Option<i32>,
     pub selection_mode: Option<String>,
+    pub study_mode: String,
 }
 
 pub struct AnswerModel {
     pub question: String,
     pub is_correct: bool,
     pub question_idx: i32,
+    pub attempt: i32,
     pub is_bookmarked: bool,
+    pub hints_used: i32,
+    pub active_ms: i32,
 }
 
-pub struct QuestionStatsModel {
-    pub question: String,
-    pub correct_answers: i64,
+/// One position in a session's question queue. Cram sessions can hold several
+/// attempts at the same question.
+pub struct SessionSlot {
+    pub question_id: i32,
+    pub question_number: i32,
+    pub attempt: i32,
+    pub is_answered: bool,
+}
+
+/// Result of grading and storing a submitted answer.
+#[derive(Debug, PartialEq)]
+pub enum AnswerOutcome {
+    /// The answer was graded and stored.
+    Recorded { is_correct: bool },
+    /// The question was already answered by a submission with the same idempotency key.
+    Replayed,
+    /// The question was already answered by a different submission (e.g. another tab).
+    AlreadyAnswered,
+    /// No option was selected, or one belongs to another question. Nothing was stored.
+    InvalidSelection,
 }
 
 pub struct SessionReportModel {
@@ -69,6 +99,18 @@ pub struct SessionReportModel {
     pub selection_mode: Option<String>,
 }
 
+/// An unfinished session of a quiz, most recently active first.
+pub struct InProgressSession {
+    pub id: i32,
+    pub name: String,
+    pub session_token: String,
+    pub answered_questions: i32,
+    pub total_questions: i32,
+    pub study_mode: String,
+    /// UTC, formatted for display
+    pub last_active: String,
+}
+
 pub struct CategoryStats {
     pub category: String,
     pub total: i64,
@@ -76,6 +118,40 @@ pub struct CategoryStats {
     pub accuracy: f64,
 }
 
+/// Answer accuracy at one self-reported confidence level ("unrated" when not given)
+pub struct ConfidenceStats {
+    pub confidence: String,
+    pub total: i64,
+    pub correct: i64,
+}
+
+/// A question the learner answered as "sure" but got wrong
+pub struct ConfidentWrongQuestion {
+    pub question_id: i32,
+    pub question: String,
+    pub category: Option<String>,
+    pub times_wrong: i64,
+}
+
+/// A question bookmarked by the user, with the quiz it belongs to
+pub struct BookmarkedQuestion {
+    pub quiz_id: i32,
+    pub quiz_public_id: String,
+    pub quiz_name: String,
+    pub question_id: i32,
+    pub question: String,
+    pub category: Option<String>,
+    pub note: Option<String>,
+}
+
+/// Owner-chosen session size settings of a quiz; `None` falls back to the instance defaults.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct QuizSessionSizes {
+    pub default_size: Option<i32>,
+    pub min_size: Option<i32>,
+    pub max_size: Option<i32>,
+}
+
 pub struct QuizOverallStats {
     pub total_questions: i64,
     pub unique_asked: i64,
@@ -106,6 +182,33 @@ pub struct SharedQuizInfo {
     pub import_count: i64,
 }
 
+pub struct ChallengeModel {
+    pub id: i32,
+    pub public_id: String,
+    pub quiz_id: i32,
+    pub quiz_public_id: String,
+    pub quiz_name: String,
+    /// Other users may join only when the quiz is shared.
+    pub is_joinable: bool,
+    pub created_by: i32,
+    pub creator_name: String,
+    pub question_ids: Vec<i32>,
+    pub shuffle_seed: i32,
+    pub study_mode: String,
+}
+
+/// One participant's session in a challenge, best first.
+pub struct ChallengeStanding {
+    pub session_id: i32,
+    pub user_id: i32,
+    pub display_name: String,
+    pub is_creator: bool,
+    pub correct: i32,
+    pub answered: i32,
+    pub total: i32,
+    pub is_complete: bool,
+}
+
 pub struct AdminUserStats {
     pub id: i32,
     pub display_name: String,
@@ -120,11 +223,40 @@ pub struct QuestionContext {
     pub quiz_name: String,
     pub quiz_public_id: String,
     pub question_id: i32,
+    pub attempt: i32,
     pub question: String,
     pub is_multiple_choice: bool,
     pub is_answered: bool,
     pub is_bookmarked: bool,
     pub questions_count: i32,
+    pub confidence: Option<String>,
+    pub self_grade: Option<String>,
+    pub note: Option<String>,
+    pub study_mode: String,
+    pub explanation: Option<String>,
+    pub references: Vec<String>,
+    pub hints: Vec<String>,
+    pub hints_used: i32,
+    pub template_values: Option<String>,
+}
+
+/// One answered question of a session, with the learner's selection and the explanations.
+pub struct ReviewItem {
+    pub question_idx: i32,
+    pub question_id: i32,
+    pub attempt: i32,
+    pub question: String,
+    pub category: Option<String>,
+    pub is_multiple_choice: bool,
+    pub is_correct: bool,
+    pub is_bookmarked: bool,
+    pub active_ms: i32,
+    pub hints_used: i32,
+    pub confidence: Option<String>,
+    pub self_grade: Option<String>,
+    pub explanation: Option<String>,
+    pub references: Vec<String>,
+    pub options: Vec<OptionWithSelection>,
 }
 
 /// Option with selection status for the current session (avoids separate selected_answers query)
@@ -135,3 +267,523 @@ pub struct OptionWithSelection {
     pub explanation: Option<String>,
     pub is_selected: bool,
 }
+
+/// How often each option of a question was picked on first attempts, across all learners
+pub struct AnswerDistribution {
+    pub responses: i32,
+    /// option id → number of responses that picked it
+    pub picks: std::collections::HashMap<i32, i32>,
+}
+
+/// One option of a question in a quiz owner's distractor analysis
+pub struct DistractorOption {
+    pub option: String,
+    pub is_answer: bool,
+    pub picks: i32,
+}
+
+/// A question with its options' pick counts, for spotting misleading distractors
+pub struct DistractorQuestion {
+    pub question_id: i32,
+    pub question: String,
+    pub category: Option<String>,
+    pub responses: i32,
+    pub options: Vec<DistractorOption>,
+}
+
+/// A learner's first attempt at a question, as input for item analysis
+pub struct ItemResponse {
+    pub user_id: i32,
+    pub question_id: i32,
+    pub is_correct: bool,
+    pub active_ms: i32,
+}
+
+/// Classical test statistics for one question, across every learner of a quiz
+pub struct ItemAnalysis {
+    pub question_id: i32,
+    pub question: String,
+    pub category: Option<String>,
+    pub responses: i32,
+    /// Share of first attempts answered correctly (higher is easier)
+    pub difficulty: Option<f64>,
+    /// Accuracy of the top scorers minus accuracy of the bottom scorers
+    pub discrimination: Option<f64>,
+    pub median_ms: Option<i64>,
+    /// Most-picked wrong option and its share of responses
+    pub top_distractor: Option<(String, f64)>,
+    pub likely_miskeyed: bool,
+}
+
+/// One of the learner's timed first attempts, as input for pace analytics
+pub struct PaceResponse {
+    pub session_id: i32,
+    pub session_name: String,
+    pub question_id: i32,
+    pub question: String,
+    pub category: Option<String>,
+    pub is_correct: bool,
+    pub active_ms: i32,
+}
+
+/// Median answer time of one session
+pub struct SessionPace {
+    pub session_id: i32,
+    pub session_name: String,
+    pub answered: i32,
+    pub median_ms: i64,
+}
+
+/// A question the learner spends the most time on
+pub struct SlowQuestion {
+    pub question_id: i32,
+    pub question: String,
+    pub category: Option<String>,
+    pub attempts: i32,
+    pub correct: i32,
+    pub median_ms: i64,
+}
+
+/// Median answer time of correct and incorrect answers in one category
+pub struct CategoryPace {
+    pub category: String,
+    pub correct: i32,
+    pub incorrect: i32,
+    pub correct_median_ms: Option<i64>,
+    pub incorrect_median_ms: Option<i64>,
+}
+
+/// Where a learner's time goes in one quiz
+pub struct PaceReport {
+    /// Oldest session first
+    pub sessions: Vec<SessionPace>,
+    pub slowest: Vec<SlowQuestion>,
+    pub categories: Vec<CategoryPace>,
+    /// Change of the session median per session (negative is getting faster)
+    pub trend_ms_per_session: Option<f64>,
+}
+
+/// One session picked for a side-by-side comparison
+pub struct ComparedSession {
+    pub id: i32,
+    pub name: String,
+    pub score: i32,
+    pub answered_questions: i32,
+    pub total_questions: i32,
+    pub is_complete: bool,
+    pub active_ms: i64,
+}
+
+/// Checked first attempts in one category of one compared session
+pub struct SessionCategoryStats {
+    pub session_id: i32,
+    pub category: String,
+    pub total: i64,
+    pub correct: i64,
+}
+
+/// Outcome of a first attempt in one compared session
+pub struct SessionOutcome {
+    pub session_id: i32,
+    pub question_id: i32,
+    pub question: String,
+    pub category: Option<String>,
+    pub is_correct: bool,
+}
+
+/// Accuracy of one category in each compared session (`None` when not asked)
+pub struct CategoryComparison {
+    pub category: String,
+    pub accuracy: Vec<Option<f64>>,
+}
+
+/// A question answered correctly in some compared sessions and incorrectly in others
+pub struct FlippedQuestion {
+    pub question_id: i32,
+    pub question: String,
+    pub category: Option<String>,
+    /// Outcome in each compared session (`None` when not asked)
+    pub outcomes: Vec<Option<bool>>,
+    /// Outcome in the first and the last session that asked it
+    pub was_correct: bool,
+    pub now_correct: bool,
+}
+
+/// Several sessions of one quiz side by side, oldest first
+pub struct SessionComparison {
+    pub sessions: Vec<ComparedSession>,
+    pub categories: Vec<CategoryComparison>,
+    pub flipped: Vec<FlippedQuestion>,
+}
+
+/// One of the learner's first attempts, as input for a readiness estimate
+pub struct ReadinessResponse {
+    pub question_id: i32,
+    pub category: Option<String>,
+    pub is_correct: bool,
+    /// Time since the answer, in fractional days
+    pub days_ago: f64,
+}
+
+/// Number of questions of a quiz in one category (`None` is uncategorized)
+pub struct CategorySize {
+    pub category: Option<String>,
+    pub questions: i64,
+}
+
+/// A category that pulls the readiness estimate down
+pub struct WeakCategory {
+    pub category: String,
+    /// Estimated share of the category answered correctly
+    pub estimate: f64,
+    /// Share of the category's questions answered at least once
+    pub coverage: f64,
+}
+
+/// How likely a learner is to pass a quiz if it were the exam today
+pub struct Readiness {
+    /// Estimated share of the whole quiz answered correctly
+    pub score: f64,
+    /// Score needed to pass, from the learner's study plan or the default
+    pub pass_mark: f64,
+    /// Lower and upper bound of the confidence band around `score`
+    pub low: f64,
+    pub high: f64,
+    /// Share of the quiz's questions answered at least once
+    pub coverage: f64,
+    /// Accuracy over the most recent answers
+    pub recent_accuracy: f64,
+    pub days_since_practice: i32,
+    /// Weakest categories first
+    pub holding_back: Vec<WeakCategory>,
+}
+
+/// One answered question in a learner's exported answer log
+#[derive(Serialize)]
+pub struct AnswerLogEntry {
+    pub session_id: i32,
+    pub session_name: String,
+    pub study_mode: String,
+    /// 1-based position in the session
+    pub question_number: i32,
+    pub attempt: i32,
+    pub question_id: i32,
+    pub question: String,
+    pub category: Option<String>,
+    pub selected: Vec<String>,
+    pub correct_options: Vec<String>,
+    pub is_correct: bool,
+    pub confidence: Option<String>,
+    pub self_grade: Option<String>,
+    pub hints_used: i32,
+    pub active_ms: i32,
+    /// UTC, ISO 8601
+    pub answered_at: Option<String>,
+}
+
+/// A learner's first-attempt totals in one category, for export
+#[derive(Serialize)]
+pub struct CategoryExport {
+    pub category: Option<String>,
+    pub questions: i64,
+    pub seen: i64,
+    pub answered: i64,
+    pub correct: i64,
+    pub accuracy: Option<f64>,
+    pub active_ms: i64,
+}
+
+/// Target exam date (`YYYY-MM-DD`) and pass threshold (percent) a learner set for a quiz
+pub struct StudyPlanSettings {
+    pub exam_date: String,
+    pub pass_threshold: i32,
+}
+
+/// A learner's study plan for a quiz with their progress so far, as input for the
+/// daily recommendation. Days are UTC.
+pub struct StudyPlanInputs {
+    pub exam_date: String,
+    /// 0 is exam day
+    pub days_left: i32,
+    pub pass_threshold: i32,
+    /// Daily question count recommended when the plan was set (0 until computed)
+    pub planned_daily: i32,
+    /// Days since the plan was set, 0 on the day it was set
+    pub days_elapsed: i32,
+    /// Questions of the quiz never answered
+    pub unseen: i64,
+    /// Questions whose latest answer was incorrect
+    pub to_review: i64,
+    pub answered_today: i64,
+    pub answered_since_start: i64,
+}
+
+/// What to study each day until the exam, and whether the learner keeps up
+pub struct StudyPlan {
+    pub exam_date: String,
+    pub days_left: i32,
+    pub pass_threshold: i32,
+    /// Questions to answer per day, split by selection mode
+    pub unseen_per_day: i32,
+    pub review_per_day: i32,
+    pub mixed_per_day: i32,
+    pub daily_target: i32,
+    pub answered_today: i64,
+    /// Answers the plan expected by the end of yesterday
+    pub expected_by_now: i64,
+    pub answered_since_start: i64,
+    pub behind: bool,
+    /// Weak categories to focus the mixed practice on
+    pub focus: Vec<String>,
+}
+
+/// A user who opted in to the weekly digest and has not received one for a week
+#[derive(Clone, Debug, PartialEq)]
+pub struct DigestRecipient {
+    pub user_id: i32,
+    pub email: String,
+    pub display_name: String,
+    pub locale: String,
+}
+
+/// A weak category in the weekly digest
+#[derive(Clone, Debug, PartialEq)]
+pub struct DigestCategory {
+    pub quiz_name: String,
+    pub category: String,
+    pub correct: i64,
+    pub total: i64,
+}
+
+/// A user's progress over the last seven days, for the weekly digest email
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WeeklyDigest {
+    pub questions_answered: i64,
+    pub active_ms: i64,
+    /// First-attempt accuracy in percent this week and the week before
+    pub accuracy: Option<f64>,
+    pub previous_accuracy: Option<f64>,
+    /// Lowest accuracy first, over the last four weeks
+    pub weakest: Vec<DigestCategory>,
+    /// Questions whose latest answer was incorrect
+    pub due_reviews: i64,
+}
+
+/// Questions answered and time studied on one day (UTC)
+pub struct DailyActivity {
+    /// 0 is today
+    pub days_ago: i32,
+    pub date_label: String,
+    /// ISO weekday (1 = Monday)
+    pub weekday: i32,
+    pub questions: i32,
+    pub active_ms: i64,
+}
+
+/// A daily target of questions answered or minutes studied
+pub struct DailyGoal {
+    /// "questions" or "minutes"
+    pub kind: String,
+    pub target: i32,
+}
+
+/// Study activity of a user across all quizzes
+pub struct ActivitySummary {
+    /// Every day of the requested window, oldest first and ending today
+    pub days: Vec<DailyActivity>,
+    pub current_streak: i32,
+    pub longest_streak: i32,
+    pub goal: Option<DailyGoal>,
+}
+
+/// A group of learners led by its owner (the instructor)
+pub struct GroupModel {
+    pub id: i32,
+    pub public_id: String,
+    /// Secret part of the link members join with
+    pub invite_code: String,
+    pub name: String,
+    pub owner_id: i32,
+    pub owner_name: String,
+}
+
+/// A group in the list of groups a user leads or belongs to
+pub struct GroupSummary {
+    pub public_id: String,
+    pub name: String,
+    pub owner_name: String,
+    pub is_owner: bool,
+    pub member_count: i64,
+    pub quiz_count: i64,
+}
+
+pub struct GroupMember {
+    pub user_id: i32,
+    pub display_name: String,
+}
+
+/// A quiz assigned to a group
+pub struct GroupQuiz {
+    pub quiz_id: i32,
+    pub public_id: String,
+    pub name: String,
+    pub question_count: i64,
+}
+
+/// First attempts of one member in one category of a group quiz
+pub struct GroupProgressRow {
+    pub user_id: i32,
+    pub quiz_id: i32,
+    pub category: Option<String>,
+    pub questions: i64,
+    /// Questions answered at least once
+    pub seen: i64,
+    pub answered: i64,
+    pub correct: i64,
+    pub active_ms: i64,
+}
+
+/// Coverage and accuracy of one member, in one category or a whole quiz
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ProgressCell {
+    pub questions: i64,
+    pub seen: i64,
+    pub answered: i64,
+    pub correct: i64,
+}
+
+impl ProgressCell {
+    /// Share of first attempts answered correctly, `None` before any answer
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.answered > 0).then(|| self.correct as f64 / self.answered as f64)
+    }
+}
+
+pub struct MemberProgress {
+    pub user_id: i32,
+    pub display_name: String,
+    /// In the order of `GroupQuizProgress::categories`
+    pub categories: Vec<ProgressCell>,
+    pub total: ProgressCell,
+    pub active_ms: i64,
+}
+
+/// The progress table of one group quiz: a row per member, a column per category
+pub struct GroupQuizProgress {
+    pub quiz: GroupQuiz,
+    /// `None` is uncategorized
+    pub categories: Vec<Option<String>>,
+    pub members: Vec<MemberProgress>,
+}
+
+/// Who an assignment is for
+pub enum AssignmentTarget {
+    /// Every current member of a group
+    Group(i32),
+    /// A single learner
+    User(i32),
+}
+
+/// What an instructor asks for when assigning a quiz
+pub struct AssignmentSettings {
+    pub quiz_id: i32,
+    /// ISO date (YYYY-MM-DD), UTC
+    pub due_date: String,
+    pub question_count: i32,
+    pub selection_mode: String,
+    /// Percent of first attempts answered correctly
+    pub target_score: i32,
+}
+
+pub struct AssignmentModel {
+    pub id: i32,
+    pub public_id: String,
+    pub quiz_id: i32,
+    pub quiz_public_id: String,
+    pub quiz_name: String,
+    pub created_by: i32,
+    pub creator_name: String,
+    pub group_id: Option<i32>,
+    pub group_name: Option<String>,
+    pub assignee_name: Option<String>,
+    pub due_date: String,
+    /// Negative once the due date has passed
+    pub days_left: i32,
+    pub question_count: i32,
+    pub selection_mode: String,
+    pub target_score: i32,
+}
+
+/// One learner's sessions started from an assignment
+pub struct AssignmentLearner {
+    pub assignment_id: i32,
+    pub user_id: i32,
+    pub display_name: String,
+    pub attempts: i64,
+    /// Best first-attempt score of a finished session, in percent
+    pub best_score: Option<i32>,
+    /// Day (UTC) the first session meeting the assignment was finished
+    pub completed_on: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AssignmentStatus {
+    Completed { late: bool },
+    Overdue,
+    Pending,
+}
+
+/// How a user may work on a quiz as its author.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuizAccess {
+    Owner,
+    /// Renames the quiz and changes its session sizes. Questions themselves have no editor
+    /// in the app, for the owner either.
+    Editor,
+    /// Shares and unshares the quiz
+    SharingManager,
+}
+
+impl QuizAccess {
+    pub fn can_edit(self) -> bool {
+        matches!(self, QuizAccess::Owner | QuizAccess::Editor)
+    }
+
+    pub fn can_share(self) -> bool {
+        matches!(self, QuizAccess::Owner | QuizAccess::SharingManager)
+    }
+}
+
+pub struct QuizCollaborator {
+    pub user_id: i32,
+    pub display_name: String,
+    /// One of `names::COLLABORATOR_ROLES`
+    pub role: String,
+}
+
+/// A pending collaborator invitation, as the quiz owner sees it
+pub struct QuizInvitation {
+    pub id: i32,
+    pub email: String,
+    /// One of `names::COLLABORATOR_ROLES`
+    pub role: String,
+}
+
+/// A pending collaborator invitation, as the invitee sees it
+pub struct ReceivedInvitation {
+    pub id: i32,
+    pub quiz_name: String,
+    pub invited_by: String,
+    /// One of `names::COLLABORATOR_ROLES`
+    pub role: String,
+}
+
+/// One entry of a quiz's change history
+pub struct QuizChange {
+    /// `None` once the user who made the change is deleted
+    pub display_name: Option<String>,
+    pub action: String,
+    pub detail: String,
+    /// `YYYY-MM-DD HH:MM` (UTC)
+    pub changed_at: String,
+}
@@ -0,0 +1,130 @@
+use color_eyre::Result;
+
+use super::models::{StudyPlanInputs, StudyPlanSettings};
+use super::Db;
+
+impl Db {
+    /// A learner's study plan for a quiz with their progress, or `None` when no exam date
+    /// is set. Days are UTC.
+    pub async fn get_study_plan_inputs(
+        &self,
+        quiz_id: i32,
+        user_id: i32,
+    ) -> Result<Option<StudyPlanInputs>> {
+        let inputs = sqlx::query_as!(
+            StudyPlanInputs,
+            r#"
+            WITH answers AS (
+                SELECT sq.question_id, sq.is_correct, sq.answered_at, sq.id
+                FROM session_questions sq
+                JOIN quiz_sessions s ON s.id = sq.session_id
+                WHERE s.quiz_id = $1 AND s.user_id = $2 AND sq.is_correct IS NOT NULL
+            ),
+            latest AS (
+                SELECT DISTINCT ON (question_id) question_id, is_correct
+                FROM answers
+                ORDER BY question_id, answered_at DESC NULLS LAST, id DESC
+            )
+            SELECT TO_CHAR(uq.exam_date, 'YYYY-MM-DD') AS "exam_date!",
+                   (uq.exam_date - (NOW() AT TIME ZONE 'UTC')::DATE) AS "days_left!",
+                   COALESCE(uq.pass_threshold, $3) AS "pass_threshold!",
+                   COALESCE(uq.plan_daily_target, 0) AS "planned_daily!",
+                   ((NOW() AT TIME ZONE 'UTC')::DATE - uq.plan_started_on) AS "days_elapsed!",
+                   (SELECT COUNT(*) FROM questions q
+                    WHERE q.quiz_id = $1
+                      AND NOT EXISTS (SELECT 1 FROM answers a WHERE a.question_id = q.id)) AS "unseen!",
+                   (SELECT COUNT(*) FROM latest l
+                    JOIN questions q ON q.id = l.question_id AND q.quiz_id = $1
+                    WHERE NOT l.is_correct) AS "to_review!",
+                   (SELECT COUNT(*) FROM answers a
+                    WHERE (a.answered_at AT TIME ZONE 'UTC')::DATE = (NOW() AT TIME ZONE 'UTC')::DATE) AS "answered_today!",
+                   (SELECT COUNT(*) FROM answers a
+                    WHERE (a.answered_at AT TIME ZONE 'UTC')::DATE >= uq.plan_started_on) AS "answered_since_start!"
+            FROM user_quizzes uq
+            WHERE uq.quiz_id = $1 AND uq.user_id = $2
+              AND uq.exam_date IS NOT NULL AND uq.plan_started_on IS NOT NULL
+            "#,
+            quiz_id,
+            user_id,
+            crate::names::DEFAULT_PASS_THRESHOLD
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(inputs)
+    }
+
+    /// Whether an ISO date is before today (UTC).
+    pub async fn is_past_date(&self, date: &str) -> Result<bool> {
+        let is_past: bool = sqlx::query_scalar!(
+            r#"SELECT $1::TEXT::DATE < (NOW() AT TIME ZONE 'UTC')::DATE AS "is_past!""#,
+            date
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(is_past)
+    }
+
+    /// Set (starting the plan today) or clear (with `None`) a learner's study plan for a
+    /// quiz. Returns false when the quiz is not in the learner's library.
+    pub async fn set_study_plan(
+        &self,
+        quiz_id: i32,
+        user_id: i32,
+        settings: Option<&StudyPlanSettings>,
+    ) -> Result<bool> {
+        let Some(settings) = settings else {
+            let result = sqlx::query!(
+                r#"
+                UPDATE user_quizzes
+                SET exam_date = NULL, pass_threshold = NULL, plan_daily_target = NULL,
+                    plan_started_on = NULL
+                WHERE quiz_id = $1 AND user_id = $2
+                "#,
+                quiz_id,
+                user_id
+            )
+            .execute(&self.pool)
+            .await?;
+            return Ok(result.rows_affected() > 0);
+        };
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE user_quizzes
+            SET exam_date = $3::TEXT::DATE, pass_threshold = $4, plan_daily_target = NULL,
+                plan_started_on = (NOW() AT TIME ZONE 'UTC')::DATE
+            WHERE quiz_id = $1 AND user_id = $2
+            "#,
+            quiz_id,
+            user_id,
+            settings.exam_date,
+            settings.pass_threshold
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remember the daily question count recommended when the plan was set, to measure
+    /// progress against it.
+    pub async fn set_plan_daily_target(
+        &self,
+        quiz_id: i32,
+        user_id: i32,
+        daily_target: i32,
+    ) -> Result<()> {
+        sqlx::query!(
+            "UPDATE user_quizzes SET plan_daily_target = $3 WHERE quiz_id = $1 AND user_id = $2",
+            quiz_id,
+            user_id,
+            daily_target
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,15 +1,19 @@
 use color_eyre::{eyre::OptionExt, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 
 use super::models::{
     OptionWithSelection, QuestionContext, QuestionModel, QuestionOptionModel,
-    QuizCategoryOverallStats, QuizOverallStats,
+    QuizCategoryOverallStats, QuizOverallStats, ReviewItem, SessionSlot,
 };
 use super::Db;
+use crate::template;
 
 impl Db {
     pub async fn get_question(&self, question_id: i32) -> Result<QuestionModel> {
         let row = sqlx::query!(
-            r#"SELECT question, is_multiple_choice AS "is_multiple_choice!" FROM questions WHERE id = $1"#,
+            r#"SELECT question, is_multiple_choice AS "is_multiple_choice!", explanation, reference_links, hints FROM questions WHERE id = $1"#,
             question_id
         )
         .fetch_optional(&self.pool)
@@ -21,7 +25,7 @@ impl Db {
 
         let options: Vec<QuestionOptionModel> = sqlx::query_as!(
             QuestionOptionModel,
-            "SELECT id, is_answer, option, explanation FROM options WHERE question_id = $1",
+            "SELECT id, is_answer, option, explanation FROM options WHERE question_id = $1 ORDER BY id",
             question_id
         )
         .fetch_all(&self.pool)
@@ -31,6 +35,9 @@ impl Db {
             question,
             is_multiple_choice,
             options,
+            explanation: row.explanation,
+            references: row.reference_links,
+            hints: row.hints,
         })
     }
 
@@ -71,6 +78,87 @@ impl Db {
         Ok(question_id)
     }
 
+    /// Position (question_number) of the latest attempt at a question within a session.
+    pub async fn get_question_idx(&self, session_id: i32, question_id: i32) -> Result<i32> {
+        let idx: i32 = sqlx::query_scalar!(
+            "SELECT question_number FROM session_questions WHERE session_id = $1 AND question_id = $2 ORDER BY attempt DESC LIMIT 1",
+            session_id,
+            question_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_eyre("question is not part of this session")?;
+
+        Ok(idx)
+    }
+
+    pub async fn get_session_slot(&self, session_id: i32, idx: i32) -> Result<SessionSlot> {
+        let slot = sqlx::query_as!(
+            SessionSlot,
+            r#"
+            SELECT question_id, question_number, attempt, (is_correct IS NOT NULL) AS "is_answered!"
+            FROM session_questions
+            WHERE session_id = $1 AND question_number = $2
+            "#,
+            session_id,
+            idx
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_eyre("no question at this position")?;
+
+        Ok(slot)
+    }
+
+    /// The answered attempt a submission refers to: the one recorded with its idempotency key,
+    /// otherwise the latest answered attempt.
+    pub async fn get_answered_slot(
+        &self,
+        session_id: i32,
+        question_id: i32,
+        idempotency_key: &str,
+    ) -> Result<SessionSlot> {
+        let slot = sqlx::query_as!(
+            SessionSlot,
+            r#"
+            SELECT question_id, question_number, attempt, TRUE AS "is_answered!"
+            FROM session_questions
+            WHERE session_id = $1 AND question_id = $2 AND is_correct IS NOT NULL
+            ORDER BY answer_key IS NOT DISTINCT FROM $3 DESC, attempt DESC
+            LIMIT 1
+            "#,
+            session_id,
+            question_id,
+            idempotency_key
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_eyre("question has not been answered in this session")?;
+
+        Ok(slot)
+    }
+
+    /// Number of distinct questions in a session (first attempts only).
+    pub async fn distinct_questions_for_session(&self, session_id: i32) -> Result<i32> {
+        let count: i32 = sqlx::query_scalar!(
+            r#"SELECT COUNT(*)::INT AS "count!" FROM session_questions WHERE session_id = $1 AND attempt = 1"#,
+            session_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    pub async fn question_quiz_id(&self, question_id: i32) -> Result<i32> {
+        let quiz_id: i32 =
+            sqlx::query_scalar!("SELECT quiz_id FROM questions WHERE id = $1", question_id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(quiz_id)
+    }
+
     pub async fn get_available_categories(&self, quiz_id: i32) -> Result<Vec<String>> {
         let categories: Vec<String> = sqlx::query_scalar!(
             r#"SELECT DISTINCT category AS "category!" FROM questions WHERE quiz_id = $1 AND category IS NOT NULL ORDER BY category"#,
@@ -104,7 +192,7 @@ impl Db {
                 COUNT(*) AS "total_answered!"
             FROM session_questions sq
             JOIN quiz_sessions s ON s.id = sq.session_id
-            WHERE s.quiz_id = $1 AND sq.is_correct IS NOT NULL
+            WHERE s.quiz_id = $1 AND sq.attempt = 1 AND sq.is_correct IS NOT NULL
             "#,
             quiz_id
         )
@@ -128,7 +216,7 @@ impl Db {
                 COALESCE(SUM(CASE WHEN sq.is_correct THEN 1 ELSE 0 END), 0) AS "total_correct!",
                 COUNT(CASE WHEN sq.is_correct IS NOT NULL THEN 1 END) AS "total_answered!"
             FROM questions q
-            LEFT JOIN session_questions sq ON sq.question_id = q.id
+            LEFT JOIN session_questions sq ON sq.question_id = q.id AND sq.attempt = 1
             WHERE q.quiz_id = $1 AND q.category IS NOT NULL
             GROUP BY q.category
             ORDER BY q.category
@@ -159,19 +247,40 @@ impl Db {
         quiz_id: i32,
         question_idx: i32,
     ) -> Result<QuestionContext> {
-        let ctx = sqlx::query_as!(
+        let mut ctx = sqlx::query_as!(
             QuestionContext,
             r#"
             SELECT
                 qz.name AS quiz_name,
                 qz.public_id AS "quiz_public_id!",
                 sq.question_id,
+                sq.attempt,
                 q.question,
                 q.is_multiple_choice AS "is_multiple_choice!",
                 (sq.is_correct IS NOT NULL) AS "is_answered!",
-                sq.is_bookmarked AS "is_bookmarked!",
-                (SELECT COUNT(*)::INT FROM session_questions WHERE session_id = $1) AS "questions_count!"
+                EXISTS(
+                    SELECT 1 FROM user_bookmarks ub
+                    WHERE ub.user_id = s.user_id AND ub.question_id = sq.question_id
+                ) AS "is_bookmarked!",
+                (SELECT COUNT(*)::INT FROM session_questions WHERE session_id = $1) AS "questions_count!",
+                (SELECT ua.confidence FROM user_answers ua
+                 WHERE ua.session_id = $1 AND ua.question_id = sq.question_id
+                   AND ua.attempt = sq.attempt
+                 LIMIT 1) AS confidence,
+                (SELECT ua.self_grade FROM user_answers ua
+                 WHERE ua.session_id = $1 AND ua.question_id = sq.question_id
+                   AND ua.attempt = sq.attempt
+                 LIMIT 1) AS self_grade,
+                (SELECT n.body FROM question_notes n
+                 WHERE n.user_id = s.user_id AND n.question_id = sq.question_id) AS note,
+                s.study_mode,
+                q.explanation,
+                q.reference_links AS references,
+                q.hints,
+                sq.hints_used,
+                sq.template_values
             FROM session_questions sq
+            JOIN quiz_sessions s ON s.id = sq.session_id
             JOIN questions q ON q.id = sq.question_id
             JOIN quizzes qz ON qz.id = $2
             WHERE sq.session_id = $1 AND sq.question_number = $3
@@ -183,25 +292,83 @@ impl Db {
         .fetch_one(&self.pool)
         .await?;
 
+        if let Some(values) = template::parse_values(ctx.template_values.as_deref()) {
+            ctx.question = template::render(&ctx.question, &values);
+            ctx.explanation = ctx.explanation.map(|e| template::render(&e, &values));
+            ctx.hints = ctx
+                .hints
+                .iter()
+                .map(|h| template::render(h, &values))
+                .collect();
+        }
+
         Ok(ctx)
     }
 
-    /// Options with per-session selection status (replaces separate get_question + get_selected_answers)
+    /// Options with per-session selection status (replaces separate get_question + get_selected_answers),
+    /// in the session's shuffled order.
     pub async fn get_options_with_selection(
         &self,
         session_id: i32,
         question_id: i32,
+        attempt: i32,
     ) -> Result<Vec<OptionWithSelection>> {
-        let options = sqlx::query_as!(
-            OptionWithSelection,
+        let rows = sqlx::query!(
             r#"
-            SELECT o.id, o.is_answer, o.option, o.explanation,
+            SELECT o.id, o.is_answer, o.option, o.explanation, o.is_pinned,
                    EXISTS(
                        SELECT 1 FROM user_answers ua
                        WHERE ua.option_id = o.id AND ua.session_id = $1 AND ua.question_id = $2
-                   ) AS "is_selected!"
+                         AND ua.attempt = $3
+                   ) AS "is_selected!",
+                   COALESCE(s.shuffle_seed, 0) AS "shuffle_seed!",
+                   (SELECT sq.template_values FROM session_questions sq
+                    WHERE sq.session_id = $1 AND sq.question_id = $2 AND sq.attempt = $3) AS template_values
             FROM options o
+            JOIN quiz_sessions s ON s.id = $1
             WHERE o.question_id = $2
+            ORDER BY o.id
+            "#,
+            session_id,
+            question_id,
+            attempt
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let shuffle_seed = rows.first().map_or(0, |r| r.shuffle_seed);
+        let values =
+            template::parse_values(rows.first().and_then(|r| r.template_values.as_deref()));
+        let options = shuffle_options(rows, |r| r.is_pinned, shuffle_seed, question_id)
+            .into_iter()
+            .map(|r| OptionWithSelection {
+                id: r.id,
+                is_answer: r.is_answer,
+                option: render_template(r.option, values.as_ref()),
+                explanation: r.explanation.map(|e| render_template(e, values.as_ref())),
+                is_selected: r.is_selected,
+            })
+            .collect();
+
+        Ok(options)
+    }
+
+    /// Options in the session's shuffled order (without selection status).
+    pub async fn get_session_options(
+        &self,
+        session_id: i32,
+        question_id: i32,
+    ) -> Result<Vec<QuestionOptionModel>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT o.id, o.is_answer, o.option, o.explanation, o.is_pinned,
+                   COALESCE(s.shuffle_seed, 0) AS "shuffle_seed!",
+                   (SELECT sq.template_values FROM session_questions sq
+                    WHERE sq.session_id = $1 AND sq.question_id = $2 LIMIT 1) AS template_values
+            FROM options o
+            JOIN quiz_sessions s ON s.id = $1
+            WHERE o.question_id = $2
+            ORDER BY o.id
             "#,
             session_id,
             question_id
@@ -209,14 +376,138 @@ impl Db {
         .fetch_all(&self.pool)
         .await?;
 
+        let shuffle_seed = rows.first().map_or(0, |r| r.shuffle_seed);
+        let values =
+            template::parse_values(rows.first().and_then(|r| r.template_values.as_deref()));
+        let options = shuffle_options(rows, |r| r.is_pinned, shuffle_seed, question_id)
+            .into_iter()
+            .map(|r| QuestionOptionModel {
+                id: r.id,
+                is_answer: r.is_answer,
+                option: render_template(r.option, values.as_ref()),
+                explanation: r.explanation.map(|e| render_template(e, values.as_ref())),
+            })
+            .collect();
+
         Ok(options)
     }
 
-    /// Fetch options only (without selection status)
+    /// Every answered question of a session in order, with options in the session's
+    /// shuffled order and the learner's selection for that attempt.
+    pub async fn get_session_review(&self, session_id: i32) -> Result<Vec<ReviewItem>> {
+        let slots = sqlx::query!(
+            r#"
+            SELECT
+                sq.question_number,
+                sq.question_id,
+                sq.attempt,
+                q.question,
+                q.category,
+                q.is_multiple_choice AS "is_multiple_choice!",
+                sq.is_correct AS "is_correct!",
+                EXISTS(
+                    SELECT 1 FROM user_bookmarks ub
+                    WHERE ub.user_id = s.user_id AND ub.question_id = sq.question_id
+                ) AS "is_bookmarked!",
+                sq.active_ms,
+                sq.hints_used,
+                (SELECT ua.confidence FROM user_answers ua
+                 WHERE ua.session_id = $1 AND ua.question_id = sq.question_id
+                   AND ua.attempt = sq.attempt
+                 LIMIT 1) AS confidence,
+                (SELECT ua.self_grade FROM user_answers ua
+                 WHERE ua.session_id = $1 AND ua.question_id = sq.question_id
+                   AND ua.attempt = sq.attempt
+                 LIMIT 1) AS self_grade,
+                q.explanation,
+                q.reference_links,
+                sq.template_values,
+                COALESCE(s.shuffle_seed, 0) AS "shuffle_seed!"
+            FROM session_questions sq
+            JOIN quiz_sessions s ON s.id = sq.session_id
+            JOIN questions q ON q.id = sq.question_id
+            WHERE sq.session_id = $1 AND sq.is_correct IS NOT NULL
+            ORDER BY sq.question_number
+            "#,
+            session_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let option_rows = sqlx::query!(
+            r#"
+            SELECT sq.question_number, o.id, o.is_answer, o.option, o.explanation, o.is_pinned,
+                   EXISTS(
+                       SELECT 1 FROM user_answers ua
+                       WHERE ua.option_id = o.id AND ua.session_id = $1
+                         AND ua.question_id = sq.question_id AND ua.attempt = sq.attempt
+                   ) AS "is_selected!"
+            FROM session_questions sq
+            JOIN options o ON o.question_id = sq.question_id
+            WHERE sq.session_id = $1 AND sq.is_correct IS NOT NULL
+            ORDER BY sq.question_number, o.id
+            "#,
+            session_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut options_by_slot: std::collections::HashMap<i32, Vec<_>> =
+            std::collections::HashMap::new();
+        for row in option_rows {
+            options_by_slot
+                .entry(row.question_number)
+                .or_default()
+                .push(row);
+        }
+
+        let items = slots
+            .into_iter()
+            .map(|slot| {
+                let rows = options_by_slot
+                    .remove(&slot.question_number)
+                    .unwrap_or_default();
+                let values = template::parse_values(slot.template_values.as_deref());
+                let values = values.as_ref();
+                let options =
+                    shuffle_options(rows, |r| r.is_pinned, slot.shuffle_seed, slot.question_id)
+                        .into_iter()
+                        .map(|r| OptionWithSelection {
+                            id: r.id,
+                            is_answer: r.is_answer,
+                            option: render_template(r.option, values),
+                            explanation: r.explanation.map(|e| render_template(e, values)),
+                            is_selected: r.is_selected,
+                        })
+                        .collect();
+                ReviewItem {
+                    question_idx: slot.question_number,
+                    question_id: slot.question_id,
+                    attempt: slot.attempt,
+                    question: render_template(slot.question, values),
+                    category: slot.category,
+                    is_multiple_choice: slot.is_multiple_choice,
+                    is_correct: slot.is_correct,
+                    is_bookmarked: slot.is_bookmarked,
+                    active_ms: slot.active_ms,
+                    hints_used: slot.hints_used,
+                    confidence: slot.confidence,
+                    self_grade: slot.self_grade,
+                    explanation: slot.explanation.map(|e| render_template(e, values)),
+                    references: slot.reference_links,
+                    options,
+                }
+            })
+            .collect();
+
+        Ok(items)
+    }
+
+    /// Fetch options only (without selection status), in authored order
     pub async fn get_options(&self, question_id: i32) -> Result<Vec<QuestionOptionModel>> {
         let options = sqlx::query_as!(
             QuestionOptionModel,
-            "SELECT id, is_answer, option, explanation FROM options WHERE question_id = $1",
+            "SELECT id, is_answer, option, explanation FROM options WHERE question_id = $1 ORDER BY id",
             question_id
         )
         .fetch_all(&self.pool)
@@ -225,3 +516,48 @@ impl Db {
         Ok(options)
     }
 }
+
+/// Fill in a templated question's text; plain questions pass through untouched.
+pub(super) fn render_template(text: String, values: Option<&template::TemplateValues>) -> String {
+    match values {
+        Some(values) => template::render(&text, values),
+        None => text,
+    }
+}
+
+/// Shuffle a question's options deterministically from the session seed and question id,
+/// so the order is stable on resume and revisit. Pinned options (e.g. "All of the above")
+/// keep their authored position; only the unpinned ones move around them.
+fn shuffle_options<T>(
+    options: Vec<T>,
+    is_pinned: impl Fn(&T) -> bool,
+    shuffle_seed: i32,
+    question_id: i32,
+) -> Vec<T> {
+    let seed = (u64::from(shuffle_seed as u32) << 32) | u64::from(question_id as u32);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let len = options.len();
+    let mut pinned = Vec::new();
+    let mut free = Vec::new();
+    for (idx, opt) in options.into_iter().enumerate() {
+        if is_pinned(&opt) {
+            pinned.push((idx, opt));
+        } else {
+            free.push(opt);
+        }
+    }
+    free.shuffle(&mut rng);
+
+    let mut pinned = pinned.into_iter().peekable();
+    let mut free = free.into_iter();
+    (0..len)
+        .filter_map(|pos| {
+            if pinned.peek().is_some_and(|(idx, _)| *idx == pos) {
+                pinned.next().map(|(_, opt)| opt)
+            } else {
+                free.next()
+            }
+        })
+        .collect()
+}
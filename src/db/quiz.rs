@@ -1,9 +1,13 @@
-use color_eyre::{eyre::OptionExt, Result};
+use color_eyre::{
+    eyre::{eyre, OptionExt},
+    Result,
+};
 use ulid::Ulid;
 
-use super::models::{Quiz, SharedQuizInfo};
+use super::models::{Quiz, QuizSessionSizes, SharedQuizInfo};
 use super::Db;
-use crate::models::Questions;
+use crate::models::{Question, Questions};
+use crate::template;
 
 impl Db {
     /// Insert a quiz with all its questions and options atomically in a transaction.
@@ -15,6 +19,11 @@ impl Db {
         questions: Questions,
         user_id: i32,
     ) -> Result<String> {
+        for question in &questions {
+            validate_template(question)
+                .map_err(|e| eyre!("question \"{}\": {e}", question.question))?;
+        }
+
         let public_id = Ulid::new().to_string();
         let mut tx = self.pool.begin().await?;
 
@@ -48,16 +57,28 @@ impl Db {
         let q_categories: Vec<Option<String>> =
             questions.iter().map(|q| q.category.clone()).collect();
         let q_multiple: Vec<bool> = questions.iter().map(|q| q.is_multiple_choice).collect();
+        let q_explanations: Vec<Option<String>> =
+            questions.iter().map(|q| q.explanation.clone()).collect();
+        let q_variables: Vec<Option<String>> = questions
+            .iter()
+            .map(|q| {
+                (!q.variables.is_empty())
+                    .then(|| serde_json::to_string(&q.variables))
+                    .transpose()
+            })
+            .collect::<serde_json::Result<_>>()?;
         let q_quiz_ids: Vec<i32> = vec![quiz_id; questions.len()];
 
         sqlx::query!(
             r#"
-            INSERT INTO questions (question, category, is_multiple_choice, quiz_id)
-            SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[], $3::BOOL[], $4::INT4[])
+            INSERT INTO questions (question, category, is_multiple_choice, explanation, template_variables, quiz_id)
+            SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[], $3::BOOL[], $4::TEXT[], $5::TEXT[], $6::INT4[])
             "#,
             &q_texts,
             &q_categories as &[Option<String>],
             &q_multiple,
+            &q_explanations as &[Option<String>],
+            &q_variables as &[Option<String>],
             &q_quiz_ids
         )
         .execute(&mut *tx)
@@ -71,10 +92,41 @@ impl Db {
         .fetch_all(&mut *tx)
         .await?;
 
+        // 3b. References and hints are arrays per question, which UNNEST would flatten,
+        // so they travel as JSON and are unpacked server-side.
+        let mut extra_ids = Vec::new();
+        let mut extra_references = Vec::new();
+        let mut extra_hints = Vec::new();
+        for (q, &q_id) in questions.iter().zip(question_ids.iter()) {
+            if !q.references.is_empty() || !q.hints.is_empty() {
+                extra_ids.push(q_id);
+                extra_references.push(serde_json::to_string(&q.references)?);
+                extra_hints.push(serde_json::to_string(&q.hints)?);
+            }
+        }
+
+        if !extra_ids.is_empty() {
+            sqlx::query!(
+                r#"
+                UPDATE questions q
+                SET reference_links = ARRAY(SELECT jsonb_array_elements_text(d.refs::JSONB)),
+                    hints = ARRAY(SELECT jsonb_array_elements_text(d.hints::JSONB))
+                FROM UNNEST($1::INT4[], $2::TEXT[], $3::TEXT[]) AS d(id, refs, hints)
+                WHERE q.id = d.id
+                "#,
+                &extra_ids,
+                &extra_references,
+                &extra_hints
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
         // 4. Batch INSERT all options via UNNEST
         let mut o_texts = Vec::new();
         let mut o_is_answers = Vec::new();
         let mut o_explanations: Vec<Option<String>> = Vec::new();
+        let mut o_is_pinned = Vec::new();
         let mut o_question_ids = Vec::new();
 
         for (q, &q_id) in questions.iter().zip(question_ids.iter()) {
@@ -82,6 +134,7 @@ impl Db {
                 o_texts.push(opt.text.clone());
                 o_is_answers.push(opt.is_answer);
                 o_explanations.push(opt.explanation.clone());
+                o_is_pinned.push(opt.is_pinned);
                 o_question_ids.push(q_id);
             }
         }
@@ -89,12 +142,13 @@ impl Db {
         if !o_texts.is_empty() {
             sqlx::query!(
                 r#"
-                INSERT INTO options (option, is_answer, explanation, question_id)
-                SELECT * FROM UNNEST($1::TEXT[], $2::BOOL[], $3::TEXT[], $4::INT4[])
+                INSERT INTO options (option, is_answer, explanation, is_pinned, question_id)
+                SELECT * FROM UNNEST($1::TEXT[], $2::BOOL[], $3::TEXT[], $4::BOOL[], $5::INT4[])
                 "#,
                 &o_texts,
                 &o_is_answers,
                 &o_explanations as &[Option<String>],
+                &o_is_pinned,
                 &o_question_ids
             )
             .execute(&mut *tx)
@@ -119,6 +173,14 @@ impl Db {
               MAX(qs.id) AS last_session_id,
               quizzes.is_shared AS "is_shared!",
               (quizzes.owner_id = $1) AS "is_owner!",
+              (quizzes.owner_id = $1 OR EXISTS (
+                SELECT 1 FROM quiz_collaborators c
+                WHERE c.quiz_id = quizzes.id AND c.user_id = $1 AND c.role = 'editor'
+              )) AS "can_edit!",
+              (quizzes.owner_id = $1 OR EXISTS (
+                SELECT 1 FROM quiz_collaborators c
+                WHERE c.quiz_id = quizzes.id AND c.user_id = $1 AND c.role = 'sharing'
+              )) AS "can_share!",
               users.display_name AS "owner_name!",
               (SELECT COUNT(DISTINCT sq.question_id)
                FROM session_questions sq
@@ -130,18 +192,18 @@ impl Db {
                FROM session_questions sq
                JOIN quiz_sessions s ON s.id = sq.session_id
                WHERE s.quiz_id = quizzes.id AND s.user_id = $1
-                 AND sq.is_correct IS NOT NULL
+                 AND sq.attempt = 1 AND sq.is_correct IS NOT NULL
               ) AS "total_correct!",
               (SELECT COUNT(*)
                FROM session_questions sq
                JOIN quiz_sessions s ON s.id = sq.session_id
                WHERE s.quiz_id = quizzes.id AND s.user_id = $1
-                 AND sq.is_correct IS NOT NULL
+                 AND sq.attempt = 1 AND sq.is_correct IS NOT NULL
               ) AS "total_answered!",
               COALESCE((
-                SELECT SUM(ua.duration_ms)::BIGINT
-                FROM user_answers ua
-                JOIN quiz_sessions qs2 ON qs2.id = ua.session_id
+                SELECT SUM(sq.active_ms)::BIGINT
+                FROM session_questions sq
+                JOIN quiz_sessions qs2 ON qs2.id = sq.session_id
                 WHERE qs2.quiz_id = quizzes.id AND qs2.user_id = $1
               ), 0) AS "study_time_ms!"
             FROM
@@ -166,6 +228,7 @@ impl Db {
         Ok(quizzes)
     }
 
+    /// Whether anyone besides the owner and the quiz's collaborators has it in their library.
     pub async fn quiz_has_other_users(&self, public_id: &str, owner_id: i32) -> Result<bool> {
         let exists: bool = sqlx::query_scalar!(
             r#"
@@ -173,6 +236,8 @@ impl Db {
                 SELECT 1 FROM user_quizzes uq
                 JOIN quizzes q ON q.id = uq.quiz_id
                 WHERE q.public_id = $1 AND uq.user_id != $2
+                  AND NOT EXISTS (SELECT 1 FROM quiz_collaborators c
+                                  WHERE c.quiz_id = q.id AND c.user_id = uq.user_id)
             )
             "#,
             public_id,
@@ -246,18 +311,98 @@ impl Db {
         Ok(public_id)
     }
 
-    pub async fn rename_quiz(&self, public_id: &str, name: &str, user_id: i32) -> Result<()> {
-        sqlx::query!(
-            "UPDATE quizzes SET name = $1 WHERE public_id = $2 AND owner_id = $3",
+    /// Rename a quiz the user owns or edits, and record the change. Returns `false` when
+    /// the user may not edit it.
+    pub async fn rename_quiz(&self, public_id: &str, name: &str, user_id: i32) -> Result<bool> {
+        let renamed: bool = sqlx::query_scalar!(
+            r#"
+            WITH renamed AS (
+                UPDATE quizzes SET name = $1
+                WHERE public_id = $2 AND (owner_id = $3 OR EXISTS (
+                  SELECT 1 FROM quiz_collaborators c
+                  WHERE c.quiz_id = quizzes.id AND c.user_id = $3 AND c.role = 'editor'))
+                RETURNING id
+            ),
+            logged AS (
+                INSERT INTO quiz_changes (quiz_id, user_id, action, detail)
+                SELECT id, $3, 'rename', $1 FROM renamed
+            )
+            SELECT EXISTS(SELECT 1 FROM renamed) AS "renamed!"
+            "#,
             name,
             public_id,
             user_id
         )
-        .execute(&self.pool)
+        .fetch_one(&self.pool)
         .await?;
 
-        tracing::info!("quiz renamed with public_id: {public_id} by user_id: {user_id}");
-        Ok(())
+        if renamed {
+            tracing::info!("quiz renamed with public_id: {public_id} by user_id: {user_id}");
+        }
+        Ok(renamed)
+    }
+
+    pub async fn get_quiz_session_sizes(&self, quiz_id: i32) -> Result<QuizSessionSizes> {
+        let sizes = sqlx::query_as!(
+            QuizSessionSizes,
+            r#"
+            SELECT default_session_size AS default_size, min_session_size AS min_size,
+                   max_session_size AS max_size
+            FROM quizzes WHERE id = $1
+            "#,
+            quiz_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(sizes)
+    }
+
+    /// Set the session sizes of a quiz the user owns or edits, and record the change.
+    /// Returns `false` when the user may not edit it.
+    pub async fn update_quiz_session_sizes(
+        &self,
+        public_id: &str,
+        sizes: QuizSessionSizes,
+        user_id: i32,
+    ) -> Result<bool> {
+        let size = |n: Option<i32>| n.map_or("-".to_string(), |n| n.to_string());
+        let detail = format!(
+            "{} / {} / {}",
+            size(sizes.default_size),
+            size(sizes.min_size),
+            size(sizes.max_size)
+        );
+        let updated: bool = sqlx::query_scalar!(
+            r#"
+            WITH updated AS (
+                UPDATE quizzes
+                SET default_session_size = $1, min_session_size = $2, max_session_size = $3
+                WHERE public_id = $4 AND (owner_id = $5 OR EXISTS (
+                  SELECT 1 FROM quiz_collaborators c
+                  WHERE c.quiz_id = quizzes.id AND c.user_id = $5 AND c.role = 'editor'))
+                RETURNING id
+            ),
+            logged AS (
+                INSERT INTO quiz_changes (quiz_id, user_id, action, detail)
+                SELECT id, $5, 'session_sizes', $6 FROM updated
+            )
+            SELECT EXISTS(SELECT 1 FROM updated) AS "updated!"
+            "#,
+            sizes.default_size,
+            sizes.min_size,
+            sizes.max_size,
+            public_id,
+            user_id,
+            detail
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        if updated {
+            tracing::info!("session sizes updated for quiz {public_id} by user_id: {user_id}");
+        }
+        Ok(updated)
     }
 
     /// Verify that a quiz belongs to the given user (owner check)
@@ -274,17 +419,29 @@ impl Db {
         Ok(exists)
     }
 
-    /// Toggle the is_shared flag for a quiz owned by the given user.
-    /// Returns the new value of is_shared.
-    pub async fn toggle_share(&self, public_id: &str, user_id: i32) -> Result<bool> {
-        let is_shared: bool = sqlx::query_scalar!(
-            r#"UPDATE quizzes SET is_shared = NOT is_shared
-               WHERE public_id = $1 AND owner_id = $2
-               RETURNING is_shared AS "is_shared!""#,
+    /// Toggle the is_shared flag for a quiz the given user owns or manages the sharing of,
+    /// and record the change. Returns the new value of is_shared, or `None` when the user
+    /// may not share it.
+    pub async fn toggle_share(&self, public_id: &str, user_id: i32) -> Result<Option<bool>> {
+        let is_shared: Option<bool> = sqlx::query_scalar!(
+            r#"
+            WITH toggled AS (
+                UPDATE quizzes SET is_shared = NOT is_shared
+                WHERE public_id = $1 AND (owner_id = $2 OR EXISTS (
+                  SELECT 1 FROM quiz_collaborators c
+                  WHERE c.quiz_id = quizzes.id AND c.user_id = $2 AND c.role = 'sharing'))
+                RETURNING id, is_shared
+            ),
+            logged AS (
+                INSERT INTO quiz_changes (quiz_id, user_id, action)
+                SELECT id, $2, CASE WHEN is_shared THEN 'share' ELSE 'unshare' END FROM toggled
+            )
+            SELECT is_shared AS "is_shared!" FROM toggled
+            "#,
             public_id,
             user_id
         )
-        .fetch_one(&self.pool)
+        .fetch_optional(&self.pool)
         .await?;
 
         Ok(is_shared)
@@ -355,6 +512,22 @@ impl Db {
         Ok(())
     }
 
+    pub(super) async fn add_quiz_to_library_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: i32,
+        quiz_id: i32,
+    ) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO user_quizzes (user_id, quiz_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            user_id,
+            quiz_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     /// List all shared quizzes with owner info and question count.
     pub async fn list_shared_quizzes(&self) -> Result<Vec<SharedQuizInfo>> {
         let rows = sqlx::query_as!(
@@ -424,3 +597,25 @@ impl Db {
         Ok(rows)
     }
 }
+
+/// Every text of a templated question must only reference its declared variables.
+fn validate_template(question: &Question) -> Result<(), template::TemplateError> {
+    if question.variables.is_empty() {
+        return Ok(());
+    }
+    template::validate_variables(&question.variables)?;
+
+    let texts = std::iter::once(&question.question)
+        .chain(question.explanation.iter())
+        .chain(question.hints.iter())
+        .chain(
+            question
+                .options
+                .iter()
+                .flat_map(|o| std::iter::once(&o.text).chain(o.explanation.iter())),
+        );
+    for text in texts {
+        template::validate(text, &question.variables)?;
+    }
+    Ok(())
+}
@@ -1,25 +1,35 @@
 use color_eyre::Result;
 
-use super::models::{DailyAccuracy, QuestionStatsModel, SessionReportModel};
+use super::models::{
+    AnswerDistribution, AnswerLogEntry, CategoryExport, CategorySize, ComparedSession,
+    ConfidenceStats, ConfidentWrongQuestion, DailyAccuracy, DistractorOption, DistractorQuestion,
+    ItemAnalysis, ItemResponse, PaceReport, PaceResponse, Readiness, ReadinessResponse,
+    SessionCategoryStats, SessionComparison, SessionOutcome, SessionReportModel,
+};
+use super::question::render_template;
 use super::Db;
+use crate::{analytics, names, template};
 
 impl Db {
-    pub async fn get_questions_report(&self, quiz_id: i32) -> Result<Vec<QuestionStatsModel>> {
-        let report = sqlx::query_as!(
-            QuestionStatsModel,
+    /// Item analysis of every question of a quiz, over all learners' first attempts.
+    /// Flashcard self-grades are left out, as they are not checked against the key.
+    pub async fn get_item_analysis(&self, quiz_id: i32) -> Result<Vec<ItemAnalysis>> {
+        let responses = sqlx::query_as!(
+            ItemResponse,
             r#"
-            SELECT q.question AS question, COUNT(*) AS "correct_answers!"
-            FROM questions q
-            JOIN session_questions sq ON sq.question_id = q.id AND sq.is_correct = TRUE
-            WHERE q.quiz_id = $1
-            GROUP BY q.question
+            SELECT s.user_id AS "user_id!", sq.question_id, sq.is_correct AS "is_correct!", sq.active_ms
+            FROM session_questions sq
+            JOIN quiz_sessions s ON s.id = sq.session_id
+            WHERE s.quiz_id = $1 AND sq.attempt = 1 AND sq.is_correct IS NOT NULL
+              AND s.study_mode <> 'flashcard' AND s.user_id IS NOT NULL
             "#,
             quiz_id
         )
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(report)
+        let questions = self.get_distractor_analysis(quiz_id).await?;
+        Ok(analytics::item_analysis(questions, &responses))
     }
 
     pub async fn get_sessions_report(&self, quiz_id: i32) -> Result<Vec<SessionReportModel>> {
@@ -56,7 +66,7 @@ impl Db {
                    ROUND(SUM(CASE WHEN sq.is_correct THEN 1 ELSE 0 END)::NUMERIC * 100.0 / COUNT(*), 1)::FLOAT8 AS "accuracy!"
             FROM session_questions sq
             JOIN quiz_sessions s ON s.id = sq.session_id
-            WHERE s.quiz_id = $1 AND sq.is_correct IS NOT NULL
+            WHERE s.quiz_id = $1 AND sq.attempt = 1 AND sq.is_correct IS NOT NULL
             GROUP BY SUBSTR(s.name, 1, 10)
             ORDER BY SUBSTR(s.name, 1, 10) ASC
             "#,
@@ -67,4 +77,430 @@ impl Db {
 
         Ok(accuracy)
     }
+
+    /// Accuracy per self-reported confidence level for one user's answers to a quiz.
+    pub async fn get_confidence_stats(
+        &self,
+        quiz_id: i32,
+        user_id: i32,
+    ) -> Result<Vec<ConfidenceStats>> {
+        let stats = sqlx::query_as!(
+            ConfidenceStats,
+            r#"
+            SELECT
+                COALESCE(a.confidence, 'unrated') AS "confidence!",
+                COUNT(*) AS "total!",
+                COUNT(*) FILTER (WHERE a.is_correct) AS "correct!"
+            FROM (
+                SELECT DISTINCT ON (ua.session_id, ua.question_id, ua.attempt) ua.confidence, ua.is_correct
+                FROM user_answers ua
+                JOIN quiz_sessions qs ON qs.id = ua.session_id
+                WHERE qs.quiz_id = $1 AND qs.user_id = $2
+            ) a
+            GROUP BY a.confidence
+            ORDER BY CASE a.confidence
+                WHEN 'sure' THEN 0 WHEN 'unsure' THEN 1 WHEN 'guess' THEN 2 ELSE 3
+            END
+            "#,
+            quiz_id,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// Questions the user marked as "sure" but answered incorrectly, most frequent first.
+    pub async fn get_confident_wrong_questions(
+        &self,
+        quiz_id: i32,
+        user_id: i32,
+    ) -> Result<Vec<ConfidentWrongQuestion>> {
+        let questions = sqlx::query_as!(
+            ConfidentWrongQuestion,
+            r#"
+            SELECT
+                q.id AS question_id,
+                q.question,
+                q.category,
+                COUNT(DISTINCT ua.session_id) AS "times_wrong!"
+            FROM user_answers ua
+            JOIN quiz_sessions qs ON qs.id = ua.session_id
+            JOIN questions q ON q.id = ua.question_id
+            WHERE qs.quiz_id = $1 AND qs.user_id = $2
+              AND ua.confidence = 'sure' AND NOT ua.is_correct
+            GROUP BY q.id, q.question, q.category
+            ORDER BY COUNT(DISTINCT ua.session_id) DESC, q.id
+            "#,
+            quiz_id,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(questions)
+    }
+
+    /// Cached first-attempt answer distribution of one question across all learners.
+    pub async fn get_answer_distribution(&self, question_id: i32) -> Result<AnswerDistribution> {
+        let responses: i32 = sqlx::query_scalar!(
+            "SELECT responses FROM question_response_counts WHERE question_id = $1",
+            question_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .unwrap_or(0);
+
+        let picks = sqlx::query!(
+            "SELECT option_id, picks FROM option_pick_counts WHERE question_id = $1",
+            question_id
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|r| (r.option_id, r.picks))
+        .collect();
+
+        Ok(AnswerDistribution { responses, picks })
+    }
+
+    /// Pick counts for every option of a quiz, questions with the most popular wrong
+    /// option (relative to responses) first.
+    pub async fn get_distractor_analysis(&self, quiz_id: i32) -> Result<Vec<DistractorQuestion>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT q.id AS question_id, q.question, q.category,
+                   COALESCE(rc.responses, 0) AS "responses!",
+                   o.option, o.is_answer,
+                   COALESCE(pc.picks, 0) AS "picks!"
+            FROM questions q
+            JOIN options o ON o.question_id = q.id
+            LEFT JOIN question_response_counts rc ON rc.question_id = q.id
+            LEFT JOIN option_pick_counts pc ON pc.option_id = o.id
+            WHERE q.quiz_id = $1
+            ORDER BY q.id, o.id
+            "#,
+            quiz_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut questions: Vec<DistractorQuestion> = Vec::new();
+        for r in rows {
+            if questions.last().map(|q| q.question_id) != Some(r.question_id) {
+                questions.push(DistractorQuestion {
+                    question_id: r.question_id,
+                    question: r.question,
+                    category: r.category,
+                    responses: r.responses,
+                    options: Vec::new(),
+                });
+            }
+            if let Some(question) = questions.last_mut() {
+                question.options.push(DistractorOption {
+                    option: r.option,
+                    is_answer: r.is_answer,
+                    picks: r.picks,
+                });
+            }
+        }
+
+        let top_distractor_share = |q: &DistractorQuestion| {
+            let top = q
+                .options
+                .iter()
+                .filter(|o| !o.is_answer)
+                .map(|o| o.picks)
+                .max()
+                .unwrap_or(0);
+            if q.responses > 0 {
+                f64::from(top) / f64::from(q.responses)
+            } else {
+                0.0
+            }
+        };
+        questions.sort_by(|a, b| top_distractor_share(b).total_cmp(&top_distractor_share(a)));
+
+        Ok(questions)
+    }
+
+    /// Pace analytics over one learner's timed first attempts at a quiz.
+    pub async fn get_pace_report(&self, quiz_id: i32, user_id: i32) -> Result<PaceReport> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT s.id AS session_id, s.name AS session_name, sq.question_id,
+                   q.question, q.category, sq.is_correct AS "is_correct!", sq.active_ms,
+                   sq.template_values
+            FROM session_questions sq
+            JOIN quiz_sessions s ON s.id = sq.session_id
+            JOIN questions q ON q.id = sq.question_id
+            WHERE s.quiz_id = $1 AND s.user_id = $2
+              AND sq.attempt = 1 AND sq.is_correct IS NOT NULL AND sq.active_ms > 0
+            ORDER BY s.id, sq.question_number
+            "#,
+            quiz_id,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let responses: Vec<PaceResponse> = rows
+            .into_iter()
+            .map(|r| {
+                let values = template::parse_values(r.template_values.as_deref());
+                PaceResponse {
+                    session_id: r.session_id,
+                    session_name: r.session_name,
+                    question_id: r.question_id,
+                    question: render_template(r.question, values.as_ref()),
+                    category: r.category,
+                    is_correct: r.is_correct,
+                    active_ms: r.active_ms,
+                }
+            })
+            .collect();
+
+        Ok(analytics::pace_report(&responses))
+    }
+
+    /// Side-by-side comparison of the learner's sessions of a quiz, oldest first.
+    /// Session ids that are not the learner's sessions of this quiz are left out.
+    pub async fn get_session_comparison(
+        &self,
+        quiz_id: i32,
+        user_id: i32,
+        session_ids: &[i32],
+    ) -> Result<SessionComparison> {
+        let sessions = sqlx::query_as!(
+            ComparedSession,
+            r#"
+            SELECT st.session_id AS "id!", st.name AS "name!", st.correct_answers AS "score!",
+                   st.answered_questions AS "answered_questions!",
+                   st.total_questions AS "total_questions!", st.is_complete AS "is_complete!",
+                   COALESCE((SELECT SUM(active_ms) FROM session_questions WHERE session_id = s.id), 0)::BIGINT
+                       AS "active_ms!"
+            FROM quiz_sessions s
+            JOIN session_stats st ON st.session_id = s.id
+            WHERE s.id = ANY($1) AND s.quiz_id = $2 AND s.user_id = $3
+            ORDER BY s.id
+            "#,
+            session_ids,
+            quiz_id,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let ids: Vec<i32> = sessions.iter().map(|s| s.id).collect();
+
+        let (category_stats, rows) = tokio::try_join!(
+            sqlx::query_as!(
+                SessionCategoryStats,
+                r#"
+                SELECT sq.session_id, q.category AS "category!", COUNT(*) AS "total!",
+                       COUNT(*) FILTER (WHERE sq.is_correct) AS "correct!"
+                FROM session_questions sq
+                JOIN questions q ON q.id = sq.question_id
+                WHERE sq.session_id = ANY($1) AND sq.attempt = 1
+                  AND q.category IS NOT NULL AND sq.is_correct IS NOT NULL
+                GROUP BY sq.session_id, q.category
+                "#,
+                &ids
+            )
+            .fetch_all(&self.pool),
+            sqlx::query!(
+                r#"
+                SELECT sq.session_id, sq.question_id, q.question, q.category,
+                       sq.is_correct AS "is_correct!", sq.template_values
+                FROM session_questions sq
+                JOIN questions q ON q.id = sq.question_id
+                WHERE sq.session_id = ANY($1) AND sq.attempt = 1 AND sq.is_correct IS NOT NULL
+                "#,
+                &ids
+            )
+            .fetch_all(&self.pool),
+        )?;
+
+        let outcomes: Vec<SessionOutcome> = rows
+            .into_iter()
+            .map(|r| {
+                let values = template::parse_values(r.template_values.as_deref());
+                SessionOutcome {
+                    session_id: r.session_id,
+                    question_id: r.question_id,
+                    question: render_template(r.question, values.as_ref()),
+                    category: r.category,
+                    is_correct: r.is_correct,
+                }
+            })
+            .collect();
+
+        Ok(analytics::compare_sessions(
+            sessions,
+            &category_stats,
+            &outcomes,
+        ))
+    }
+
+    /// Exam readiness of one learner in a quiz, from their checked first attempts of
+    /// questions still in the quiz, against the pass threshold of their study plan.
+    pub async fn get_readiness(&self, quiz_id: i32, user_id: i32) -> Result<Option<Readiness>> {
+        let (sizes, responses, threshold) = tokio::try_join!(
+            sqlx::query_as!(
+                CategorySize,
+                r#"
+                SELECT category, COUNT(*) AS "questions!"
+                FROM questions
+                WHERE quiz_id = $1
+                GROUP BY category
+                "#,
+                quiz_id
+            )
+            .fetch_all(&self.pool),
+            sqlx::query_as!(
+                ReadinessResponse,
+                r#"
+                SELECT sq.question_id, q.category, sq.is_correct AS "is_correct!",
+                       EXTRACT(EPOCH FROM NOW() - COALESCE(sq.answered_at, s.last_active_at))::FLOAT8
+                           / 86400 AS "days_ago!"
+                FROM session_questions sq
+                JOIN quiz_sessions s ON s.id = sq.session_id
+                JOIN questions q ON q.id = sq.question_id
+                WHERE s.quiz_id = $1 AND s.user_id = $2
+                  AND sq.attempt = 1 AND sq.is_correct IS NOT NULL
+                  AND s.study_mode <> 'flashcard'
+                "#,
+                quiz_id,
+                user_id
+            )
+            .fetch_all(&self.pool),
+            sqlx::query_scalar!(
+                "SELECT pass_threshold FROM user_quizzes WHERE quiz_id = $1 AND user_id = $2",
+                quiz_id,
+                user_id
+            )
+            .fetch_optional(&self.pool),
+        )?;
+
+        let threshold = threshold.flatten().unwrap_or(names::DEFAULT_PASS_THRESHOLD);
+        Ok(analytics::readiness(
+            &sizes,
+            &responses,
+            f64::from(threshold) / 100.0,
+        ))
+    }
+
+    /// Every answered question of a learner in a quiz, oldest session first, with the
+    /// options as the learner saw them.
+    pub async fn get_answer_log(&self, quiz_id: i32, user_id: i32) -> Result<Vec<AnswerLogEntry>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT s.id AS session_id, s.name AS session_name, s.study_mode,
+                   sq.question_number + 1 AS "question_number!", sq.attempt, sq.question_id,
+                   q.question, q.category, sq.is_correct AS "is_correct!", sq.hints_used, sq.active_ms,
+                   sq.template_values,
+                   TO_CHAR(sq.answered_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS answered_at,
+                   ARRAY(
+                       SELECT o.option FROM user_answers ua
+                       JOIN options o ON o.id = ua.option_id
+                       WHERE ua.session_id = sq.session_id AND ua.question_id = sq.question_id
+                         AND ua.attempt = sq.attempt
+                       ORDER BY o.id
+                   ) AS "selected!",
+                   ARRAY(
+                       SELECT o.option FROM options o
+                       WHERE o.question_id = sq.question_id AND o.is_answer
+                       ORDER BY o.id
+                   ) AS "correct_options!",
+                   (SELECT ua.confidence FROM user_answers ua
+                    WHERE ua.session_id = sq.session_id AND ua.question_id = sq.question_id
+                      AND ua.attempt = sq.attempt
+                    LIMIT 1) AS confidence,
+                   (SELECT ua.self_grade FROM user_answers ua
+                    WHERE ua.session_id = sq.session_id AND ua.question_id = sq.question_id
+                      AND ua.attempt = sq.attempt
+                    LIMIT 1) AS self_grade
+            FROM session_questions sq
+            JOIN quiz_sessions s ON s.id = sq.session_id
+            JOIN questions q ON q.id = sq.question_id
+            WHERE s.quiz_id = $1 AND s.user_id = $2 AND sq.is_correct IS NOT NULL
+            ORDER BY s.id, sq.question_number
+            "#,
+            quiz_id,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                let values = template::parse_values(r.template_values.as_deref());
+                let render = |texts: Vec<String>| -> Vec<String> {
+                    texts
+                        .into_iter()
+                        .map(|t| render_template(t, values.as_ref()))
+                        .collect()
+                };
+                AnswerLogEntry {
+                    session_id: r.session_id,
+                    session_name: r.session_name,
+                    study_mode: r.study_mode,
+                    question_number: r.question_number,
+                    attempt: r.attempt,
+                    question_id: r.question_id,
+                    question: render_template(r.question, values.as_ref()),
+                    category: r.category,
+                    selected: render(r.selected),
+                    correct_options: render(r.correct_options),
+                    is_correct: r.is_correct,
+                    confidence: r.confidence,
+                    self_grade: r.self_grade,
+                    hints_used: r.hints_used,
+                    active_ms: r.active_ms,
+                    answered_at: r.answered_at,
+                }
+            })
+            .collect())
+    }
+
+    /// A learner's first-attempt totals per category of a quiz, including categories
+    /// they have not answered yet. Uncategorized questions come last.
+    pub async fn get_category_export(
+        &self,
+        quiz_id: i32,
+        user_id: i32,
+    ) -> Result<Vec<CategoryExport>> {
+        let stats = sqlx::query_as!(
+            CategoryExport,
+            r#"
+            WITH answers AS (
+                SELECT sq.question_id, sq.is_correct, sq.active_ms
+                FROM session_questions sq
+                JOIN quiz_sessions s ON s.id = sq.session_id
+                WHERE s.quiz_id = $1 AND s.user_id = $2
+                  AND sq.attempt = 1 AND sq.is_correct IS NOT NULL
+            )
+            SELECT q.category,
+                   COUNT(DISTINCT q.id) AS "questions!",
+                   COUNT(DISTINCT a.question_id) AS "seen!",
+                   COUNT(a.question_id) AS "answered!",
+                   COUNT(a.question_id) FILTER (WHERE a.is_correct) AS "correct!",
+                   ROUND(AVG(CASE WHEN a.is_correct THEN 100.0 WHEN NOT a.is_correct THEN 0.0 END), 1)::FLOAT8
+                       AS accuracy,
+                   COALESCE(SUM(a.active_ms), 0)::BIGINT AS "active_ms!"
+            FROM questions q
+            LEFT JOIN answers a ON a.question_id = q.id
+            WHERE q.quiz_id = $1
+            GROUP BY q.category
+            ORDER BY q.category NULLS LAST
+            "#,
+            quiz_id,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
 }
@@ -1,11 +1,14 @@
-use color_eyre::{eyre::OptionExt, Result};
+use std::collections::BTreeMap;
+
+use color_eyre::Result;
 use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use ulid::Ulid;
 
-use super::models::QuizSessionModel;
+use super::models::{InProgressSession, QuizSessionModel};
 use super::Db;
+use crate::{models::TemplateVariable, template};
 
 impl Db {
     pub async fn session_name_exists(&self, name: &str, quiz_id: i32) -> Result<bool> {
@@ -28,7 +31,32 @@ impl Db {
         quiz_id: i32,
         question_count: i32,
         selection_mode: &str,
+        study_mode: &str,
+        user_id: i32,
+    ) -> Result<(String, i32)> {
+        self.insert_session(
+            name,
+            quiz_id,
+            question_count,
+            selection_mode,
+            study_mode,
+            user_id,
+            None,
+        )
+        .await
+    }
+
+    /// Create a session, counting toward `assignment_id` from the start when given.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn insert_session(
+        &self,
+        name: &str,
+        quiz_id: i32,
+        question_count: i32,
+        selection_mode: &str,
+        study_mode: &str,
         user_id: i32,
+        assignment_id: Option<i32>,
     ) -> Result<(String, i32)> {
         if self.session_name_exists(name, quiz_id).await? {
             return Err(color_eyre::eyre::eyre!(
@@ -42,26 +70,35 @@ impl Db {
 
         // Select questions before transaction (read-only)
         let selected_ids = self
-            .select_questions(quiz_id, question_count, selection_mode, shuffle_seed)
+            .select_questions(
+                quiz_id,
+                question_count,
+                selection_mode,
+                shuffle_seed,
+                user_id,
+            )
             .await?;
 
         // Transaction: insert session + session_questions atomically
         let mut tx = self.pool.begin().await?;
 
         let session_id: i32 = sqlx::query_scalar!(
-            "INSERT INTO quiz_sessions (name, session_token, quiz_id, shuffle_seed, question_count, selection_mode, user_id) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id",
+            "INSERT INTO quiz_sessions (name, session_token, quiz_id, shuffle_seed, question_count, selection_mode, study_mode, user_id, assignment_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id",
             name,
             session_token,
             quiz_id,
             shuffle_seed,
             question_count,
             selection_mode,
-            user_id
+            study_mode,
+            user_id,
+            assignment_id
         )
         .fetch_one(&mut *tx)
         .await?;
 
-        Self::batch_insert_session_questions_tx(&mut tx, session_id, &selected_ids).await?;
+        Self::batch_insert_session_questions_tx(&mut tx, session_id, &selected_ids, shuffle_seed)
+            .await?;
 
         tx.commit().await?;
 
@@ -76,6 +113,7 @@ impl Db {
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         session_id: i32,
         question_ids: &[i32],
+        shuffle_seed: i32,
     ) -> Result<()> {
         if question_ids.is_empty() {
             return Ok(());
@@ -93,6 +131,57 @@ impl Db {
         .execute(&mut **tx)
         .await?;
 
+        Self::instantiate_templates_tx(tx, session_id, question_ids, shuffle_seed).await
+    }
+
+    /// Draw and store variable values for the session's templated questions. Values come
+    /// from the session seed, so a retake or challenge with the same seed sees the same numbers.
+    async fn instantiate_templates_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        session_id: i32,
+        question_ids: &[i32],
+        shuffle_seed: i32,
+    ) -> Result<()> {
+        let templates = sqlx::query!(
+            r#"
+            SELECT q.id, q.template_variables AS "variables!",
+                   ARRAY(SELECT o.option FROM options o WHERE o.question_id = q.id ORDER BY o.id) AS "options!"
+            FROM questions q
+            WHERE q.id = ANY($1) AND q.template_variables IS NOT NULL
+            "#,
+            question_ids
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+
+        if templates.is_empty() {
+            return Ok(());
+        }
+
+        let mut ids = Vec::with_capacity(templates.len());
+        let mut values = Vec::with_capacity(templates.len());
+        for row in templates {
+            let variables: BTreeMap<String, TemplateVariable> =
+                serde_json::from_str(&row.variables)?;
+            let seed = (u64::from(shuffle_seed as u32) << 32) | u64::from(row.id as u32);
+            let drawn = template::sample_values(&variables, &row.options, seed);
+            ids.push(row.id);
+            values.push(serde_json::to_string(&drawn)?);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE session_questions sq SET template_values = d.template_values
+            FROM UNNEST($2::INT4[], $3::TEXT[]) AS d(question_id, template_values)
+            WHERE sq.session_id = $1 AND sq.question_id = d.question_id
+            "#,
+            session_id,
+            &ids,
+            &values
+        )
+        .execute(&mut **tx)
+        .await?;
+
         Ok(())
     }
 
@@ -102,6 +191,7 @@ impl Db {
         question_count: i32,
         selection_mode: &str,
         shuffle_seed: i32,
+        user_id: i32,
     ) -> Result<Vec<i32>> {
         let mut rng = StdRng::seed_from_u64(shuffle_seed as u64);
 
@@ -143,13 +233,28 @@ impl Db {
                 }
             }
             "incorrect" => {
+                // Previously incorrect questions, plus questions whose latest answer was a
+                // lucky guess or a flashcard graded "hard": neither is mastered knowledge.
                 let mut incorrect: Vec<i32> = sqlx::query_scalar!(
                     r#"
                     SELECT question_id AS "question_id!" FROM question_stats
                     WHERE quiz_id = $1 AND times_incorrect > 0
-                    ORDER BY accuracy ASC, times_incorrect DESC
+                    UNION
+                    SELECT question_id FROM (
+                        SELECT DISTINCT ON (ua.question_id) ua.question_id, ua.is_correct, ua.confidence, ua.self_grade
+                        FROM user_answers ua
+                        JOIN quiz_sessions qs ON qs.id = ua.session_id
+                        JOIN session_questions sq ON sq.session_id = ua.session_id
+                         AND sq.question_id = ua.question_id AND sq.attempt = ua.attempt
+                        WHERE qs.quiz_id = $1 AND qs.user_id = $2
+                        ORDER BY ua.question_id, sq.answered_at DESC NULLS LAST, ua.id DESC
+                    ) latest
+                    WHERE latest.is_correct
+                      AND (latest.confidence = 'guess' OR latest.self_grade = 'hard')
+                    ORDER BY 1
                     "#,
-                    quiz_id
+                    quiz_id,
+                    user_id
                 )
                 .fetch_all(&self.pool)
                 .await?;
@@ -208,7 +313,7 @@ impl Db {
     pub async fn get_session(&self, token: &str) -> Result<QuizSessionModel> {
         let session = sqlx::query_as!(
             QuizSessionModel,
-            "SELECT id, quiz_id, name, question_count, selection_mode FROM quiz_sessions WHERE session_token = $1",
+            "SELECT id, quiz_id, name, question_count, selection_mode, study_mode FROM quiz_sessions WHERE session_token = $1",
             token
         )
         .fetch_one(&self.pool)
@@ -220,7 +325,7 @@ impl Db {
     pub async fn get_session_by_id(&self, session_id: i32) -> Result<QuizSessionModel> {
         let session = sqlx::query_as!(
             QuizSessionModel,
-            "SELECT id, quiz_id, name, question_count, selection_mode FROM quiz_sessions WHERE id = $1",
+            "SELECT id, quiz_id, name, question_count, selection_mode, study_mode FROM quiz_sessions WHERE id = $1",
             session_id
         )
         .fetch_one(&self.pool)
@@ -241,13 +346,53 @@ impl Db {
         Ok(count)
     }
 
+    /// Whether every question of the session has been answered.
+    pub async fn is_session_complete(&self, session_id: i32) -> Result<bool> {
+        let complete: bool = sqlx::query_scalar!(
+            r#"SELECT COALESCE((SELECT is_complete FROM session_stats WHERE session_id = $1), FALSE) AS "complete!""#,
+            session_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(complete)
+    }
+
     pub async fn create_session_with_questions(
         &self,
         name: &str,
         quiz_id: i32,
         question_ids: &[i32],
         selection_mode: &str,
+        study_mode: &str,
+        user_id: i32,
+    ) -> Result<String> {
+        self.create_seeded_session(
+            name,
+            quiz_id,
+            question_ids,
+            rand::random::<i32>(),
+            selection_mode,
+            study_mode,
+            user_id,
+            None,
+        )
+        .await
+    }
+
+    /// Create a session with a fixed question order and option shuffle seed, so a retake or a
+    /// challenge shows exactly the same questions and options as the original.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_seeded_session(
+        &self,
+        name: &str,
+        quiz_id: i32,
+        question_ids: &[i32],
+        shuffle_seed: i32,
+        selection_mode: &str,
+        study_mode: &str,
         user_id: i32,
+        challenge_id: Option<i32>,
     ) -> Result<String> {
         let mut seen = std::collections::HashSet::new();
         let deduped_question_ids: Vec<i32> = question_ids
@@ -263,18 +408,27 @@ impl Db {
         let mut tx = self.pool.begin().await?;
 
         let session_id: i32 = sqlx::query_scalar!(
-            "INSERT INTO quiz_sessions (name, session_token, quiz_id, shuffle_seed, question_count, selection_mode, user_id) VALUES ($1, $2, $3, 0, $4, $5, $6) RETURNING id",
+            "INSERT INTO quiz_sessions (name, session_token, quiz_id, shuffle_seed, question_count, selection_mode, study_mode, user_id, challenge_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id",
             name,
             session_token,
             quiz_id,
+            shuffle_seed,
             question_count,
             selection_mode,
-            user_id
+            study_mode,
+            user_id,
+            challenge_id
         )
         .fetch_one(&mut *tx)
         .await?;
 
-        Self::batch_insert_session_questions_tx(&mut tx, session_id, &deduped_question_ids).await?;
+        Self::batch_insert_session_questions_tx(
+            &mut tx,
+            session_id,
+            &deduped_question_ids,
+            shuffle_seed,
+        )
+        .await?;
 
         tx.commit().await?;
 
@@ -284,11 +438,17 @@ impl Db {
         Ok(session_token)
     }
 
+    /// Delete a session and its answers, keeping the cached answer distribution in step.
     pub async fn delete_session(&self, session_id: i32) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        Self::uncount_session_tx(&mut tx, session_id).await?;
         sqlx::query!("DELETE FROM quiz_sessions WHERE id = $1", session_id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
 
+        tx.commit().await?;
+
         tracing::info!("deleted session {session_id}");
         Ok(())
     }
@@ -318,43 +478,6 @@ impl Db {
         Ok(())
     }
 
-    pub async fn is_question_bookmarked(&self, session_id: i32, question_id: i32) -> Result<bool> {
-        let bookmarked: bool = sqlx::query_scalar!(
-            "SELECT is_bookmarked FROM session_questions WHERE session_id = $1 AND question_id = $2",
-            session_id,
-            question_id
-        )
-        .fetch_optional(&self.pool)
-        .await?
-        .ok_or_eyre("session_question not found")?;
-
-        Ok(bookmarked)
-    }
-
-    /// ブックマーク状態をトグルし、新しい状態を返す
-    pub async fn toggle_bookmark(&self, session_id: i32, question_id: i32) -> Result<bool> {
-        sqlx::query!(
-            "UPDATE session_questions SET is_bookmarked = NOT is_bookmarked WHERE session_id = $1 AND question_id = $2",
-            session_id,
-            question_id
-        )
-        .execute(&self.pool)
-        .await?;
-
-        self.is_question_bookmarked(session_id, question_id).await
-    }
-
-    pub async fn get_bookmarked_questions(&self, session_id: i32) -> Result<Vec<i32>> {
-        let ids: Vec<i32> = sqlx::query_scalar!(
-            "SELECT question_id FROM session_questions WHERE session_id = $1 AND is_bookmarked = TRUE",
-            session_id
-        )
-        .fetch_all(&self.pool)
-        .await?;
-
-        Ok(ids)
-    }
-
     pub async fn find_incomplete_session(
         &self,
         name: &str,
@@ -384,6 +507,49 @@ impl Db {
         }
     }
 
+    /// The user's unfinished sessions of a quiz, most recently active first.
+    pub async fn get_in_progress_sessions(
+        &self,
+        quiz_id: i32,
+        user_id: i32,
+    ) -> Result<Vec<InProgressSession>> {
+        let sessions = sqlx::query_as!(
+            InProgressSession,
+            r#"
+            SELECT
+                s.id,
+                s.name,
+                s.session_token,
+                ss.answered_questions AS "answered_questions!",
+                ss.total_questions AS "total_questions!",
+                s.study_mode,
+                TO_CHAR(s.last_active_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI') AS "last_active!"
+            FROM quiz_sessions s
+            JOIN session_stats ss ON ss.session_id = s.id
+            WHERE s.quiz_id = $1 AND s.user_id = $2 AND NOT ss.is_complete
+            ORDER BY s.last_active_at DESC, s.id DESC
+            "#,
+            quiz_id,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Mark a session as the user's most recently active one.
+    pub async fn touch_session(&self, session_id: i32) -> Result<()> {
+        sqlx::query!(
+            "UPDATE quiz_sessions SET last_active_at = NOW() WHERE id = $1",
+            session_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Verify that a session belongs to the given user
     pub async fn verify_session_owner(&self, session_id: i32, user_id: i32) -> Result<bool> {
         let exists: bool = sqlx::query_scalar!(
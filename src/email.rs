@@ -1,8 +1,12 @@
 use std::time::Duration;
 
 use color_eyre::Result;
+use maud::html;
+use rust_i18n::t;
 use serde::Serialize;
 
+use crate::db::models::{DigestRecipient, WeeklyDigest};
+use crate::names;
 use crate::services::auth::EmailSender;
 
 const MAX_RETRIES: u32 = 3;
@@ -60,6 +64,82 @@ impl EmailSender for ResendEmailSender {
         tracing::info!("password reset email sent to {to_email}");
         Ok(())
     }
+
+    async fn send_weekly_digest(
+        &self,
+        recipient: &DigestRecipient,
+        digest: &WeeklyDigest,
+        base_url: &str,
+    ) -> Result<()> {
+        let subject = t!("digest.subject", locale = &recipient.locale);
+        let html = weekly_digest_html(recipient, digest, base_url);
+        send_email_with_retry(&self.api_key, &recipient.email, &subject, &html).await?;
+        tracing::info!("weekly digest sent to {}", recipient.email);
+        Ok(())
+    }
+}
+
+/// Body of the weekly digest, in the recipient's language.
+fn weekly_digest_html(
+    recipient: &DigestRecipient,
+    digest: &WeeklyDigest,
+    base_url: &str,
+) -> String {
+    let locale = recipient.locale.as_str();
+    let change = digest
+        .accuracy
+        .zip(digest.previous_accuracy)
+        .map(|(now, before)| (now - before).round() as i64);
+
+    html! {
+        h2 { (t!("digest.greeting", locale = locale, name = recipient.display_name)) }
+        @if digest.questions_answered == 0 {
+            p { (t!("digest.no_activity", locale = locale)) }
+        } @else {
+            p {
+                (t!("digest.answered", locale = locale,
+                    count = digest.questions_answered,
+                    minutes = digest.active_ms / 60_000))
+            }
+        }
+        @if let Some(accuracy) = digest.accuracy {
+            p {
+                (t!("digest.accuracy", locale = locale, accuracy = accuracy.round() as i64))
+                @match change {
+                    Some(points) if points > 0 => {
+                        " " (t!("digest.accuracy_up", locale = locale, points = points))
+                    }
+                    Some(points) if points < 0 => {
+                        " " (t!("digest.accuracy_down", locale = locale, points = -points))
+                    }
+                    Some(_) => { " " (t!("digest.accuracy_same", locale = locale)) }
+                    None => {}
+                }
+            }
+        }
+        @if !digest.weakest.is_empty() {
+            h3 { (t!("digest.weakest_title", locale = locale)) }
+            ul {
+                @for category in &digest.weakest {
+                    li {
+                        (category.quiz_name) " — " (category.category) ": "
+                        (category.correct) "/" (category.total)
+                    }
+                }
+            }
+        }
+        @if digest.due_reviews > 0 {
+            p { (t!("digest.due_reviews", locale = locale, count = digest.due_reviews)) }
+        }
+        p { a href=(base_url) { (t!("digest.cta", locale = locale)) } }
+        p style="color: #888; font-size: 0.85em;" {
+            (t!("digest.unsubscribe", locale = locale)) " "
+            a href=(format!("{base_url}{}", names::ACCOUNT_URL)) {
+                (t!("digest.settings_link", locale = locale))
+            }
+        }
+    }
+    .into_string()
 }
 
 /// Send an email via Resend API with exponential backoff retry (3 attempts: 1s, 2s, 4s).
@@ -0,0 +1,136 @@
+//! CSV exports of a learner's answer log and category totals.
+//!
+//! Files start with a UTF-8 byte order mark so spreadsheet applications detect the
+//! encoding, and text cells that a spreadsheet would evaluate as a formula are
+//! prefixed with an apostrophe.
+
+use std::fmt::Write;
+
+use crate::db::{AnswerLogEntry, CategoryExport};
+
+/// Separator between several options in one cell.
+const OPTION_SEPARATOR: &str = " | ";
+
+const BOM: &str = "\u{feff}";
+
+/// One CSV cell of text: quoted when needed, and defused when it starts like a formula.
+fn text(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn push_row(out: &mut String, cells: &[String]) {
+    out.push_str(&cells.join(","));
+    out.push_str("\r\n");
+}
+
+/// The answer log as CSV, one row per answered question.
+pub fn answers_csv(entries: &[AnswerLogEntry]) -> String {
+    let mut out = String::from(BOM);
+    out.push_str(
+        "session_id,session_name,study_mode,question_number,attempt,question_id,question,\
+         category,selected,correct_options,is_correct,confidence,self_grade,hints_used,\
+         active_ms,answered_at\r\n",
+    );
+    for e in entries {
+        push_row(
+            &mut out,
+            &[
+                e.session_id.to_string(),
+                text(&e.session_name),
+                text(&e.study_mode),
+                e.question_number.to_string(),
+                e.attempt.to_string(),
+                e.question_id.to_string(),
+                text(&e.question),
+                text(e.category.as_deref().unwrap_or_default()),
+                text(&e.selected.join(OPTION_SEPARATOR)),
+                text(&e.correct_options.join(OPTION_SEPARATOR)),
+                e.is_correct.to_string(),
+                text(e.confidence.as_deref().unwrap_or_default()),
+                text(e.self_grade.as_deref().unwrap_or_default()),
+                e.hints_used.to_string(),
+                e.active_ms.to_string(),
+                e.answered_at.clone().unwrap_or_default(),
+            ],
+        );
+    }
+    out
+}
+
+/// Category totals as CSV, one row per category.
+pub fn categories_csv(categories: &[CategoryExport]) -> String {
+    let mut out = String::from(BOM);
+    out.push_str("category,questions,seen,answered,correct,accuracy,active_ms\r\n");
+    for c in categories {
+        let mut accuracy = String::new();
+        if let Some(a) = c.accuracy {
+            let _ = write!(accuracy, "{a:.1}");
+        }
+        push_row(
+            &mut out,
+            &[
+                text(c.category.as_deref().unwrap_or_default()),
+                c.questions.to_string(),
+                c.seen.to_string(),
+                c.answered.to_string(),
+                c.correct.to_string(),
+                accuracy,
+                c.active_ms.to_string(),
+            ],
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_cells_are_quoted_and_defused() {
+        assert_eq!(text("plain"), "plain");
+        assert_eq!(text("a, b"), "\"a, b\"");
+        assert_eq!(text("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(text("=SUM(A1)"), "'=SUM(A1)");
+        assert_eq!(text("-1, or not"), "\"'-1, or not\"");
+    }
+
+    #[test]
+    fn answers_csv_has_a_row_per_answer() {
+        let entries = vec![AnswerLogEntry {
+            session_id: 7,
+            session_name: "morning".to_string(),
+            study_mode: "quiz".to_string(),
+            question_number: 1,
+            attempt: 1,
+            question_id: 3,
+            question: "Pick two, please".to_string(),
+            category: None,
+            selected: vec!["A".to_string(), "B".to_string()],
+            correct_options: vec!["A".to_string()],
+            is_correct: false,
+            confidence: Some("sure".to_string()),
+            self_grade: None,
+            hints_used: 0,
+            active_ms: 1500,
+            answered_at: Some("2030-01-01T09:00:00Z".to_string()),
+        }];
+        let csv = answers_csv(&entries);
+        let lines: Vec<&str> = csv.trim_start_matches(BOM).lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("session_id,"));
+        assert_eq!(
+            lines[1],
+            "7,morning,quiz,1,1,3,\"Pick two, please\",,A | B,A,false,sure,,0,1500,2030-01-01T09:00:00Z"
+        );
+    }
+}
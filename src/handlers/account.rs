@@ -2,12 +2,13 @@ use axum::{
     extract::State,
     response::IntoResponse,
     routing::{get, post},
-    Json, Router,
+    Form, Json, Router,
 };
 use serde::Deserialize;
 
 use crate::{
     extractors::{AuthGuard, IsHtmx, Locale},
+    names,
     rejections::{AppError, ResultExt},
     views, AppState,
 };
@@ -18,24 +19,37 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/account", get(account_page))
         .route("/change-password", post(change_password_post))
+        .route(names::WEEKLY_DIGEST_URL, post(weekly_digest_post))
 }
 
 async fn account_page(
     AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
     IsHtmx(is_htmx): IsHtmx,
     Locale(locale): Locale,
-) -> maud::Markup {
+) -> Result<maud::Markup, AppError> {
+    let weekly_digest = state
+        .db
+        .weekly_digest_enabled(user.id)
+        .await
+        .reject("could not get email settings")?;
+
     let nav_user = views::NavUser {
         display_name: &user.display_name,
         is_admin: user.is_admin,
     };
-    views::render(
+    Ok(views::render(
         is_htmx,
         "Account",
-        account_views::account_page(&user, account_views::ChangePasswordState::NoError, &locale),
+        account_views::account_page(
+            &user,
+            account_views::ChangePasswordState::NoError,
+            weekly_digest,
+            &locale,
+        ),
         &locale,
         Some(&nav_user),
-    )
+    ))
 }
 
 #[derive(Deserialize)]
@@ -73,9 +87,46 @@ async fn change_password_post(
         ChangePasswordOutcome::DemoUser => account_views::ChangePasswordState::DemoUser,
     };
 
+    let weekly_digest = state
+        .db
+        .weekly_digest_enabled(user.id)
+        .await
+        .reject("could not get email settings")?;
+
     Ok(views::titled(
         "Account",
-        account_views::account_page(&user, pw_state, &locale),
+        account_views::account_page(&user, pw_state, weekly_digest, &locale),
     )
     .into_response())
 }
+
+#[derive(Deserialize)]
+struct WeeklyDigestPost {
+    /// Present only when the switch is on.
+    #[serde(default)]
+    enabled: bool,
+}
+
+/// Opt in to or out of the weekly digest. The email is written in the current language.
+async fn weekly_digest_post(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+    Form(body): Form<WeeklyDigestPost>,
+) -> Result<maud::Markup, AppError> {
+    if user.is_demo {
+        return Err(AppError::Forbidden);
+    }
+
+    state
+        .db
+        .set_weekly_digest(user.id, body.enabled, &locale)
+        .await
+        .reject("could not save email settings")?;
+
+    Ok(account_views::weekly_digest_form(
+        body.enabled,
+        true,
+        &locale,
+    ))
+}
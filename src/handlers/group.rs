@@ -0,0 +1,419 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Form, Router,
+};
+use maud::Markup;
+use rust_i18n::t;
+use serde::Deserialize;
+
+use crate::{
+    analytics,
+    db::{models::AuthUser, GroupModel},
+    extractors::{AuthGuard, IsHtmx, Locale},
+    names,
+    rejections::{AppError, ResultExt},
+    views,
+    views::group as group_views,
+    AppState,
+};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(names::GROUPS_URL, get(groups_page).post(create_group))
+        .route("/groups/{id}", get(group_page).delete(delete_group))
+        .route("/groups/{id}/quizzes", post(assign_quiz))
+        .route("/groups/{id}/quizzes/{quiz_id}", delete(unassign_quiz))
+        .route("/groups/{id}/members/{user_id}", delete(remove_member))
+        .route("/groups/join/{code}", get(invite_page).post(join_group))
+}
+
+#[derive(Deserialize)]
+struct CreateGroupBody {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct AssignQuizBody {
+    quiz_id: String,
+}
+
+async fn load_group(state: &AppState, public_id: &str) -> Result<GroupModel, AppError> {
+    state
+        .db
+        .get_group(public_id)
+        .await
+        .reject("could not get group")?
+        .ok_or(AppError::Input("group not found"))
+}
+
+async fn load_owned_group(
+    state: &AppState,
+    public_id: &str,
+    user_id: i32,
+) -> Result<GroupModel, AppError> {
+    let group = load_group(state, public_id).await?;
+    if group.owner_id != user_id {
+        return Err(AppError::Forbidden);
+    }
+    Ok(group)
+}
+
+/// The instructor sees members, assigned quizzes and progress; members see the quizzes.
+async fn group_view(
+    state: &AppState,
+    group: GroupModel,
+    user_id: i32,
+    locale: &str,
+) -> Result<Markup, AppError> {
+    if group.owner_id != user_id {
+        if !state
+            .db
+            .is_group_member(group.id, user_id)
+            .await
+            .reject("could not check group membership")?
+        {
+            return Err(AppError::Forbidden);
+        }
+        let quizzes = state
+            .db
+            .group_quizzes(group.id)
+            .await
+            .reject("could not get group quizzes")?;
+        return Ok(group_views::group_page(
+            &group,
+            group_views::GroupView::Member { user_id, quizzes },
+            locale,
+        ));
+    }
+
+    let (members, quizzes, rows, library) = tokio::try_join!(
+        state.db.group_members(group.id),
+        state.db.group_quizzes(group.id),
+        state.db.get_group_progress(group.id),
+        state.db.quizzes(user_id),
+    )
+    .reject("could not get group progress")?;
+
+    let assignable = library
+        .into_iter()
+        .filter(|quiz| !quizzes.iter().any(|q| q.quiz_id == quiz.id))
+        .collect();
+    let progress = analytics::group_progress(quizzes, &members, &rows);
+
+    Ok(group_views::group_page(
+        &group,
+        group_views::GroupView::Owner {
+            members,
+            progress,
+            assignable,
+        },
+        locale,
+    ))
+}
+
+fn nav_user(user: &AuthUser) -> views::NavUser<'_> {
+    views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    }
+}
+
+async fn groups_page(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let groups = state
+        .db
+        .groups_for_user(user.id)
+        .await
+        .reject("could not get groups")?;
+
+    let title = t!("group.title", locale = &locale);
+    Ok(views::render(
+        is_htmx,
+        &title,
+        group_views::groups_page(&groups, &locale),
+        &locale,
+        Some(&nav_user(&user)),
+    ))
+}
+
+async fn create_group(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+    Form(body): Form<CreateGroupBody>,
+) -> Result<impl IntoResponse, AppError> {
+    let name = body.name.trim();
+    if name.is_empty() {
+        return Err(AppError::Input("group name is required"));
+    }
+    if name.chars().count() > names::MAX_GROUP_NAME_LENGTH {
+        return Err(AppError::Input("group name is too long"));
+    }
+
+    let public_id = state
+        .db
+        .create_group(name, user.id)
+        .await
+        .reject("could not create group")?;
+    let group = load_group(&state, &public_id).await?;
+    let title = group.name.clone();
+    let page = group_view(&state, group, user.id, &locale).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "HX-Push-Url",
+        names::group_url(&public_id)
+            .parse()
+            .reject("could not build group URL header")?,
+    );
+    Ok((headers, views::titled(&title, page)))
+}
+
+async fn group_page(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let Some(group) = state
+        .db
+        .get_group(&public_id)
+        .await
+        .reject("could not get group")?
+    else {
+        let title = t!("group.not_found_title", locale = &locale);
+        return Ok(views::render(
+            is_htmx,
+            &title,
+            group_views::group_not_available(&locale),
+            &locale,
+            Some(&nav_user(&user)),
+        ));
+    };
+
+    let title = group.name.clone();
+    let page = group_view(&state, group, user.id, &locale).await?;
+    Ok(views::render(
+        is_htmx,
+        &title,
+        page,
+        &locale,
+        Some(&nav_user(&user)),
+    ))
+}
+
+async fn delete_group(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<impl IntoResponse, AppError> {
+    let group = load_owned_group(&state, &public_id, user.id).await?;
+    state
+        .db
+        .delete_group(group.id)
+        .await
+        .reject("could not delete group")?;
+
+    let groups = state
+        .db
+        .groups_for_user(user.id)
+        .await
+        .reject("could not get groups")?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "HX-Push-Url",
+        names::GROUPS_URL
+            .parse()
+            .reject("could not build groups URL header")?,
+    );
+    let title = t!("group.title", locale = &locale);
+    Ok((
+        headers,
+        views::titled(&title, group_views::groups_page(&groups, &locale)),
+    ))
+}
+
+/// Assign a quiz from the instructor's library to the group.
+async fn assign_quiz(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+    Form(body): Form<AssignQuizBody>,
+) -> Result<Markup, AppError> {
+    let group = load_owned_group(&state, &public_id, user.id).await?;
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&body.quiz_id)
+        .await
+        .reject_input("quiz not found")?;
+    if !state
+        .db
+        .user_has_quiz(user.id, quiz_id)
+        .await
+        .reject("could not check library")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    state
+        .db
+        .assign_group_quiz(group.id, quiz_id)
+        .await
+        .reject("could not assign quiz")?;
+
+    let title = group.name.clone();
+    Ok(views::titled(
+        &title,
+        group_view(&state, group, user.id, &locale).await?,
+    ))
+}
+
+async fn unassign_quiz(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path((public_id, quiz_public_id)): Path<(String, String)>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let group = load_owned_group(&state, &public_id, user.id).await?;
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&quiz_public_id)
+        .await
+        .reject_input("quiz not found")?;
+
+    state
+        .db
+        .unassign_group_quiz(group.id, quiz_id)
+        .await
+        .reject("could not unassign quiz")?;
+
+    let title = group.name.clone();
+    Ok(views::titled(
+        &title,
+        group_view(&state, group, user.id, &locale).await?,
+    ))
+}
+
+/// The instructor removes a member, or a member leaves.
+async fn remove_member(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path((public_id, member_id)): Path<(String, i32)>,
+    Locale(locale): Locale,
+) -> Result<impl IntoResponse, AppError> {
+    let group = load_group(&state, &public_id).await?;
+    let is_owner = group.owner_id == user.id;
+    if !is_owner && member_id != user.id {
+        return Err(AppError::Forbidden);
+    }
+
+    state
+        .db
+        .remove_group_member(group.id, member_id)
+        .await
+        .reject("could not remove group member")?;
+
+    let mut headers = HeaderMap::new();
+    if is_owner {
+        let title = group.name.clone();
+        let page = group_view(&state, group, user.id, &locale).await?;
+        return Ok((headers, views::titled(&title, page)));
+    }
+
+    let groups = state
+        .db
+        .groups_for_user(user.id)
+        .await
+        .reject("could not get groups")?;
+    headers.insert(
+        "HX-Push-Url",
+        names::GROUPS_URL
+            .parse()
+            .reject("could not build groups URL header")?,
+    );
+    let title = t!("group.title", locale = &locale);
+    Ok((
+        headers,
+        views::titled(&title, group_views::groups_page(&groups, &locale)),
+    ))
+}
+
+async fn invite_page(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Path(invite_code): Path<String>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let group = state
+        .db
+        .get_group_by_invite(&invite_code)
+        .await
+        .reject("could not get group")?;
+
+    let page = match group {
+        Some(group) => {
+            let is_member = group.owner_id == user.id
+                || state
+                    .db
+                    .is_group_member(group.id, user.id)
+                    .await
+                    .reject("could not check group membership")?;
+            group_views::invite_page(&group, is_member, &locale)
+        }
+        None => group_views::group_not_available(&locale),
+    };
+
+    let title = t!("group.invite_title", locale = &locale);
+    Ok(views::render(
+        is_htmx,
+        &title,
+        page,
+        &locale,
+        Some(&nav_user(&user)),
+    ))
+}
+
+async fn join_group(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(invite_code): Path<String>,
+    Locale(locale): Locale,
+) -> Result<impl IntoResponse, AppError> {
+    let group = state
+        .db
+        .get_group_by_invite(&invite_code)
+        .await
+        .reject("could not get group")?
+        .ok_or(AppError::Input("group not found"))?;
+
+    if group.owner_id != user.id {
+        state
+            .db
+            .join_group(group.id, user.id)
+            .await
+            .reject("could not join group")?;
+    }
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "HX-Push-Url",
+        names::group_url(&group.public_id)
+            .parse()
+            .reject("could not build group URL header")?,
+    );
+    let title = group.name.clone();
+    let page = group_view(&state, group, user.id, &locale).await?;
+    Ok((headers, views::titled(&title, page)))
+}
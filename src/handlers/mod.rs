@@ -1,4 +1,5 @@
 pub mod account;
 pub mod admin;
+pub mod group;
 pub mod homepage;
 pub mod quiz;
@@ -0,0 +1,96 @@
+use axum::extract::{Form, State};
+use maud::Markup;
+use rust_i18n::t;
+
+use super::DailyGoalBody;
+use crate::{
+    db::DailyGoal,
+    extractors::{AuthGuard, IsHtmx, Locale},
+    names,
+    rejections::{AppError, ResultExt},
+    views,
+    views::quiz as quiz_views,
+    AppState,
+};
+
+pub(crate) async fn activity_page(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let summary = state
+        .db
+        .get_activity_summary(user.id, names::HEATMAP_WEEKS * 7)
+        .await
+        .reject("could not get activity")?;
+
+    let title = t!("activity.title", locale = &locale);
+    let nav_user = views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    };
+    Ok(views::render(
+        is_htmx,
+        &title,
+        quiz_views::activity_page(&summary, &locale),
+        &locale,
+        Some(&nav_user),
+    ))
+}
+
+/// Today's goal progress and streak, loaded into the quiz list on the home page.
+pub(crate) async fn today_progress(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let summary = state
+        .db
+        .get_activity_summary(user.id, 1)
+        .await
+        .reject("could not get activity")?;
+
+    Ok(quiz_views::today_progress(&summary, &locale))
+}
+
+pub(crate) async fn save_daily_goal(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+    Form(body): Form<DailyGoalBody>,
+) -> Result<Markup, AppError> {
+    let goal = if body.kind == names::NO_DAILY_GOAL {
+        None
+    } else {
+        if !names::DAILY_GOAL_KINDS.contains(&body.kind.as_str()) {
+            return Err(AppError::Input("invalid daily goal kind"));
+        }
+        let target: i32 = body
+            .target
+            .trim()
+            .parse()
+            .map_err(|_| AppError::Input("daily goal must be a number"))?;
+        if !(1..=names::MAX_DAILY_GOAL).contains(&target) {
+            return Err(AppError::Input("daily goal is out of range"));
+        }
+        Some(DailyGoal {
+            kind: body.kind,
+            target,
+        })
+    };
+
+    state
+        .db
+        .set_daily_goal(user.id, goal.as_ref())
+        .await
+        .reject("could not save daily goal")?;
+
+    let summary = state
+        .db
+        .get_activity_summary(user.id, names::HEATMAP_WEEKS * 7)
+        .await
+        .reject("could not get activity")?;
+
+    Ok(quiz_views::activity_page(&summary, &locale))
+}
@@ -0,0 +1,384 @@
+use axum::{
+    extract::{Form, Path, State},
+    http::{header::SET_COOKIE, HeaderMap},
+    response::IntoResponse,
+};
+use maud::Markup;
+use rust_i18n::t;
+
+use super::{is_iso_date, AssignmentBody};
+use crate::{
+    analytics,
+    db::{
+        models::AuthUser, AssignmentModel, AssignmentSettings, AssignmentStatus, AssignmentTarget,
+    },
+    extractors::{AuthGuard, IsHtmx, Locale},
+    names,
+    rejections::{AppError, ResultExt},
+    utils, views,
+    views::quiz as quiz_views,
+    AppState,
+};
+
+fn nav_user(user: &AuthUser) -> views::NavUser<'_> {
+    views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    }
+}
+
+async fn load_assignment(state: &AppState, public_id: &str) -> Result<AssignmentModel, AppError> {
+    state
+        .db
+        .get_assignment(public_id)
+        .await
+        .reject("could not get assignment")?
+        .ok_or(AppError::Input("assignment not found"))
+}
+
+/// What the user has to do, what they gave, and the form to give more.
+async fn assignments_view(
+    state: &AppState,
+    user_id: i32,
+    error: Option<String>,
+    locale: &str,
+) -> Result<Markup, AppError> {
+    let (assigned, created, library, groups) = tokio::try_join!(
+        state.db.assignments_for_learner(user_id),
+        state.db.assignments_created_by(user_id),
+        state.db.quizzes(user_id),
+        state.db.groups_for_user(user_id),
+    )
+    .reject("could not get assignments")?;
+
+    let assigned_ids: Vec<i32> = assigned.iter().map(|a| a.id).collect();
+    let created_ids: Vec<i32> = created.iter().map(|a| a.id).collect();
+    let (mine, learners) = tokio::try_join!(
+        state.db.assignment_learners(&assigned_ids, Some(user_id)),
+        state.db.assignment_learners(&created_ids, None),
+    )
+    .reject("could not get assignment progress")?;
+
+    let mut to_do = Vec::new();
+    let mut completed = Vec::new();
+    for assignment in assigned {
+        let progress = mine.iter().find(|l| l.assignment_id == assignment.id);
+        let best_score = progress.and_then(|l| l.best_score);
+        let completed_on = progress.and_then(|l| l.completed_on.clone());
+        let status = analytics::assignment_status(
+            &assignment.due_date,
+            assignment.days_left,
+            completed_on.as_deref(),
+        );
+        let row = quiz_views::LearnerAssignmentRow {
+            assignment,
+            best_score,
+            completed_on,
+            status,
+        };
+        match status {
+            AssignmentStatus::Completed { .. } => completed.push(row),
+            _ => to_do.push(row),
+        }
+    }
+
+    let created = created
+        .into_iter()
+        .map(|assignment| {
+            let statuses: Vec<_> = learners
+                .iter()
+                .filter(|l| l.assignment_id == assignment.id)
+                .map(|l| {
+                    analytics::assignment_status(
+                        &assignment.due_date,
+                        assignment.days_left,
+                        l.completed_on.as_deref(),
+                    )
+                })
+                .collect();
+            quiz_views::CreatedAssignmentRow {
+                learners: statuses.len(),
+                completed: statuses
+                    .iter()
+                    .filter(|s| matches!(s, AssignmentStatus::Completed { .. }))
+                    .count(),
+                overdue: statuses
+                    .iter()
+                    .filter(|s| **s == AssignmentStatus::Overdue)
+                    .count(),
+                assignment,
+            }
+        })
+        .collect();
+
+    Ok(quiz_views::assignments_page(
+        quiz_views::AssignmentsPageData {
+            to_do,
+            completed,
+            created,
+            library,
+            groups,
+            min_questions: state.session_limits.min_questions,
+            max_questions: state.session_limits.max_questions,
+            error,
+        },
+        locale,
+    ))
+}
+
+pub(crate) async fn assignments_page(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let page = assignments_view(&state, user.id, None, &locale).await?;
+    let title = t!("assignment.title", locale = &locale);
+    Ok(views::render(
+        is_htmx,
+        &title,
+        page,
+        &locale,
+        Some(&nav_user(&user)),
+    ))
+}
+
+/// Assign a quiz from the user's library to a group they lead or to one of its members.
+pub(crate) async fn create_assignment(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+    Form(body): Form<AssignmentBody>,
+) -> Result<Markup, AppError> {
+    let question_count: i32 = body
+        .question_count
+        .trim()
+        .parse()
+        .reject_input("question count must be a number")?;
+    let limits = state.session_limits;
+    if !(limits.min_questions..=limits.max_questions).contains(&question_count) {
+        return Err(AppError::Input("question count out of range"));
+    }
+    let target_score: i32 = body
+        .target_score
+        .trim()
+        .parse()
+        .reject_input("target score must be a number")?;
+    if !(1..=100).contains(&target_score) {
+        return Err(AppError::Input("target score must be between 1 and 100"));
+    }
+    if !names::SELECTION_MODES.contains(&body.selection_mode.as_str()) {
+        return Err(AppError::Input("invalid selection mode"));
+    }
+    let due_date = body.due_date.trim();
+    if !is_iso_date(due_date) {
+        return Err(AppError::Input("due date must be YYYY-MM-DD"));
+    }
+
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&body.quiz_id)
+        .await
+        .reject_input("quiz not found")?;
+    if !state
+        .db
+        .user_has_quiz(user.id, quiz_id)
+        .await
+        .reject("could not check library")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let group = body.group.trim();
+    let target = if !group.is_empty() {
+        let group = state
+            .db
+            .get_group(group)
+            .await
+            .reject("could not get group")?
+            .ok_or(AppError::Input("group not found"))?;
+        if group.owner_id != user.id {
+            return Err(AppError::Forbidden);
+        }
+        AssignmentTarget::Group(group.id)
+    } else {
+        // Same answer whether or not the email is registered, so it cannot probe accounts
+        let email = body.email.trim().to_lowercase();
+        let learner = state
+            .db
+            .find_led_group_member(user.id, &email)
+            .await
+            .reject("could not look up learner")?;
+        match learner {
+            Some(learner_id) => AssignmentTarget::User(learner_id),
+            None => {
+                let error = t!("assignment.error_learner", locale = &locale).to_string();
+                let page = assignments_view(&state, user.id, Some(error), &locale).await?;
+                let title = t!("assignment.title", locale = &locale);
+                return Ok(views::titled(&title, page));
+            }
+        }
+    };
+
+    let settings = AssignmentSettings {
+        quiz_id,
+        due_date: due_date.to_string(),
+        question_count,
+        selection_mode: body.selection_mode,
+        target_score,
+    };
+    let created = state
+        .db
+        .create_assignment(user.id, &target, &settings)
+        .await
+        .reject("could not create assignment")?;
+    let error = created
+        .is_none()
+        .then(|| t!("assignment.error_past_due", locale = &locale).to_string());
+
+    let title = t!("assignment.title", locale = &locale);
+    Ok(views::titled(
+        &title,
+        assignments_view(&state, user.id, error, &locale).await?,
+    ))
+}
+
+/// Per-learner completion of an assignment, for the user who gave it.
+pub(crate) async fn assignment_page(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let Some(assignment) = state
+        .db
+        .get_assignment(&public_id)
+        .await
+        .reject("could not get assignment")?
+    else {
+        let title = t!("assignment.not_found_title", locale = &locale);
+        return Ok(views::render(
+            is_htmx,
+            &title,
+            quiz_views::assignment_not_available(&locale),
+            &locale,
+            Some(&nav_user(&user)),
+        ));
+    };
+    if assignment.created_by != user.id {
+        return Err(AppError::Forbidden);
+    }
+
+    let learners = state
+        .db
+        .assignment_learners(&[assignment.id], None)
+        .await
+        .reject("could not get assignment progress")?
+        .into_iter()
+        .map(|learner| {
+            let status = analytics::assignment_status(
+                &assignment.due_date,
+                assignment.days_left,
+                learner.completed_on.as_deref(),
+            );
+            (learner, status)
+        })
+        .collect::<Vec<_>>();
+
+    let title = assignment.quiz_name.clone();
+    Ok(views::render(
+        is_htmx,
+        &title,
+        quiz_views::assignment_page(&assignment, &learners, &locale),
+        &locale,
+        Some(&nav_user(&user)),
+    ))
+}
+
+pub(crate) async fn delete_assignment(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<impl IntoResponse, AppError> {
+    let assignment = load_assignment(&state, &public_id).await?;
+    if assignment.created_by != user.id {
+        return Err(AppError::Forbidden);
+    }
+    state
+        .db
+        .delete_assignment(assignment.id)
+        .await
+        .reject("could not delete assignment")?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "HX-Push-Url",
+        names::ASSIGNMENTS_URL
+            .parse()
+            .reject("could not build assignments URL header")?,
+    );
+    let title = t!("assignment.title", locale = &locale);
+    let page = assignments_view(&state, user.id, None, &locale).await?;
+    Ok((headers, views::titled(&title, page)))
+}
+
+/// Start a session with the assignment's settings that counts toward it.
+pub(crate) async fn start_assignment(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<axum::response::Response, AppError> {
+    let assignment = load_assignment(&state, &public_id).await?;
+    if !state
+        .db
+        .is_assignment_learner(assignment.id, user.id)
+        .await
+        .reject("could not check assignment learner")?
+    {
+        return Err(AppError::Forbidden);
+    }
+    // The learner may have removed the quiz from their library since it was assigned
+    state
+        .db
+        .add_quiz_to_library(user.id, assignment.quiz_id)
+        .await
+        .reject("could not add quiz to library")?;
+
+    let suffix = &ulid::Ulid::new().to_string()[..6];
+    let session_name = format!("assignment-{}", suffix.to_lowercase());
+    let (token, session_id) = state
+        .db
+        .create_assignment_session(
+            &session_name,
+            &assignment,
+            names::DEFAULT_STUDY_MODE,
+            user.id,
+        )
+        .await
+        .reject("could not create assignment session")?;
+
+    let page = views::titled(
+        &assignment.quiz_name,
+        super::question::question(&state.db, session_id, assignment.quiz_id, 0, false, &locale)
+            .await?,
+    );
+    let cookie = utils::cookie(
+        names::QUIZ_SESSION_COOKIE_NAME,
+        &token,
+        state.secure_cookies,
+    )
+    .reject("could not build session cookie")?;
+    let mut headers = HeaderMap::new();
+    headers.insert(SET_COOKIE, cookie);
+    headers.insert(
+        "HX-Push-Url",
+        names::quiz_page_url(&assignment.quiz_public_id)
+            .parse()
+            .reject("could not build quiz URL header")?,
+    );
+
+    Ok((headers, page).into_response())
+}
@@ -0,0 +1,123 @@
+use axum::{
+    extract::{Path, State},
+    http::{header::SET_COOKIE, HeaderMap},
+    response::IntoResponse,
+};
+use maud::Markup;
+use rust_i18n::t;
+
+use crate::{
+    extractors::{AuthGuard, IsHtmx, Locale},
+    names,
+    rejections::{AppError, ResultExt},
+    utils, views,
+    views::quiz as quiz_views,
+    AppState,
+};
+
+pub(crate) async fn bookmarks_page(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let bookmarks = state
+        .db
+        .get_user_bookmarks(user.id)
+        .await
+        .reject("could not get bookmarks")?;
+
+    let title = t!("bookmarks.title", locale = &locale);
+    let nav_user = views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    };
+    Ok(views::render(
+        is_htmx,
+        &title,
+        quiz_views::bookmarks_page(&bookmarks, &locale),
+        &locale,
+        Some(&nav_user),
+    ))
+}
+
+pub(crate) async fn start_bookmarks_session(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<axum::response::Response, AppError> {
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&public_id)
+        .await
+        .reject("quiz not found")?;
+
+    if !state
+        .db
+        .user_has_quiz(user.id, quiz_id)
+        .await
+        .reject("could not check access")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let bookmarked_ids = state
+        .db
+        .get_quiz_bookmarks(user.id, quiz_id)
+        .await
+        .reject("could not get bookmarked questions")?;
+
+    if bookmarked_ids.is_empty() {
+        return Err(AppError::Input("no bookmarked questions"));
+    }
+
+    let suffix = &ulid::Ulid::new().to_string()[..6];
+    let session_name = format!("bookmarks-{}", suffix.to_lowercase());
+
+    let token = state
+        .db
+        .create_session_with_questions(
+            &session_name,
+            quiz_id,
+            &bookmarked_ids,
+            "bookmarked",
+            names::DEFAULT_STUDY_MODE,
+            user.id,
+        )
+        .await
+        .reject("could not create bookmarked session")?;
+
+    let new_session = state
+        .db
+        .get_session(&token)
+        .await
+        .reject("could not get new session")?;
+
+    let quiz_name = state
+        .db
+        .quiz_name(quiz_id)
+        .await
+        .reject("could not get quiz name")?;
+
+    let page = views::titled(
+        &quiz_name,
+        super::question::question(&state.db, new_session.id, quiz_id, 0, false, &locale).await?,
+    );
+    let cookie = utils::cookie(
+        names::QUIZ_SESSION_COOKIE_NAME,
+        &token,
+        state.secure_cookies,
+    )
+    .reject("could not build session cookie")?;
+    let mut headers = HeaderMap::new();
+    headers.insert(SET_COOKIE, cookie);
+    headers.insert(
+        "HX-Push-Url",
+        names::quiz_page_url(&public_id)
+            .parse()
+            .reject("could not build quiz URL header")?,
+    );
+
+    Ok((headers, page).into_response())
+}
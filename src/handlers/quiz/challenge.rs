@@ -0,0 +1,196 @@
+use axum::{
+    extract::{Path, State},
+    http::{header::SET_COOKIE, HeaderMap},
+    response::IntoResponse,
+};
+use maud::Markup;
+use rust_i18n::t;
+
+use crate::{
+    extractors::{AuthGuard, IsHtmx, Locale},
+    names,
+    rejections::{AppError, ResultExt},
+    utils, views,
+    views::quiz as quiz_views,
+    AppState,
+};
+
+pub(crate) async fn create_challenge(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(session_id): Path<i32>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    if !state
+        .db
+        .verify_session_owner(session_id, user.id)
+        .await
+        .reject("could not verify session owner")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let (slots_count, current_idx) = tokio::try_join!(
+        state.db.questions_count_for_session(session_id),
+        state.db.current_question_index(session_id),
+    )
+    .reject("could not get session progress")?;
+
+    if current_idx < slots_count {
+        return Err(AppError::Input(
+            "finish the session before challenging others",
+        ));
+    }
+
+    let challenge_id = state
+        .db
+        .get_or_create_challenge(session_id, user.id)
+        .await
+        .reject("could not create challenge")?;
+
+    Ok(quiz_views::challenge_link(&challenge_id, &locale))
+}
+
+pub(crate) async fn challenge_page(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let title = t!("challenge.title", locale = &locale);
+    let nav_user = views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    };
+
+    let Some(challenge) = state
+        .db
+        .get_challenge(&public_id)
+        .await
+        .reject("could not get challenge")?
+    else {
+        return Ok(views::render(
+            is_htmx,
+            &title,
+            quiz_views::challenge_not_available(&locale),
+            &locale,
+            Some(&nav_user),
+        ));
+    };
+
+    let (standings, has_quiz) = tokio::try_join!(
+        state.db.get_challenge_standings(challenge.id),
+        state.db.user_has_quiz(user.id, challenge.quiz_id),
+    )
+    .reject("could not get challenge standings")?;
+
+    let page = quiz_views::challenge_page(
+        quiz_views::ChallengePageData {
+            challenge_id: challenge.public_id,
+            quiz_name: challenge.quiz_name,
+            creator_name: challenge.creator_name,
+            questions_count: challenge.question_ids.len(),
+            standings,
+            user_id: user.id,
+            can_join: has_quiz || challenge.is_joinable,
+        },
+        &locale,
+    );
+
+    Ok(views::render(
+        is_htmx,
+        &title,
+        page,
+        &locale,
+        Some(&nav_user),
+    ))
+}
+
+pub(crate) async fn accept_challenge(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<axum::response::Response, AppError> {
+    let challenge = state
+        .db
+        .get_challenge(&public_id)
+        .await
+        .reject("could not get challenge")?
+        .ok_or(AppError::Input("challenge not found"))?;
+
+    let (standings, has_quiz) = tokio::try_join!(
+        state.db.get_challenge_standings(challenge.id),
+        state.db.user_has_quiz(user.id, challenge.quiz_id),
+    )
+    .reject("could not get challenge standings")?;
+
+    if standings.iter().any(|s| s.user_id == user.id) {
+        return Err(AppError::Input("challenge already accepted"));
+    }
+
+    if !has_quiz {
+        if !challenge.is_joinable {
+            return Err(AppError::Forbidden);
+        }
+        state
+            .db
+            .add_quiz_to_library(user.id, challenge.quiz_id)
+            .await
+            .reject("could not add quiz to library")?;
+    }
+
+    let suffix = &ulid::Ulid::new().to_string()[..6];
+    let session_name = format!("challenge-{}", suffix.to_lowercase());
+
+    let token = state
+        .db
+        .create_seeded_session(
+            &session_name,
+            challenge.quiz_id,
+            &challenge.question_ids,
+            challenge.shuffle_seed,
+            "challenge",
+            &challenge.study_mode,
+            user.id,
+            Some(challenge.id),
+        )
+        .await
+        .reject("could not create challenge session")?;
+
+    let new_session = state
+        .db
+        .get_session(&token)
+        .await
+        .reject("could not get new session")?;
+
+    let page = views::titled(
+        &challenge.quiz_name,
+        super::question::question(
+            &state.db,
+            new_session.id,
+            challenge.quiz_id,
+            0,
+            false,
+            &locale,
+        )
+        .await?,
+    );
+    let cookie = utils::cookie(
+        names::QUIZ_SESSION_COOKIE_NAME,
+        &token,
+        state.secure_cookies,
+    )
+    .reject("could not build session cookie")?;
+    let mut headers = HeaderMap::new();
+    headers.insert(SET_COOKIE, cookie);
+    headers.insert(
+        "HX-Push-Url",
+        names::quiz_page_url(&challenge.quiz_public_id)
+            .parse()
+            .reject("could not build quiz URL header")?,
+    );
+
+    Ok((headers, page).into_response())
+}
@@ -0,0 +1,281 @@
+use axum::{
+    extract::{Form, Path, State},
+    http::{HeaderMap, HeaderValue},
+    response::IntoResponse,
+};
+use maud::Markup;
+use rust_i18n::t;
+
+use super::CollaboratorBody;
+use crate::{
+    db::QuizAccess,
+    extractors::{AuthGuard, IsHtmx, Locale},
+    names,
+    rejections::{AppError, ResultExt},
+    views,
+    views::homepage as homepage_views,
+    views::quiz as quiz_views,
+    AppState,
+};
+
+async fn quiz_access(
+    state: &AppState,
+    public_id: &str,
+    user_id: i32,
+) -> Result<QuizAccess, AppError> {
+    state
+        .db
+        .quiz_access(public_id, user_id)
+        .await
+        .reject("could not check quiz access")?
+        .ok_or(AppError::Forbidden)
+}
+
+async fn collaborators_view(
+    state: &AppState,
+    public_id: &str,
+    user_id: i32,
+    access: QuizAccess,
+    error: Option<String>,
+    locale: &str,
+) -> Result<Markup, AppError> {
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(public_id)
+        .await
+        .reject("quiz not found")?;
+
+    let (quiz_name, collaborators, changes) = tokio::try_join!(
+        state.db.quiz_name(quiz_id),
+        state.db.quiz_collaborators(quiz_id),
+        state.db.quiz_changes(quiz_id),
+    )
+    .reject("could not get collaborators")?;
+    // Invited emails are for the owner's eyes only
+    let invitations = if access == QuizAccess::Owner {
+        state
+            .db
+            .quiz_invitations(quiz_id)
+            .await
+            .reject("could not get invitations")?
+    } else {
+        Vec::new()
+    };
+
+    Ok(quiz_views::collaborators_page(
+        quiz_views::CollaboratorsPageData {
+            quiz_name,
+            quiz_id: public_id.to_string(),
+            user_id,
+            access,
+            collaborators,
+            invitations,
+            changes,
+            error,
+        },
+        locale,
+    ))
+}
+
+/// Collaborators and change history, for the owner and every collaborator.
+pub(crate) async fn collaborators_page(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let access = quiz_access(&state, &public_id, user.id).await?;
+    let page = collaborators_view(&state, &public_id, user.id, access, None, &locale).await?;
+
+    let title = t!("collaborator.title", locale = &locale);
+    let nav_user = views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    };
+    Ok(views::render(
+        is_htmx,
+        &title,
+        page,
+        &locale,
+        Some(&nav_user),
+    ))
+}
+
+/// The owner invites an email address, or changes the role of a pending invitation. The
+/// response is the same whether or not the email belongs to an account.
+pub(crate) async fn invite_collaborator(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+    Form(body): Form<CollaboratorBody>,
+) -> Result<Markup, AppError> {
+    let access = quiz_access(&state, &public_id, user.id).await?;
+    if access != QuizAccess::Owner {
+        return Err(AppError::Forbidden);
+    }
+    if !names::COLLABORATOR_ROLES.contains(&body.role.as_str()) {
+        return Err(AppError::Input("invalid collaborator role"));
+    }
+    let email = body.email.trim().to_lowercase();
+    if !email.contains('@') {
+        return Err(AppError::Input("invalid email"));
+    }
+
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&public_id)
+        .await
+        .reject("quiz not found")?;
+    let error = if email == user.email.to_lowercase() {
+        Some(t!("collaborator.error_self", locale = &locale).to_string())
+    } else {
+        state
+            .db
+            .invite_quiz_collaborator(quiz_id, &email, &body.role, user.id)
+            .await
+            .reject("could not invite collaborator")?;
+        None
+    };
+
+    let title = t!("collaborator.title", locale = &locale);
+    Ok(views::titled(
+        &title,
+        collaborators_view(&state, &public_id, user.id, access, error, &locale).await?,
+    ))
+}
+
+/// The owner withdraws a pending invitation.
+pub(crate) async fn cancel_invitation(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path((public_id, invitation_id)): Path<(String, i32)>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let access = quiz_access(&state, &public_id, user.id).await?;
+    if access != QuizAccess::Owner {
+        return Err(AppError::Forbidden);
+    }
+
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&public_id)
+        .await
+        .reject("quiz not found")?;
+    state
+        .db
+        .cancel_quiz_invitation(quiz_id, invitation_id)
+        .await
+        .reject("could not cancel invitation")?;
+
+    let title = t!("collaborator.title", locale = &locale);
+    Ok(views::titled(
+        &title,
+        collaborators_view(&state, &public_id, user.id, access, None, &locale).await?,
+    ))
+}
+
+/// Invitations to collaborate that the user received, shown above their quiz list.
+pub(crate) async fn invitations(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let invitations = state
+        .db
+        .received_invitations(user.id)
+        .await
+        .reject("could not get invitations")?;
+
+    Ok(quiz_views::received_invitations(&invitations, &locale))
+}
+
+pub(crate) async fn accept_invitation(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(invitation_id): Path<i32>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    if !state
+        .db
+        .accept_quiz_invitation(invitation_id, user.id)
+        .await
+        .reject("could not accept invitation")?
+    {
+        return Err(AppError::Input("invitation not found"));
+    }
+
+    let quizzes = state
+        .db
+        .quizzes(user.id)
+        .await
+        .reject("failed to get quizzes")?;
+    Ok(views::titled(
+        "My Quizzes",
+        homepage_views::quiz_list(quizzes, &locale),
+    ))
+}
+
+pub(crate) async fn decline_invitation(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(invitation_id): Path<i32>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    state
+        .db
+        .decline_quiz_invitation(invitation_id, user.id)
+        .await
+        .reject("could not decline invitation")?;
+
+    let invitations = state
+        .db
+        .received_invitations(user.id)
+        .await
+        .reject("could not get invitations")?;
+    Ok(quiz_views::received_invitations(&invitations, &locale))
+}
+
+/// The owner revokes a collaborator, or a collaborator leaves.
+pub(crate) async fn remove_collaborator(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path((public_id, collaborator_id)): Path<(String, i32)>,
+    Locale(locale): Locale,
+) -> Result<impl IntoResponse, AppError> {
+    let access = quiz_access(&state, &public_id, user.id).await?;
+    let is_owner = access == QuizAccess::Owner;
+    if !is_owner && collaborator_id != user.id {
+        return Err(AppError::Forbidden);
+    }
+
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&public_id)
+        .await
+        .reject("quiz not found")?;
+    state
+        .db
+        .remove_quiz_collaborator(quiz_id, collaborator_id, user.id)
+        .await
+        .reject("could not remove collaborator")?;
+
+    let mut headers = HeaderMap::new();
+    if is_owner {
+        let title = t!("collaborator.title", locale = &locale);
+        let page = collaborators_view(&state, &public_id, user.id, access, None, &locale).await?;
+        return Ok((headers, views::titled(&title, page)));
+    }
+
+    let quizzes = state
+        .db
+        .quizzes(user.id)
+        .await
+        .reject("failed to get quizzes")?;
+    headers.insert("HX-Push-Url", HeaderValue::from_static("/"));
+    Ok((
+        headers,
+        views::titled("My Quizzes", homepage_views::quiz_list(quizzes, &locale)),
+    ))
+}
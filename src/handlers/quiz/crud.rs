@@ -1,20 +1,23 @@
 use std::collections::HashMap;
 
 use axum::{
-    extract::{Form, Multipart, State},
+    extract::{Form, Multipart, Path, State},
     http::HeaderMap,
     response::IntoResponse,
-    routing::{delete, patch, post},
+    routing::{delete, get, patch, post},
     Router,
 };
 use rust_i18n::t;
 use serde::Deserialize;
 
 use crate::{
-    extractors::{AuthGuard, Locale},
+    db::{QuizAccess, QuizSessionSizes},
+    extractors::{AuthGuard, IsHtmx, Locale},
     models, names,
     rejections::{AppError, ResultExt},
-    views, AppState,
+    views,
+    views::quiz as quiz_views,
+    AppState,
 };
 
 use crate::views::homepage as homepage_views;
@@ -26,6 +29,10 @@ pub fn routes() -> Router<AppState> {
         .route("/create-quiz", post(create_quiz))
         .route("/delete-quiz/{id}", delete(delete_quiz))
         .route("/rename-quiz/{id}", patch(rename_quiz))
+        .route(
+            "/quiz/{id}/settings",
+            get(session_size_settings).post(update_session_size_settings),
+        )
 }
 
 async fn create_quiz(
@@ -83,7 +90,7 @@ async fn create_quiz(
         headers,
         views::titled(
             "Quiz Dashboard",
-            dashboard::dashboard(&state.db, quiz_id, &public_id, &locale).await?,
+            dashboard::dashboard(&state.db, quiz_id, &public_id, user.id, &locale).await?,
         ),
     ))
 }
@@ -92,7 +99,7 @@ async fn delete_quiz(
     AuthGuard(user): AuthGuard,
     State(state): State<AppState>,
     Locale(locale): Locale,
-    axum::extract::Path(public_id): axum::extract::Path<String>,
+    Path(public_id): Path<String>,
 ) -> Result<maud::Markup, AppError> {
     let is_owner = state
         .db
@@ -153,14 +160,17 @@ async fn rename_quiz(
     AuthGuard(user): AuthGuard,
     State(state): State<AppState>,
     Locale(locale): Locale,
-    axum::extract::Path(public_id): axum::extract::Path<String>,
+    Path(public_id): Path<String>,
     Form(body): Form<RenameQuizBody>,
 ) -> Result<maud::Markup, AppError> {
-    state
+    if !state
         .db
         .rename_quiz(&public_id, &body.name, user.id)
         .await
-        .reject("failed to rename quiz")?;
+        .reject("failed to rename quiz")?
+    {
+        return Err(AppError::Forbidden);
+    }
 
     let quizzes = state
         .db
@@ -172,3 +182,138 @@ async fn rename_quiz(
         homepage_views::quiz_list(quizzes, &locale),
     ))
 }
+
+async fn session_size_settings(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+    Path(public_id): Path<String>,
+) -> Result<maud::Markup, AppError> {
+    let page = session_size_settings_page(&state, &public_id, user.id, false, &locale).await?;
+
+    let title = t!("session_sizes.title", locale = &locale);
+    let nav_user = views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    };
+    Ok(views::render(
+        is_htmx,
+        &title,
+        page,
+        &locale,
+        Some(&nav_user),
+    ))
+}
+
+/// Number inputs left blank arrive as empty strings.
+#[derive(Deserialize)]
+struct SessionSizesBody {
+    #[serde(default)]
+    default_size: String,
+    #[serde(default)]
+    min_size: String,
+    #[serde(default)]
+    max_size: String,
+}
+
+fn parse_session_size(value: &str) -> Result<Option<i32>, AppError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse()
+        .map(Some)
+        .reject_input("failed to parse session size")
+}
+
+async fn update_session_size_settings(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Locale(locale): Locale,
+    Path(public_id): Path<String>,
+    Form(body): Form<SessionSizesBody>,
+) -> Result<maud::Markup, AppError> {
+    let sizes = QuizSessionSizes {
+        default_size: parse_session_size(&body.default_size)?,
+        min_size: parse_session_size(&body.min_size)?,
+        max_size: parse_session_size(&body.max_size)?,
+    };
+
+    let limits = state.session_limits;
+    let within_limits = |size: Option<i32>| {
+        size.is_none_or(|n| (limits.min_questions..=limits.max_questions).contains(&n))
+    };
+    if !(within_limits(sizes.default_size)
+        && within_limits(sizes.min_size)
+        && within_limits(sizes.max_size))
+    {
+        return Err(AppError::Input(
+            "session size is outside the instance limits",
+        ));
+    }
+    let min = sizes.min_size.unwrap_or(limits.min_questions);
+    let max = sizes.max_size.unwrap_or(limits.max_questions);
+    if min > max || sizes.default_size.is_some_and(|n| n < min || n > max) {
+        return Err(AppError::Input("session sizes are inconsistent"));
+    }
+
+    if !state
+        .db
+        .update_quiz_session_sizes(&public_id, sizes, user.id)
+        .await
+        .reject("failed to update session sizes")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let page = session_size_settings_page(&state, &public_id, user.id, true, &locale).await?;
+    Ok(views::titled(
+        &t!("session_sizes.title", locale = &locale),
+        page,
+    ))
+}
+
+async fn session_size_settings_page(
+    state: &AppState,
+    public_id: &str,
+    user_id: i32,
+    saved: bool,
+    locale: &str,
+) -> Result<maud::Markup, AppError> {
+    if !state
+        .db
+        .quiz_access(public_id, user_id)
+        .await
+        .reject("failed to check quiz access")?
+        .is_some_and(QuizAccess::can_edit)
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(public_id)
+        .await
+        .reject("quiz not found")?;
+
+    let (quiz_name, sizes) = tokio::try_join!(
+        state.db.quiz_name(quiz_id),
+        state.db.get_quiz_session_sizes(quiz_id),
+    )
+    .reject("could not get session sizes")?;
+
+    Ok(quiz_views::session_size_settings(
+        quiz_views::SessionSizeSettingsData {
+            quiz_name,
+            quiz_id: public_id.to_string(),
+            sizes,
+            effective: super::session::session_size_range(state.session_limits, sizes),
+            min_limit: state.session_limits.min_questions,
+            max_limit: state.session_limits.max_questions,
+            saved,
+        },
+        locale,
+    ))
+}
@@ -1,8 +1,12 @@
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use maud::Markup;
+use rust_i18n::t;
 
+use super::{compared_session_ids, ReviewQuery};
 use crate::{
+    analytics,
     extractors::{AuthGuard, IsHtmx, Locale},
+    names,
     rejections::{AppError, ResultExt},
     views,
     views::quiz as quiz_views,
@@ -38,7 +42,7 @@ pub(crate) async fn quiz_dashboard(
     Ok(views::render(
         is_htmx,
         "Quiz Dashboard",
-        dashboard(&state.db, quiz_id, &public_id, &locale).await?,
+        dashboard(&state.db, quiz_id, &public_id, user.id, &locale).await?,
         &locale,
         Some(&nav_user),
     ))
@@ -79,6 +83,265 @@ pub(crate) async fn quiz_session_history(
     ))
 }
 
+pub(crate) async fn quiz_calibration(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&public_id)
+        .await
+        .reject("quiz not found")?;
+
+    if !state
+        .db
+        .user_has_quiz(user.id, quiz_id)
+        .await
+        .reject("could not check access")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let (quiz_name, confidence_stats, confident_wrong) = tokio::try_join!(
+        state.db.quiz_name(quiz_id),
+        state.db.get_confidence_stats(quiz_id, user.id),
+        state.db.get_confident_wrong_questions(quiz_id, user.id),
+    )
+    .reject("could not get calibration data")?;
+
+    let page = quiz_views::calibration(
+        quiz_views::CalibrationData {
+            quiz_name,
+            quiz_id: public_id,
+            confidence_stats,
+            confident_wrong,
+        },
+        &locale,
+    );
+
+    let nav_user = views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    };
+    Ok(views::render(
+        is_htmx,
+        "Calibration",
+        page,
+        &locale,
+        Some(&nav_user),
+    ))
+}
+
+pub(crate) async fn quiz_pace(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&public_id)
+        .await
+        .reject("quiz not found")?;
+
+    if !state
+        .db
+        .user_has_quiz(user.id, quiz_id)
+        .await
+        .reject("could not check access")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let (quiz_name, report) = tokio::try_join!(
+        state.db.quiz_name(quiz_id),
+        state.db.get_pace_report(quiz_id, user.id),
+    )
+    .reject("could not get pace report")?;
+
+    let page = quiz_views::pace(
+        quiz_views::PaceData {
+            quiz_name,
+            quiz_id: public_id,
+            report,
+        },
+        &locale,
+    );
+
+    let nav_user = views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    };
+    Ok(views::render(
+        is_htmx,
+        "Pace",
+        page,
+        &locale,
+        Some(&nav_user),
+    ))
+}
+
+pub(crate) async fn quiz_compare_sessions(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Query(params): Query<Vec<(String, String)>>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&public_id)
+        .await
+        .reject("quiz not found")?;
+
+    if !state
+        .db
+        .user_has_quiz(user.id, quiz_id)
+        .await
+        .reject("could not check access")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let session_ids = compared_session_ids(&params);
+    let (quiz_name, comparison) = tokio::try_join!(
+        state.db.quiz_name(quiz_id),
+        state
+            .db
+            .get_session_comparison(quiz_id, user.id, &session_ids),
+    )
+    .reject("could not get session comparison")?;
+
+    let page = quiz_views::session_comparison(
+        quiz_views::SessionComparisonData {
+            quiz_name,
+            quiz_id: public_id,
+            comparison,
+        },
+        &locale,
+    );
+
+    let title = t!("compare.title", locale = &locale);
+    let nav_user = views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    };
+    Ok(views::render(
+        is_htmx,
+        &title,
+        page,
+        &locale,
+        Some(&nav_user),
+    ))
+}
+
+pub(crate) async fn quiz_distractors(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    if state
+        .db
+        .quiz_access(&public_id, user.id)
+        .await
+        .reject("could not check quiz access")?
+        .is_none()
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&public_id)
+        .await
+        .reject("quiz not found")?;
+
+    let (quiz_name, questions) = tokio::try_join!(
+        state.db.quiz_name(quiz_id),
+        state.db.get_distractor_analysis(quiz_id),
+    )
+    .reject("could not get distractor analysis")?;
+
+    let page = quiz_views::distractor_analysis(
+        quiz_views::DistractorAnalysisData {
+            quiz_name,
+            quiz_id: public_id,
+            questions,
+        },
+        &locale,
+    );
+
+    let nav_user = views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    };
+    Ok(views::render(
+        is_htmx,
+        "Distractor Analysis",
+        page,
+        &locale,
+        Some(&nav_user),
+    ))
+}
+
+pub(crate) async fn quiz_item_analysis(
+    AuthGuard(user): AuthGuard,
+    IsHtmx(is_htmx): IsHtmx,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    if state
+        .db
+        .quiz_access(&public_id, user.id)
+        .await
+        .reject("could not check quiz access")?
+        .is_none()
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&public_id)
+        .await
+        .reject("quiz not found")?;
+
+    let (quiz_name, items) = tokio::try_join!(
+        state.db.quiz_name(quiz_id),
+        state.db.get_item_analysis(quiz_id),
+    )
+    .reject("could not get item analysis")?;
+
+    let page = quiz_views::item_analysis(
+        quiz_views::ItemAnalysisData {
+            quiz_name,
+            quiz_id: public_id,
+            items,
+        },
+        &locale,
+    );
+
+    let nav_user = views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    };
+    Ok(views::render(
+        is_htmx,
+        "Item Analysis",
+        page,
+        &locale,
+        Some(&nav_user),
+    ))
+}
+
 pub(crate) async fn session_result(
     AuthGuard(user): AuthGuard,
     State(state): State<AppState>,
@@ -102,6 +365,7 @@ pub(crate) async fn session_result(
         .reject("could not get session")?;
 
     let (
+        slots_count,
         questions_count,
         current_idx,
         correct_answers,
@@ -110,8 +374,10 @@ pub(crate) async fn session_result(
         category_stats,
         quiz_public_id,
         study_time_ms,
+        idle_time_ms,
     ) = tokio::try_join!(
         state.db.questions_count_for_session(session.id),
+        state.db.distinct_questions_for_session(session.id),
         state.db.current_question_index(session.id),
         state.db.correct_answers(session.id),
         state.db.get_answers(session.id),
@@ -119,11 +385,36 @@ pub(crate) async fn session_result(
         state.db.get_category_stats(session.id),
         state.db.quiz_public_id(session.quiz_id),
         state.db.session_study_time(session.id),
+        state.db.session_idle_time(session.id),
     )
     .reject("could not get session result data")?;
 
-    let is_complete = current_idx >= questions_count;
-    let answered_count = current_idx;
+    // Cram re-queues add slots beyond the session's questions; the score only
+    // counts first attempts.
+    let is_complete = current_idx >= slots_count;
+    let answered_count = answers.iter().filter(|a| a.attempt == 1).count() as i32;
+
+    let challenge = match state
+        .db
+        .get_session_challenge_public_id(session.id)
+        .await
+        .reject("could not get session challenge")?
+    {
+        Some(public_id) => state
+            .db
+            .get_challenge(&public_id)
+            .await
+            .reject("could not get challenge")?,
+        None => None,
+    };
+    let challenge_standings = match &challenge {
+        Some(challenge) => state
+            .db
+            .get_challenge_standings(challenge.id)
+            .await
+            .reject("could not get challenge standings")?,
+        None => Vec::new(),
+    };
 
     let page = quiz_views::session_result(
         quiz_views::SessionResultData {
@@ -136,11 +427,15 @@ pub(crate) async fn session_result(
                 .unwrap_or_else(|| "random".to_string()),
             questions_count,
             answered_count,
+            current_idx,
             is_complete,
             correct_answers,
             answers,
             category_stats,
             study_time_ms,
+            idle_time_ms,
+            challenge_id: challenge.map(|c| c.public_id),
+            challenge_standings,
         },
         &locale,
     );
@@ -158,22 +453,95 @@ pub(crate) async fn session_result(
     ))
 }
 
+pub(crate) async fn session_review(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    IsHtmx(is_htmx): IsHtmx,
+    Path(session_id): Path<i32>,
+    Query(query): Query<ReviewQuery>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    if !state
+        .db
+        .verify_session_owner(session_id, user.id)
+        .await
+        .reject("could not verify session owner")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let session = state
+        .db
+        .get_session_by_id(session_id)
+        .await
+        .reject("could not get session")?;
+
+    let (items, quiz_name) = tokio::try_join!(
+        state.db.get_session_review(session.id),
+        state.db.quiz_name(session.quiz_id),
+    )
+    .reject("could not get session review")?;
+
+    let filter = query
+        .filter
+        .filter(|f| names::REVIEW_FILTERS.contains(&f.as_str()))
+        .unwrap_or_else(|| "all".to_string());
+
+    let page = quiz_views::session_review(
+        quiz_views::SessionReviewData {
+            session_name: session.name,
+            session_id,
+            quiz_name,
+            filter,
+            items,
+        },
+        &locale,
+    );
+
+    let title = t!("review.title", locale = &locale);
+    let nav_user = views::NavUser {
+        display_name: &user.display_name,
+        is_admin: user.is_admin,
+    };
+    Ok(views::render(
+        is_htmx,
+        &title,
+        page,
+        &locale,
+        Some(&nav_user),
+    ))
+}
+
 pub async fn dashboard(
     db: &crate::db::Db,
     quiz_id: i32,
     quiz_public_id: &str,
+    user_id: i32,
     locale: &str,
 ) -> Result<Markup, AppError> {
-    let (quiz_name, sessions_count, overall, cat_stats, daily_accuracy, study_time_ms) =
-        tokio::try_join!(
-            db.quiz_name(quiz_id),
-            db.sessions_count(quiz_id),
-            db.get_quiz_overall_stats(quiz_id),
-            db.get_quiz_category_stats(quiz_id),
-            db.get_daily_accuracy(quiz_id),
-            db.quiz_study_time(quiz_id),
-        )
-        .reject("could not get dashboard data")?;
+    let (
+        quiz_name,
+        sessions_count,
+        overall,
+        cat_stats,
+        daily_accuracy,
+        study_time_ms,
+        access,
+        readiness,
+        plan_inputs,
+    ) = tokio::try_join!(
+        db.quiz_name(quiz_id),
+        db.sessions_count(quiz_id),
+        db.get_quiz_overall_stats(quiz_id),
+        db.get_quiz_category_stats(quiz_id),
+        db.get_daily_accuracy(quiz_id),
+        db.quiz_study_time(quiz_id),
+        db.quiz_access(quiz_public_id, user_id),
+        db.get_readiness(quiz_id, user_id),
+        db.get_study_plan_inputs(quiz_id, user_id),
+    )
+    .reject("could not get dashboard data")?;
+    let study_plan = plan_inputs.map(|inputs| analytics::study_plan(inputs, readiness.as_ref()));
 
     Ok(quiz_views::dashboard(
         quiz_views::DashboardData {
@@ -184,6 +552,9 @@ pub async fn dashboard(
             cat_stats,
             daily_accuracy,
             study_time_ms,
+            access,
+            readiness,
+            study_plan,
         },
         locale,
     ))
@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Path, State},
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::IntoResponse,
+};
+
+use crate::{
+    export,
+    extractors::AuthGuard,
+    rejections::{AppError, ResultExt},
+    AppState,
+};
+
+const CSV: &str = "text/csv; charset=utf-8";
+const JSON: &str = "application/json";
+
+/// Download the learner's own answer log or category totals for a quiz, as one of
+/// `names::EXPORT_FILES`.
+pub(crate) async fn export_file(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path((public_id, file)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&public_id)
+        .await
+        .reject("quiz not found")?;
+
+    if !state
+        .db
+        .user_has_quiz(user.id, quiz_id)
+        .await
+        .reject("could not check access")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let (content_type, body) = match file.as_str() {
+        "answers.csv" | "answers.json" => {
+            let entries = state
+                .db
+                .get_answer_log(quiz_id, user.id)
+                .await
+                .reject("could not get answer log")?;
+            if file.ends_with(".csv") {
+                (CSV, export::answers_csv(&entries))
+            } else {
+                (
+                    JSON,
+                    serde_json::to_string_pretty(&entries).reject("could not encode answer log")?,
+                )
+            }
+        }
+        "categories.csv" | "categories.json" => {
+            let categories = state
+                .db
+                .get_category_export(quiz_id, user.id)
+                .await
+                .reject("could not get category stats")?;
+            if file.ends_with(".csv") {
+                (CSV, export::categories_csv(&categories))
+            } else {
+                (
+                    JSON,
+                    serde_json::to_string_pretty(&categories)
+                        .reject("could not encode category stats")?,
+                )
+            }
+        }
+        _ => return Err(AppError::Input("unknown export")),
+    };
+
+    let disposition = format!("attachment; filename=\"quizinart-{public_id}-{file}\"");
+    Ok((
+        [
+            (CONTENT_TYPE, content_type.to_string()),
+            (CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
@@ -1,6 +1,13 @@
+mod activity;
+mod assignment;
+mod bookmarks;
+mod challenge;
+mod collaborator;
 mod crud;
 mod dashboard;
+mod export;
 mod marketplace;
+mod plan;
 mod question;
 mod session;
 mod sharing;
@@ -45,8 +52,13 @@ struct StartSessionBody {
         deserialize_with = "deserialize_string_or_i32"
     )]
     question_count: i32,
+    /// Checkbox: present when the session should cover the whole quiz.
+    #[serde(default)]
+    all_questions: Option<String>,
     #[serde(default = "default_selection_mode")]
     selection_mode: String,
+    #[serde(default = "default_study_mode")]
+    study_mode: String,
 }
 
 fn default_question_count() -> i32 {
@@ -57,14 +69,31 @@ fn default_selection_mode() -> String {
     names::DEFAULT_SELECTION_MODE.to_string()
 }
 
+fn default_study_mode() -> String {
+    names::DEFAULT_STUDY_MODE.to_string()
+}
+
 #[derive(Deserialize)]
 struct SubmitAnswerBody {
+    #[serde(deserialize_with = "deserialize_string_or_i32")]
+    question_id: i32,
+    #[serde(default)]
+    idempotency_key: String,
     #[serde(default)]
     option: Option<String>,
     #[serde(default)]
     options: Vec<String>,
     #[serde(default)]
-    duration_ms: i32,
+    confidence: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SelfGradeBody {
+    #[serde(deserialize_with = "deserialize_string_or_i32")]
+    question_id: i32,
+    #[serde(default)]
+    idempotency_key: String,
+    grade: String,
 }
 
 #[derive(Deserialize)]
@@ -72,6 +101,12 @@ struct RenameSessionBody {
     name: String,
 }
 
+#[derive(Deserialize)]
+struct SaveNoteBody {
+    #[serde(default)]
+    note: String,
+}
+
 #[derive(Deserialize)]
 struct NavigateQuestionQuery {
     question_idx: i32,
@@ -81,6 +116,94 @@ struct NavigateQuestionQuery {
     current_idx: Option<i32>,
 }
 
+#[derive(Deserialize)]
+struct DailyGoalBody {
+    /// One of `names::DAILY_GOAL_KINDS`, or `names::NO_DAILY_GOAL` to clear the goal.
+    kind: String,
+    #[serde(default)]
+    target: String,
+}
+
+#[derive(Deserialize)]
+struct StudyPlanBody {
+    /// `YYYY-MM-DD` from a date input, or empty to clear the plan.
+    #[serde(default)]
+    exam_date: String,
+    #[serde(default)]
+    pass_threshold: String,
+}
+
+/// Whether `date` is a calendar date written as `YYYY-MM-DD`.
+fn is_iso_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return false;
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return false;
+    }
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
+
+#[derive(Deserialize)]
+struct AssignmentBody {
+    quiz_id: String,
+    /// Public id of a group the user leads, or empty to assign to one learner by email.
+    #[serde(default)]
+    group: String,
+    #[serde(default)]
+    email: String,
+    due_date: String,
+    question_count: String,
+    selection_mode: String,
+    target_score: String,
+}
+
+#[derive(Deserialize)]
+struct CollaboratorBody {
+    email: String,
+    /// One of `names::COLLABORATOR_ROLES`
+    role: String,
+}
+
+#[derive(Deserialize)]
+struct ReviewQuery {
+    #[serde(default)]
+    filter: Option<String>,
+}
+
+/// Session ids picked on the session history page (`?sessions=1&sessions=2`), in the
+/// order given, without duplicates and capped at `names::MAX_COMPARED_SESSIONS`.
+fn compared_session_ids(params: &[(String, String)]) -> Vec<i32> {
+    let mut ids = Vec::new();
+    for (key, value) in params {
+        if key == "sessions" {
+            if let Ok(id) = value.parse::<i32>() {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+    ids.truncate(names::MAX_COMPARED_SESSIONS);
+    ids
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .merge(crud::routes())
@@ -88,24 +211,84 @@ pub fn routes() -> Router<AppState> {
         .route("/quiz/{id}", get(question::quiz_page))
         .route("/start-session/{id}", post(session::start_session))
         .route("/submit-answer", post(question::submit_answer_raw))
+        .route(
+            names::SUBMIT_SELF_GRADE_URL,
+            post(question::submit_self_grade),
+        )
         .route("/results/{id}", get(dashboard::session_result))
+        .route("/results/{id}/review", get(dashboard::session_review))
         .route("/resume-session/{id}/{token}", get(session::resume_session))
         .route("/question/{id}", get(question::navigate_question))
         .route("/retry-incorrect/{id}", post(session::retry_incorrect))
         .route("/retry-bookmarked/{id}", post(session::retry_bookmarked))
+        .route("/retake/{id}", post(session::retake_session))
+        .route("/session/{id}/challenge", post(challenge::create_challenge))
+        .route("/challenge/{id}", get(challenge::challenge_page))
+        .route("/challenge/{id}/accept", post(challenge::accept_challenge))
         .route(
             "/toggle-bookmark/{session_id}/{question_id}",
             post(question::toggle_bookmark),
         )
+        .route("/notes/{question_id}", post(question::save_note))
+        .route("/hint/{id}/{idx}", post(question::reveal_hint))
+        .route(names::BOOKMARKS_URL, get(bookmarks::bookmarks_page))
+        .route(names::ACTIVITY_URL, get(activity::activity_page))
+        .route(names::ACTIVITY_TODAY_URL, get(activity::today_progress))
+        .route(names::DAILY_GOAL_URL, post(activity::save_daily_goal))
+        .route(
+            "/bookmarks/{id}/start",
+            post(bookmarks::start_bookmarks_session),
+        )
         .route("/session/{id}/delete", delete(session::delete_session))
+        .route("/session/{id}/discard", post(session::discard_session))
         .route("/session/{id}/rename", patch(session::rename_session))
         .route("/quiz/{id}/sessions", get(dashboard::quiz_session_history))
+        .route("/quiz/{id}/calibration", get(dashboard::quiz_calibration))
+        .route("/quiz/{id}/pace", get(dashboard::quiz_pace))
+        .route("/quiz/{id}/compare", get(dashboard::quiz_compare_sessions))
+        .route("/quiz/{id}/plan", post(plan::save_study_plan))
+        .route("/quiz/{id}/export/{file}", get(export::export_file))
+        .route("/quiz/{id}/distractors", get(dashboard::quiz_distractors))
+        .route("/quiz/{id}/items", get(dashboard::quiz_item_analysis))
         .route("/quiz/{id}/abandon", get(session::abandon_session))
+        .route(
+            "/quiz/{id}/collaborators",
+            get(collaborator::collaborators_page).post(collaborator::invite_collaborator),
+        )
+        .route(
+            "/quiz/{id}/collaborators/{user_id}",
+            delete(collaborator::remove_collaborator),
+        )
+        .route(
+            "/quiz/{id}/invitations/{invitation_id}",
+            delete(collaborator::cancel_invitation),
+        )
+        .route(names::INVITATIONS_URL, get(collaborator::invitations))
+        .route(
+            "/invitations/{id}/accept",
+            post(collaborator::accept_invitation),
+        )
+        .route(
+            "/invitations/{id}/decline",
+            post(collaborator::decline_invitation),
+        )
         .route("/toggle-share/{id}", post(sharing::toggle_share))
         .route("/shared/{id}", get(sharing::shared_quiz_page))
         .route("/add-to-library/{id}", post(sharing::add_to_library))
         .route("/marketplace", get(marketplace::marketplace_page))
         .route("/marketplace/search", get(marketplace::marketplace_search))
+        .route(
+            names::ASSIGNMENTS_URL,
+            get(assignment::assignments_page).post(assignment::create_assignment),
+        )
+        .route(
+            "/assignments/{id}",
+            get(assignment::assignment_page).delete(assignment::delete_assignment),
+        )
+        .route(
+            "/assignments/{id}/start",
+            post(assignment::start_assignment),
+        )
 }
 
 #[cfg(test)]
@@ -122,6 +305,44 @@ mod tests {
         assert_eq!(body.question_count, 10);
     }
 
+    #[test]
+    fn start_session_body_reads_all_questions_checkbox() {
+        let body: StartSessionBody =
+            serde_json::from_str(r#"{"name":"alice","question_count":"10","all_questions":"on"}"#)
+                .expect("should parse checkbox");
+
+        assert!(body.all_questions.is_some());
+    }
+
+    #[test]
+    fn session_size_range_uses_instance_limits_by_default() {
+        let range = session::session_size_range(
+            crate::SessionLimits::default(),
+            crate::db::QuizSessionSizes::default(),
+        );
+
+        assert_eq!(range.min, names::MIN_QUESTION_COUNT);
+        assert_eq!(range.max, names::MAX_QUESTION_COUNT);
+        assert_eq!(range.default, names::DEFAULT_QUESTION_COUNT);
+    }
+
+    #[test]
+    fn session_size_range_keeps_quiz_settings_within_instance_limits() {
+        let limits = crate::SessionLimits {
+            min_questions: 5,
+            max_questions: 200,
+        };
+        let mock_exam = crate::db::QuizSessionSizes {
+            default_size: Some(180),
+            min_size: Some(65),
+            max_size: Some(500),
+        };
+
+        let range = session::session_size_range(limits, mock_exam);
+
+        assert_eq!((range.min, range.max, range.default), (65, 200, 180));
+    }
+
     #[test]
     fn start_session_body_rejects_out_of_range_i64() {
         let result = serde_json::from_str::<StartSessionBody>(
@@ -139,4 +360,26 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn compared_session_ids_dedupes_and_caps() {
+        let params: Vec<(String, String)> = ["3", "1", "3", "x", "7", "9", "11"]
+            .iter()
+            .map(|id| ("sessions".to_string(), id.to_string()))
+            .chain([("other".to_string(), "5".to_string())])
+            .collect();
+
+        assert_eq!(compared_session_ids(&params), vec![3, 1, 7, 9]);
+    }
+
+    #[test]
+    fn is_iso_date_checks_the_calendar() {
+        assert!(is_iso_date("2030-02-28"));
+        assert!(is_iso_date("2028-02-29"));
+        assert!(!is_iso_date("2030-02-29"));
+        assert!(!is_iso_date("2100-02-29"));
+        assert!(!is_iso_date("2030-13-01"));
+        assert!(!is_iso_date("2030-4-01"));
+        assert!(!is_iso_date("tomorrow"));
+    }
 }
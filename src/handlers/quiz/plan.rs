@@ -0,0 +1,90 @@
+use axum::extract::{Form, Path, State};
+use maud::Markup;
+
+use super::{is_iso_date, StudyPlanBody};
+use crate::{
+    analytics,
+    db::StudyPlanSettings,
+    extractors::{AuthGuard, Locale},
+    names,
+    rejections::{AppError, ResultExt},
+    views, AppState,
+};
+
+/// Set or clear the exam date and pass threshold of a quiz, then show the dashboard
+/// with the new plan.
+pub(crate) async fn save_study_plan(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(public_id): Path<String>,
+    Locale(locale): Locale,
+    Form(body): Form<StudyPlanBody>,
+) -> Result<Markup, AppError> {
+    let quiz_id = state
+        .db
+        .resolve_quiz_id(&public_id)
+        .await
+        .reject("quiz not found")?;
+
+    let exam_date = body.exam_date.trim();
+    let settings = if exam_date.is_empty() {
+        None
+    } else {
+        if !is_iso_date(exam_date) {
+            return Err(AppError::Input("exam date must be a valid date"));
+        }
+        if state
+            .db
+            .is_past_date(exam_date)
+            .await
+            .reject("could not check exam date")?
+        {
+            return Err(AppError::Input("exam date is in the past"));
+        }
+        let threshold = body.pass_threshold.trim();
+        let pass_threshold = if threshold.is_empty() {
+            names::DEFAULT_PASS_THRESHOLD
+        } else {
+            threshold
+                .parse()
+                .map_err(|_| AppError::Input("pass threshold must be a number"))?
+        };
+        if !(1..=100).contains(&pass_threshold) {
+            return Err(AppError::Input("pass threshold is out of range"));
+        }
+        Some(StudyPlanSettings {
+            exam_date: exam_date.to_string(),
+            pass_threshold,
+        })
+    };
+
+    if !state
+        .db
+        .set_study_plan(quiz_id, user.id, settings.as_ref())
+        .await
+        .reject("could not save study plan")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    if settings.is_some() {
+        let (inputs, readiness) = tokio::try_join!(
+            state.db.get_study_plan_inputs(quiz_id, user.id),
+            state.db.get_readiness(quiz_id, user.id),
+        )
+        .reject("could not get study plan")?;
+        if let Some(inputs) = inputs {
+            let plan = analytics::study_plan(inputs, readiness.as_ref());
+            state
+                .db
+                .set_plan_daily_target(quiz_id, user.id, plan.daily_target)
+                .await
+                .reject("could not save study plan")?;
+        }
+    }
+
+    Ok(views::titled(
+        "Quiz Dashboard",
+        super::dashboard(&state.db, quiz_id, &public_id, user.id, &locale).await?,
+    ))
+}
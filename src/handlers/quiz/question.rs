@@ -1,12 +1,13 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Form, Path, Query, State},
     http::{header::SET_COOKIE, HeaderMap},
 };
 use axum_extra::extract::CookieJar;
 use maud::Markup;
 
-use super::{NavigateQuestionQuery, SubmitAnswerBody};
+use super::{NavigateQuestionQuery, SaveNoteBody, SelfGradeBody, SubmitAnswerBody};
 use crate::{
+    db::AnswerOutcome,
     extractors::{AuthGuard, IsHtmx, Locale},
     names,
     rejections::{AppError, ResultExt},
@@ -42,38 +43,54 @@ pub(crate) async fn quiz_page(
         .get(names::QUIZ_SESSION_COOKIE_NAME)
         .map(|c| c.value().to_string());
 
-    let content = match token {
-        Some(token) if !token.is_empty() => {
-            let res = state.db.get_session(&token).await;
-
-            match res {
-                Ok(session) if session.quiz_id == quiz_id => {
-                    let question_idx = state
-                        .db
-                        .current_question_index(session.id)
-                        .await
-                        .reject("could not get current question index")?;
-                    question(
-                        &state.db,
-                        session.id,
-                        session.quiz_id,
-                        question_idx,
-                        false,
-                        &locale,
-                    )
-                    .await?
-                }
-                Ok(_) => {
-                    // Session belongs to a different quiz; show start page for this quiz
-                    super::session::page(&state.db, quiz_id, &public_id, &locale).await?
-                }
-                Err(e) => {
-                    tracing::error!("could not get session for {token}: {e}");
-                    super::session::page(&state.db, quiz_id, &public_id, &locale).await?
-                }
+    // The cookie remembers the session this browser was working on; other in-progress
+    // sessions are listed on the start page.
+    let active_session = match token {
+        Some(token) if !token.is_empty() => match state.db.get_session(&token).await {
+            Ok(session) if session.quiz_id == quiz_id => Some(session.id),
+            // Session belongs to a different quiz; show start page for this quiz
+            Ok(_) => None,
+            Err(e) => {
+                tracing::error!("could not get session for {token}: {e}");
+                None
             }
+        },
+        _ => None,
+    };
+
+    let mut resume_at = None;
+    if let Some(session_id) = active_session {
+        let (is_owner, question_idx, questions_count) = tokio::try_join!(
+            state.db.verify_session_owner(session_id, user.id),
+            state.db.current_question_index(session_id),
+            state.db.questions_count_for_session(session_id),
+        )
+        .reject("could not get active session state")?;
+        if is_owner && question_idx < questions_count {
+            resume_at = Some((session_id, question_idx));
+        }
+    }
+
+    let content = match resume_at {
+        Some((session_id, question_idx)) => {
+            state
+                .db
+                .touch_session(session_id)
+                .await
+                .reject("could not update session activity")?;
+            question(&state.db, session_id, quiz_id, question_idx, false, &locale).await?
+        }
+        None => {
+            super::session::page(
+                &state.db,
+                state.session_limits,
+                user.id,
+                quiz_id,
+                &public_id,
+                &locale,
+            )
+            .await?
         }
-        _ => super::session::page(&state.db, quiz_id, &public_id, &locale).await?,
     };
 
     let nav_user = views::NavUser {
@@ -104,9 +121,11 @@ pub(crate) async fn submit_answer_raw(
     let body_str =
         String::from_utf8(body_bytes.to_vec()).reject_input("failed to parse body as UTF-8")?;
 
+    let mut question_id: Option<i32> = None;
+    let mut idempotency_key = String::new();
     let mut option: Option<String> = None;
     let mut options: Vec<String> = Vec::new();
-    let mut duration_ms: i32 = 0;
+    let mut confidence: Option<String> = None;
 
     for pair in body_str.split('&') {
         if let Some((key, value)) = pair.split_once('=') {
@@ -118,22 +137,35 @@ pub(crate) async fn submit_answer_raw(
                 .to_string();
 
             match key {
+                "question_id" => {
+                    question_id = Some(
+                        decoded_value
+                            .parse()
+                            .reject_input("failed to parse question id")?,
+                    )
+                }
+                "idempotency_key" => idempotency_key = decoded_value,
                 "option" => option = Some(decoded_value),
                 "options" => options.push(decoded_value),
-                "duration_ms" => duration_ms = decoded_value.parse().unwrap_or(0),
+                "confidence" => confidence = Some(decoded_value),
                 _ => {}
             }
         }
     }
 
-    duration_ms = duration_ms.clamp(0, 300_000);
-
-    tracing::info!("Received body: option={:?}, options={:?}", option, options);
+    tracing::info!(
+        "Received body: question_id={:?}, option={:?}, options={:?}",
+        question_id,
+        option,
+        options
+    );
 
     let body = SubmitAnswerBody {
+        question_id: question_id.ok_or(AppError::Input("question id not provided"))?,
+        idempotency_key,
         option,
         options,
-        duration_ms,
+        confidence,
     };
     submit_answer(state, token, body, user.id, &locale).await
 }
@@ -174,60 +206,69 @@ async fn submit_answer(
         return Err(AppError::Input("no options provided"));
     };
 
-    // Parallel: current_question_index + questions_count (both only need session.id)
-    let (question_idx, questions_count) = tokio::try_join!(
-        state.db.current_question_index(session.id),
-        state.db.questions_count_for_session(session.id),
-    )
-    .reject("could not get question state")?;
-
-    let question_id = state
+    state
         .db
-        .get_question_by_idx(session.id, question_idx)
+        .get_question_idx(session.id, body.question_id)
         .await
-        .reject("could not get question id")?;
+        .reject_input("question is not part of this session")?;
 
-    // Parallel: get_question + get_correct_option_ids (both only need question_id)
-    let (question_data, correct_ids) = tokio::try_join!(
-        state.db.get_question(question_id),
-        state.db.get_correct_option_ids(question_id),
-    )
-    .reject("could not get question data")?;
-
-    let is_correct = if question_data.is_multiple_choice {
-        let mut selected_sorted = selected_ids.clone();
-        selected_sorted.sort();
-        let mut correct_sorted = correct_ids.clone();
-        correct_sorted.sort();
-
-        tracing::info!(
-            "Multiple choice validation: selected={:?}, correct={:?}, match={}",
-            selected_sorted,
-            correct_sorted,
-            selected_sorted == correct_sorted
-        );
-
-        selected_sorted == correct_sorted
+    // Confidence is optional; unknown values are treated as "not rated".
+    let confidence = body
+        .confidence
+        .as_deref()
+        .filter(|c| names::CONFIDENCE_LEVELS.contains(c));
+
+    // Submissions without a key (stale pages) can never be replayed, only recorded once.
+    let idempotency_key = if body.idempotency_key.is_empty() {
+        ulid::Ulid::new().to_string()
     } else {
-        correct_ids.contains(&selected_ids[0])
+        body.idempotency_key
     };
 
-    // Parallel: create_answers_batch + update_question_result (independent writes)
-    tokio::try_join!(
-        state.db.create_answers_batch(
+    let outcome = state
+        .db
+        .record_answer(
             session.id,
-            question_id,
+            body.question_id,
             &selected_ids,
-            is_correct,
-            body.duration_ms
-        ),
+            &idempotency_key,
+            confidence,
+        )
+        .await
+        .reject("could not save answer")?;
+    let is_replay = match outcome {
+        AnswerOutcome::Recorded { .. } => false,
+        AnswerOutcome::Replayed | AnswerOutcome::AlreadyAnswered => true,
+        AnswerOutcome::InvalidSelection => return Err(AppError::Input("invalid option selection")),
+    };
+
+    // Cram sessions can hold several attempts at a question; show the one this
+    // submission answered rather than a re-queued copy.
+    let slot = state
+        .db
+        .get_answered_slot(session.id, body.question_id, &idempotency_key)
+        .await
+        .reject("could not get answered question")?;
+    let question_idx = slot.question_number;
+
+    // A replayed or concurrent submission shows what was actually stored.
+    let selected_ids = if is_replay {
         state
             .db
-            .update_question_result(session.id, question_id, is_correct),
+            .get_selected_answers(session.id, body.question_id, slot.attempt)
+            .await
+            .reject("could not get selected answers")?
+    } else {
+        selected_ids
+    };
+
+    let (answered_count, questions_count) = tokio::try_join!(
+        state.db.current_question_index(session.id),
+        state.db.questions_count_for_session(session.id),
     )
-    .reject("could not save answer")?;
+    .reject("could not get question state")?;
 
-    let is_final = question_idx + 1 == questions_count;
+    let is_final = answered_count >= questions_count;
 
     let page = answer(
         &state.db,
@@ -253,6 +294,92 @@ async fn submit_answer(
     }
 }
 
+pub(crate) async fn submit_self_grade(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Locale(locale): Locale,
+    Form(body): Form<SelfGradeBody>,
+) -> Result<axum::response::Response, AppError> {
+    let token = jar
+        .get(names::QUIZ_SESSION_COOKIE_NAME)
+        .map(|c| c.value().to_string())
+        .ok_or(AppError::Input("session cookie not found"))?;
+
+    let session = state
+        .db
+        .get_session(&token)
+        .await
+        .reject("could not get session")?;
+
+    if !state
+        .db
+        .verify_session_owner(session.id, user.id)
+        .await
+        .reject("could not verify session owner")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    if !names::SELF_GRADES.contains(&body.grade.as_str()) {
+        return Err(AppError::Input("unknown grade"));
+    }
+
+    let question_idx = state
+        .db
+        .get_question_idx(session.id, body.question_id)
+        .await
+        .reject_input("question is not part of this session")?;
+
+    let idempotency_key = if body.idempotency_key.is_empty() {
+        ulid::Ulid::new().to_string()
+    } else {
+        body.idempotency_key
+    };
+
+    state
+        .db
+        .record_self_grade(session.id, body.question_id, &body.grade, &idempotency_key)
+        .await
+        .reject("could not save grade")?;
+
+    let (answered_count, questions_count) = tokio::try_join!(
+        state.db.current_question_index(session.id),
+        state.db.questions_count_for_session(session.id),
+    )
+    .reject("could not get question state")?;
+
+    let is_final = answered_count >= questions_count;
+
+    // Flashcards move straight on to the next card; the last one stays revealed
+    // with a link to the results.
+    let next_idx = if is_final {
+        question_idx
+    } else {
+        answered_count
+    };
+    let page = question(
+        &state.db,
+        session.id,
+        session.quiz_id,
+        next_idx,
+        false,
+        &locale,
+    )
+    .await?;
+
+    use axum::response::IntoResponse;
+    if is_final {
+        let cookie = utils::clear_cookie(names::QUIZ_SESSION_COOKIE_NAME, state.secure_cookies)
+            .reject("could not build clear-session cookie")?;
+        let mut headers = HeaderMap::new();
+        headers.insert(SET_COOKIE, cookie);
+        Ok((headers, page).into_response())
+    } else {
+        Ok(page.into_response())
+    }
+}
+
 pub(crate) async fn navigate_question(
     AuthGuard(user): AuthGuard,
     State(state): State<AppState>,
@@ -282,22 +409,16 @@ pub(crate) async fn navigate_question(
         .await
         .reject("could not get quiz name")?;
 
-    let question_id = state
+    let slot = state
         .db
-        .get_question_by_idx(session_id, query.question_idx)
+        .get_session_slot(session_id, query.question_idx)
         .await
         .reject("could not get question id")?;
 
-    let is_answered = state
-        .db
-        .is_question_answered(session_id, question_id)
-        .await
-        .reject("could not check if question is answered")?;
-
-    let page = if is_answered {
+    let page = if slot.is_answered {
         let selected_answers = state
             .db
-            .get_selected_answers(session_id, question_id)
+            .get_selected_answers(session_id, slot.question_id, slot.attempt)
             .await
             .reject("could not get selected answers")?;
 
@@ -342,9 +463,15 @@ pub(crate) async fn toggle_bookmark(
         return Err(AppError::Forbidden);
     }
 
+    state
+        .db
+        .get_question_idx(session_id, question_id)
+        .await
+        .reject_input("question is not part of this session")?;
+
     let new_state = state
         .db
-        .toggle_bookmark(session_id, question_id)
+        .toggle_bookmark(user.id, question_id)
         .await
         .reject("could not toggle bookmark")?;
 
@@ -356,6 +483,84 @@ pub(crate) async fn toggle_bookmark(
     ))
 }
 
+pub(crate) async fn reveal_hint(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path((session_id, question_idx)): Path<(i32, i32)>,
+    Locale(locale): Locale,
+) -> Result<Markup, AppError> {
+    if !state
+        .db
+        .verify_session_owner(session_id, user.id)
+        .await
+        .reject("could not verify session owner")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let (hints, hints_used) = state
+        .db
+        .reveal_hint(session_id, question_idx)
+        .await
+        .reject_input("question is not part of this session")?;
+
+    Ok(quiz_views::hint_panel(
+        session_id,
+        question_idx,
+        &hints,
+        hints_used,
+        &locale,
+    ))
+}
+
+pub(crate) async fn save_note(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(question_id): Path<i32>,
+    Locale(locale): Locale,
+    Form(body): Form<SaveNoteBody>,
+) -> Result<Markup, AppError> {
+    let quiz_id = state
+        .db
+        .question_quiz_id(question_id)
+        .await
+        .reject_input("question not found")?;
+
+    if !state
+        .db
+        .user_has_quiz(user.id, quiz_id)
+        .await
+        .reject("could not check access")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    if body.note.chars().count() > names::MAX_NOTE_LENGTH {
+        return Err(AppError::Input("note is too long"));
+    }
+
+    state
+        .db
+        .save_note(user.id, question_id, &body.note)
+        .await
+        .reject("could not save note")?;
+
+    let note = state
+        .db
+        .get_note(user.id, question_id)
+        .await
+        .reject("could not get note")?;
+
+    Ok(quiz_views::note_panel(
+        question_id,
+        note.as_deref(),
+        &locale,
+    ))
+}
+
+/// Responses a question needs before other learners' picks are shown after answering.
+const DISTRIBUTION_MIN_RESPONSES: i32 = 5;
+
 // --- Helper functions: DB queries + view delegation ---
 // Phase 1 optimization: merged queries (7 → 2 for question, 5 → 2 for answer)
 
@@ -372,8 +577,18 @@ pub async fn question(
         .await
         .reject("could not get question context")?;
 
+    if !ctx.is_answered {
+        db.mark_question_served(session_id, question_idx)
+            .await
+            .reject("could not start question timer")?;
+    }
+
+    if ctx.study_mode == "flashcard" {
+        return flashcard(db, session_id, question_idx, ctx, None, None, locale).await;
+    }
+
     let options_with_sel = db
-        .get_options_with_selection(session_id, ctx.question_id)
+        .get_options_with_selection(session_id, ctx.question_id, ctx.attempt)
         .await
         .reject("could not get options")?;
 
@@ -400,6 +615,9 @@ pub async fn question(
                 question: ctx.question,
                 is_multiple_choice: ctx.is_multiple_choice,
                 options,
+                explanation: ctx.explanation,
+                references: ctx.references,
+                hints: ctx.hints,
             },
             question_idx,
             questions_count: ctx.questions_count,
@@ -408,8 +626,12 @@ pub async fn question(
             is_resuming,
             session_id,
             question_id: ctx.question_id,
+            attempt: ctx.attempt,
             is_bookmarked: ctx.is_bookmarked,
             quiz_id: ctx.quiz_public_id,
+            idempotency_key: ulid::Ulid::new().to_string(),
+            note: ctx.note,
+            hints_used: ctx.hints_used,
         },
         locale,
     ))
@@ -431,10 +653,36 @@ pub async fn answer(
         .await
         .reject("could not get question context")?;
 
-    let options = db
-        .get_options(ctx.question_id)
-        .await
-        .reject("could not get options")?;
+    if ctx.study_mode == "flashcard" {
+        return flashcard(
+            db,
+            session_id,
+            question_idx,
+            ctx,
+            from_context,
+            current_idx,
+            locale,
+        )
+        .await;
+    }
+
+    let (options, is_shared) = tokio::try_join!(
+        db.get_session_options(session_id, ctx.question_id),
+        db.is_quiz_shared_by_id(quiz_id),
+    )
+    .reject("could not get options")?;
+
+    // Only shared quizzes have a crowd to compare with, and a handful of answers says little.
+    let distribution = if is_shared {
+        Some(
+            db.get_answer_distribution(ctx.question_id)
+                .await
+                .reject("could not get answer distribution")?,
+        )
+        .filter(|d| d.responses >= DISTRIBUTION_MIN_RESPONSES)
+    } else {
+        None
+    };
 
     Ok(quiz_views::answer(
         quiz_views::AnswerData {
@@ -443,6 +691,9 @@ pub async fn answer(
                 question: ctx.question,
                 is_multiple_choice: ctx.is_multiple_choice,
                 options,
+                explanation: ctx.explanation,
+                references: ctx.references,
+                hints: ctx.hints,
             },
             question_idx,
             questions_count: ctx.questions_count,
@@ -452,7 +703,53 @@ pub async fn answer(
             from_context,
             current_idx,
             question_id: ctx.question_id,
+            attempt: ctx.attempt,
+            is_bookmarked: ctx.is_bookmarked,
+            confidence: ctx.confidence,
+            note: ctx.note,
+            hints_used: ctx.hints_used,
+            distribution,
+        },
+        locale,
+    ))
+}
+
+async fn flashcard(
+    db: &crate::db::Db,
+    session_id: i32,
+    question_idx: i32,
+    ctx: crate::db::QuestionContext,
+    from_context: Option<String>,
+    current_idx: Option<i32>,
+    locale: &str,
+) -> Result<Markup, AppError> {
+    let options = db
+        .get_session_options(session_id, ctx.question_id)
+        .await
+        .reject("could not get options")?;
+
+    Ok(quiz_views::flashcard(
+        quiz_views::FlashcardData {
+            quiz_name: ctx.quiz_name,
+            question: crate::db::QuestionModel {
+                question: ctx.question,
+                is_multiple_choice: ctx.is_multiple_choice,
+                options,
+                explanation: ctx.explanation,
+                references: ctx.references,
+                hints: ctx.hints,
+            },
+            question_idx,
+            questions_count: ctx.questions_count,
+            session_id,
+            quiz_id: ctx.quiz_public_id,
+            question_id: ctx.question_id,
             is_bookmarked: ctx.is_bookmarked,
+            self_grade: ctx.self_grade,
+            idempotency_key: ulid::Ulid::new().to_string(),
+            from_context,
+            current_idx,
+            note: ctx.note,
         },
         locale,
     ))
@@ -4,17 +4,19 @@ use axum::{
     response::IntoResponse,
     Json,
 };
+use axum_extra::extract::CookieJar;
 use maud::{html, Markup};
 use rust_i18n::t;
 
 use super::StartSessionBody;
 use crate::{
+    db::QuizSessionSizes,
     extractors::{AuthGuard, Locale},
     names,
     rejections::{AppError, ResultExt},
     utils, views,
     views::quiz as quiz_views,
-    AppState,
+    AppState, SessionLimits,
 };
 
 pub(crate) async fn start_session(
@@ -39,9 +41,22 @@ pub(crate) async fn start_session(
         return Err(AppError::Forbidden);
     }
 
-    let question_count = body
-        .question_count
-        .clamp(names::MIN_QUESTION_COUNT, names::MAX_QUESTION_COUNT);
+    // "All questions" sessions rehearse a full mock exam and skip the size limits.
+    let question_count = if body.all_questions.is_some() {
+        state
+            .db
+            .questions_count(quiz_id)
+            .await
+            .reject("could not get question count")?
+    } else {
+        let sizes = state
+            .db
+            .get_quiz_session_sizes(quiz_id)
+            .await
+            .reject("could not get session sizes")?;
+        let range = session_size_range(state.session_limits, sizes);
+        body.question_count.clamp(range.min, range.max)
+    };
 
     let selection_mode = if names::SELECTION_MODES.contains(&body.selection_mode.as_str()) {
         body.selection_mode.as_str()
@@ -49,6 +64,12 @@ pub(crate) async fn start_session(
         names::DEFAULT_SELECTION_MODE
     };
 
+    let study_mode = if names::STUDY_MODES.contains(&body.study_mode.as_str()) {
+        body.study_mode.as_str()
+    } else {
+        names::DEFAULT_STUDY_MODE
+    };
+
     // Fetch quiz name before session creation (fail early if quiz doesn't exist)
     let quiz_name = state
         .db
@@ -58,7 +79,14 @@ pub(crate) async fn start_session(
 
     let (session_token, session_id) = match state
         .db
-        .create_session(&body.name, quiz_id, question_count, selection_mode, user.id)
+        .create_session(
+            &body.name,
+            quiz_id,
+            question_count,
+            selection_mode,
+            study_mode,
+            user.id,
+        )
         .await
     {
         Ok(pair) => {
@@ -120,6 +148,9 @@ pub(crate) async fn resume_session(
         .get_session(&token)
         .await
         .reject("could not get session")?;
+    if session.id != session_id {
+        return Err(AppError::Forbidden);
+    }
 
     let quiz_name = state
         .db
@@ -133,6 +164,12 @@ pub(crate) async fn resume_session(
         .await
         .reject("could not get current question index")?;
 
+    state
+        .db
+        .touch_session(session.id)
+        .await
+        .reject("could not update session activity")?;
+
     let is_resuming = question_idx > 0;
     let page = views::titled(
         &quiz_name,
@@ -210,6 +247,7 @@ pub(crate) async fn retry_incorrect(
             session.quiz_id,
             &incorrect_ids,
             "incorrect",
+            &session.study_mode,
             user.id,
         )
         .await
@@ -303,6 +341,7 @@ pub(crate) async fn retry_bookmarked(
             session.quiz_id,
             &bookmarked_ids,
             "bookmarked",
+            &session.study_mode,
             user.id,
         )
         .await
@@ -344,6 +383,90 @@ pub(crate) async fn retry_bookmarked(
     Ok((headers, page).into_response())
 }
 
+pub(crate) async fn retake_session(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(session_id): Path<i32>,
+    Locale(locale): Locale,
+) -> Result<axum::response::Response, AppError> {
+    if !state
+        .db
+        .verify_session_owner(session_id, user.id)
+        .await
+        .reject("could not verify session owner")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let (session, question_ids, shuffle_seed, is_complete) = tokio::try_join!(
+        state.db.get_session_by_id(session_id),
+        state.db.get_session_question_ids(session_id),
+        state.db.get_session_shuffle_seed(session_id),
+        state.db.is_session_complete(session_id),
+    )
+    .reject("could not get session")?;
+
+    if question_ids.is_empty() {
+        return Err(AppError::Input("session has no questions"));
+    }
+    if !is_complete {
+        return Err(AppError::Input("only completed sessions can be retaken"));
+    }
+
+    let suffix = &ulid::Ulid::new().to_string()[..6];
+    let retake_name = format!("{}-retake-{}", session.name, suffix.to_lowercase());
+
+    let token = state
+        .db
+        .create_seeded_session(
+            &retake_name,
+            session.quiz_id,
+            &question_ids,
+            shuffle_seed,
+            "retake",
+            &session.study_mode,
+            user.id,
+            None,
+        )
+        .await
+        .reject("could not create retake session")?;
+
+    let new_session = state
+        .db
+        .get_session(&token)
+        .await
+        .reject("could not get new session")?;
+
+    let quiz_name = state
+        .db
+        .quiz_name(session.quiz_id)
+        .await
+        .reject("could not get quiz name")?;
+
+    let page = views::titled(
+        &quiz_name,
+        super::question::question(
+            &state.db,
+            new_session.id,
+            session.quiz_id,
+            0,
+            false,
+            &locale,
+        )
+        .await?,
+    );
+    let cookie = utils::cookie(
+        names::QUIZ_SESSION_COOKIE_NAME,
+        &token,
+        state.secure_cookies,
+    )
+    .reject("could not build session cookie")?;
+    let mut headers = HeaderMap::new();
+    headers.insert(SET_COOKIE, cookie);
+
+    Ok((headers, page).into_response())
+}
+
 pub(crate) async fn delete_session(
     AuthGuard(user): AuthGuard,
     State(state): State<AppState>,
@@ -380,7 +503,7 @@ pub(crate) async fn delete_session(
 
     Ok(views::titled(
         "Quiz Dashboard",
-        super::dashboard::dashboard(&state.db, quiz_id, &quiz_public_id, &locale).await?,
+        super::dashboard::dashboard(&state.db, quiz_id, &quiz_public_id, user.id, &locale).await?,
     ))
 }
 
@@ -423,10 +546,78 @@ pub(crate) async fn rename_session(
 
     Ok(views::titled(
         "Quiz Dashboard",
-        super::dashboard::dashboard(&state.db, quiz_id, &quiz_public_id, &locale).await?,
+        super::dashboard::dashboard(&state.db, quiz_id, &quiz_public_id, user.id, &locale).await?,
     ))
 }
 
+/// Delete an unfinished session from the start page and show the page again.
+pub(crate) async fn discard_session(
+    AuthGuard(user): AuthGuard,
+    State(state): State<AppState>,
+    Path(session_id): Path<i32>,
+    jar: CookieJar,
+    Locale(locale): Locale,
+) -> Result<impl IntoResponse, AppError> {
+    if !state
+        .db
+        .verify_session_owner(session_id, user.id)
+        .await
+        .reject("could not verify session owner")?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let session = state
+        .db
+        .get_session_by_id(session_id)
+        .await
+        .reject("could not get session")?;
+
+    let quiz_public_id = state
+        .db
+        .quiz_public_id(session.quiz_id)
+        .await
+        .reject("could not get quiz public id")?;
+
+    let is_active_here = match jar.get(names::QUIZ_SESSION_COOKIE_NAME) {
+        Some(cookie) => state
+            .db
+            .get_session(cookie.value())
+            .await
+            .is_ok_and(|active| active.id == session_id),
+        None => false,
+    };
+
+    state
+        .db
+        .delete_session(session_id)
+        .await
+        .reject("could not delete session")?;
+
+    let page = views::titled(
+        "Quiz",
+        page(
+            &state.db,
+            state.session_limits,
+            user.id,
+            session.quiz_id,
+            &quiz_public_id,
+            &locale,
+        )
+        .await?,
+    );
+
+    // Forget the session in this browser too if it was the active one.
+    let mut headers = HeaderMap::new();
+    if is_active_here {
+        let cookie = utils::clear_cookie(names::QUIZ_SESSION_COOKIE_NAME, state.secure_cookies)
+            .reject("could not build clear-session cookie")?;
+        headers.insert(SET_COOKIE, cookie);
+    }
+
+    Ok((headers, page))
+}
+
 pub(crate) async fn abandon_session(
     AuthGuard(user): AuthGuard,
     State(state): State<AppState>,
@@ -450,7 +641,7 @@ pub(crate) async fn abandon_session(
 
     let page = views::titled(
         "Quiz Dashboard",
-        super::dashboard::dashboard(&state.db, quiz_id, &public_id, &locale).await?,
+        super::dashboard::dashboard(&state.db, quiz_id, &public_id, user.id, &locale).await?,
     );
     let cookie = utils::clear_cookie(names::QUIZ_SESSION_COOKIE_NAME, state.secure_cookies)
         .reject("could not build clear-session cookie")?;
@@ -468,26 +659,48 @@ pub(crate) async fn abandon_session(
 
 pub(crate) async fn page(
     db: &crate::db::Db,
+    limits: SessionLimits,
+    user_id: i32,
     quiz_id: i32,
     quiz_public_id: &str,
     locale: &str,
 ) -> Result<Markup, AppError> {
-    let quiz_name = db
-        .quiz_name(quiz_id)
-        .await
-        .reject("could not get quiz name")?;
-
-    let total_questions = db
-        .questions_count(quiz_id)
-        .await
-        .reject("could not get question count")?;
+    let (quiz_name, total_questions, sizes, in_progress) = tokio::try_join!(
+        db.quiz_name(quiz_id),
+        db.questions_count(quiz_id),
+        db.get_quiz_session_sizes(quiz_id),
+        db.get_in_progress_sessions(quiz_id, user_id),
+    )
+    .reject("could not get quiz start data")?;
 
     Ok(quiz_views::start_page(
         quiz_views::StartPageData {
             quiz_name,
             total_questions,
             quiz_id: quiz_public_id.to_string(),
+            sizes: session_size_range(limits, sizes),
+            in_progress,
         },
         locale,
     ))
 }
+
+/// The session sizes offered for a quiz: the owner's settings, kept within the instance limits.
+pub(super) fn session_size_range(
+    limits: SessionLimits,
+    sizes: QuizSessionSizes,
+) -> quiz_views::SessionSizeRange {
+    let min = sizes
+        .min_size
+        .unwrap_or(limits.min_questions)
+        .clamp(limits.min_questions, limits.max_questions);
+    let max = sizes
+        .max_size
+        .unwrap_or(limits.max_questions)
+        .clamp(min, limits.max_questions);
+    let default = sizes
+        .default_size
+        .unwrap_or(names::DEFAULT_QUESTION_COUNT)
+        .clamp(min, max);
+    quiz_views::SessionSizeRange { min, max, default }
+}
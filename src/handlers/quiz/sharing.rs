@@ -21,21 +21,12 @@ pub(crate) async fn toggle_share(
     Path(public_id): Path<String>,
     Locale(locale): Locale,
 ) -> Result<Markup, AppError> {
-    let is_owner = state
-        .db
-        .verify_quiz_owner(&public_id, user.id)
-        .await
-        .reject("could not verify quiz owner")?;
-
-    if !is_owner {
-        return Err(AppError::Unauthorized);
-    }
-
     let is_shared = state
         .db
         .toggle_share(&public_id, user.id)
         .await
-        .reject("could not toggle share")?;
+        .reject("could not toggle share")?
+        .ok_or(AppError::Unauthorized)?;
 
     Ok(quiz_views::share_toggle_icon(
         &public_id, is_shared, &locale,
@@ -124,7 +115,8 @@ pub(crate) async fn add_to_library(
                 headers,
                 views::titled(
                     "Quiz Dashboard",
-                    super::dashboard::dashboard(&state.db, info.id, &public_id, &locale).await?,
+                    super::dashboard::dashboard(&state.db, info.id, &public_id, user.id, &locale)
+                        .await?,
                 ),
             ))
         }
@@ -2,8 +2,10 @@
 
 rust_i18n::i18n!("locales", fallback = "en");
 
+pub mod analytics;
 pub mod db;
 pub mod email;
+pub mod export;
 pub mod extractors;
 pub mod handlers;
 pub mod models;
@@ -11,6 +13,7 @@ pub mod names;
 pub mod rejections;
 pub mod services;
 pub mod statics;
+pub mod template;
 pub mod utils;
 pub mod views;
 
@@ -25,6 +28,24 @@ pub struct AppState {
     pub db: db::Db,
     pub auth: services::auth::AuthService,
     pub secure_cookies: bool,
+    pub session_limits: SessionLimits,
+}
+
+/// Instance-wide bounds on how many questions a session may have.
+/// Quiz owners can narrow them per quiz; "all questions" sessions are not bounded.
+#[derive(Clone, Copy, Debug)]
+pub struct SessionLimits {
+    pub min_questions: i32,
+    pub max_questions: i32,
+}
+
+impl Default for SessionLimits {
+    fn default() -> Self {
+        Self {
+            min_questions: names::MIN_QUESTION_COUNT,
+            max_questions: names::MAX_QUESTION_COUNT,
+        }
+    }
 }
 
 pub fn router(state: AppState, disable_rate_limit: bool) -> Router {
@@ -48,6 +69,7 @@ pub fn router(state: AppState, disable_rate_limit: bool) -> Router {
         .merge(handlers::quiz::routes())
         .merge(handlers::account::routes())
         .merge(handlers::admin::routes())
+        .merge(handlers::group::routes())
         .layer(middleware::from_fn_with_state(
             state.clone(),
             refresh_session_cookie,
@@ -1,5 +1,11 @@
 use clap::Parser;
-use quizinart::{db::Db, email::ResendEmailSender, services::auth::AuthService, AppState};
+use quizinart::{
+    db::Db,
+    email::ResendEmailSender,
+    names,
+    services::{auth::AuthService, digest::DigestService},
+    AppState, SessionLimits,
+};
 
 #[derive(Parser, Debug)]
 #[command(version, about)]
@@ -27,6 +33,14 @@ struct Args {
     /// Disable rate limiting on auth endpoints (for E2E testing).
     #[arg(long, env, default_value = "false")]
     disable_rate_limit: bool,
+
+    /// Smallest number of questions a session may have.
+    #[arg(long, env, default_value_t = names::MIN_QUESTION_COUNT)]
+    min_session_questions: i32,
+
+    /// Largest number of questions a session may have (raise it for long mock exams).
+    #[arg(long, env, default_value_t = names::MAX_QUESTION_COUNT)]
+    max_session_questions: i32,
 }
 
 fn main() -> color_eyre::Result<()> {
@@ -53,17 +67,32 @@ fn main() -> color_eyre::Result<()> {
 async fn run() -> color_eyre::Result<()> {
     let args = Args::parse();
 
+    if args.min_session_questions < 1 || args.min_session_questions > args.max_session_questions {
+        color_eyre::eyre::bail!(
+            "invalid session size limits: {}..={}",
+            args.min_session_questions,
+            args.max_session_questions
+        );
+    }
+    let session_limits = SessionLimits {
+        min_questions: args.min_session_questions,
+        max_questions: args.max_session_questions,
+    };
+
     let db = Db::new(args.database_url).await?;
     let secure_cookies = args.secure_cookies || args.base_url.starts_with("https://");
     if secure_cookies && !args.secure_cookies {
         tracing::info!("secure_cookies auto-enabled because base_url uses HTTPS");
     }
     let email_sender = ResendEmailSender::new(args.resend_api_key);
+    let digest = DigestService::new(db.clone(), email_sender.clone(), args.base_url.clone());
+    tokio::spawn(digest.run());
     let auth = AuthService::new(db.clone(), email_sender, args.base_url);
     let state = AppState {
         db,
         auth,
         secure_cookies,
+        session_limits,
     };
     let app = quizinart::router(state, args.disable_rate_limit);
 
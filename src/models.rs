@@ -1,4 +1,6 @@
-use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
 
 pub type Questions = Vec<Question>;
 
@@ -10,6 +12,18 @@ pub struct Question {
     #[serde(default)]
     pub is_multiple_choice: bool,
     pub options: Vec<QuestionOption>,
+    /// Makes the question a template: its texts may use `{expression}` placeholders,
+    /// filled from values drawn per session (see `crate::template`).
+    #[serde(default)]
+    pub variables: BTreeMap<String, TemplateVariable>,
+    /// Shown with the answer, after the per-option explanations.
+    pub explanation: Option<String>,
+    /// Links or citations for further reading.
+    #[serde(default)]
+    pub references: Vec<String>,
+    /// Revealed one at a time before answering.
+    #[serde(default)]
+    pub hints: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -18,4 +32,19 @@ pub struct QuestionOption {
     pub text: String,
     pub is_answer: bool,
     pub explanation: Option<String>,
+    #[serde(default)]
+    pub is_pinned: bool,
+}
+
+/// Integer range a template variable is drawn from.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct TemplateVariable {
+    pub min: i64,
+    pub max: i64,
+    #[serde(default = "default_step")]
+    pub step: i64,
+}
+
+fn default_step() -> i64 {
+    1
 }
@@ -2,6 +2,7 @@ pub const LOGIN_URL: &str = "/login";
 pub const GET_STARTED_URL: &str = "/start";
 pub const CREATE_QUIZ_URL: &str = "/create-quiz";
 pub const SUBMIT_ANSWER_URL: &str = "/submit-answer";
+pub const SUBMIT_SELF_GRADE_URL: &str = "/submit-self-grade";
 
 pub const ADMIN_SESSION_COOKIE_NAME: &str = "admin_session"; // legacy, kept for migration
 pub const USER_SESSION_COOKIE_NAME: &str = "user_session";
@@ -13,6 +14,7 @@ pub const FORGOT_PASSWORD_URL: &str = "/forgot-password";
 pub const RESET_PASSWORD_URL: &str = "/reset-password";
 pub const ACCOUNT_URL: &str = "/account";
 pub const CHANGE_PASSWORD_URL: &str = "/change-password";
+pub const WEEKLY_DIGEST_URL: &str = "/account/weekly-digest";
 
 pub fn quiz_dashboard_url(public_id: &str) -> String {
     format!("/quiz/{public_id}/dashboard")
@@ -46,10 +48,58 @@ pub fn results_url(session_id: i32) -> String {
     format!("/results/{session_id}")
 }
 
+pub fn session_review_url(session_id: i32, filter: &str) -> String {
+    format!("/results/{session_id}/review?filter={filter}")
+}
+
+pub fn quiz_calibration_url(public_id: &str) -> String {
+    format!("/quiz/{public_id}/calibration")
+}
+
+pub fn quiz_distractors_url(public_id: &str) -> String {
+    format!("/quiz/{public_id}/distractors")
+}
+
+pub fn quiz_pace_url(public_id: &str) -> String {
+    format!("/quiz/{public_id}/pace")
+}
+
+pub fn quiz_item_analysis_url(public_id: &str) -> String {
+    format!("/quiz/{public_id}/items")
+}
+
+pub fn quiz_compare_url(public_id: &str) -> String {
+    format!("/quiz/{public_id}/compare")
+}
+
+pub fn quiz_study_plan_url(public_id: &str) -> String {
+    format!("/quiz/{public_id}/plan")
+}
+
+pub fn quiz_export_url(public_id: &str, file: &str) -> String {
+    format!("/quiz/{public_id}/export/{file}")
+}
+
+pub fn start_bookmarks_session_url(public_id: &str) -> String {
+    format!("/bookmarks/{public_id}/start")
+}
+
+pub fn question_note_url(question_id: i32) -> String {
+    format!("/notes/{question_id}")
+}
+
+pub fn reveal_hint_url(session_id: i32, question_idx: i32) -> String {
+    format!("/hint/{session_id}/{question_idx}")
+}
+
 pub fn resume_session_url(session_id: i32, token: &str) -> String {
     format!("/resume-session/{session_id}/{token}")
 }
 
+pub fn discard_session_url(session_id: i32) -> String {
+    format!("/session/{session_id}/discard")
+}
+
 pub fn delete_session_url(session_id: i32) -> String {
     format!("/session/{session_id}/delete")
 }
@@ -58,6 +108,26 @@ pub fn rename_session_url(session_id: i32) -> String {
     format!("/session/{session_id}/rename")
 }
 
+pub fn retake_session_url(session_id: i32) -> String {
+    format!("/retake/{session_id}")
+}
+
+pub fn create_challenge_url(session_id: i32) -> String {
+    format!("/session/{session_id}/challenge")
+}
+
+pub fn challenge_url(public_id: &str) -> String {
+    format!("/challenge/{public_id}")
+}
+
+pub fn accept_challenge_url(public_id: &str) -> String {
+    format!("/challenge/{public_id}/accept")
+}
+
+pub fn quiz_settings_url(public_id: &str) -> String {
+    format!("/quiz/{public_id}/settings")
+}
+
 pub fn toggle_share_url(public_id: &str) -> String {
     format!("/toggle-share/{public_id}")
 }
@@ -76,6 +146,101 @@ pub const MAX_QUESTION_COUNT: i32 = 30;
 pub const DEFAULT_QUESTION_COUNT: i32 = 10;
 pub const DEFAULT_SELECTION_MODE: &str = "unanswered";
 pub const SELECTION_MODES: &[&str] = &["unanswered", "incorrect", "random"];
+pub const CONFIDENCE_LEVELS: &[&str] = &["sure", "unsure", "guess"];
+pub const DEFAULT_STUDY_MODE: &str = "quiz";
+pub const STUDY_MODES: &[&str] = &["quiz", "flashcard", "cram"];
+pub const SELF_GRADES: &[&str] = &["again", "hard", "good", "easy"];
+pub const REVIEW_FILTERS: &[&str] = &["all", "incorrect", "bookmarked"];
+pub const MAX_COMPARED_SESSIONS: usize = 4;
+pub const DEFAULT_PASS_THRESHOLD: i32 = 70;
+pub const EXPORT_FILES: &[&str] = &[
+    "answers.csv",
+    "answers.json",
+    "categories.csv",
+    "categories.json",
+];
+
+// Bookmarks & notes
+pub const BOOKMARKS_URL: &str = "/bookmarks";
+pub const MAX_NOTE_LENGTH: usize = 10_000;
+
+// Activity & daily goals
+pub const ACTIVITY_URL: &str = "/activity";
+pub const ACTIVITY_TODAY_URL: &str = "/activity/today";
+pub const DAILY_GOAL_URL: &str = "/activity/goal";
+pub const DAILY_GOAL_KINDS: &[&str] = &["questions", "minutes"];
+/// The goal kind that clears the daily goal
+pub const NO_DAILY_GOAL: &str = "none";
+pub const MAX_DAILY_GOAL: i32 = 1000;
+pub const HEATMAP_WEEKS: i32 = 26;
+
+// Weekly digest email
+pub const DIGEST_MIN_CATEGORY_ANSWERS: i64 = 3;
+pub const DIGEST_WEAKEST_CATEGORIES: i64 = 3;
+pub const DIGEST_CHECK_INTERVAL_SECS: u64 = 60 * 60;
+
+// Groups
+pub const GROUPS_URL: &str = "/groups";
+pub const MAX_GROUP_NAME_LENGTH: usize = 100;
+
+pub fn group_url(public_id: &str) -> String {
+    format!("/groups/{public_id}")
+}
+
+pub fn group_quizzes_url(public_id: &str) -> String {
+    format!("/groups/{public_id}/quizzes")
+}
+
+pub fn group_quiz_url(public_id: &str, quiz_public_id: &str) -> String {
+    format!("/groups/{public_id}/quizzes/{quiz_public_id}")
+}
+
+pub fn group_member_url(public_id: &str, user_id: i32) -> String {
+    format!("/groups/{public_id}/members/{user_id}")
+}
+
+pub fn group_invite_url(invite_code: &str) -> String {
+    format!("/groups/join/{invite_code}")
+}
+
+// Assignments
+pub const ASSIGNMENTS_URL: &str = "/assignments";
+
+pub fn assignment_url(public_id: &str) -> String {
+    format!("/assignments/{public_id}")
+}
+
+pub fn start_assignment_url(public_id: &str) -> String {
+    format!("/assignments/{public_id}/start")
+}
+
+// Collaborators
+pub const COLLABORATOR_ROLES: &[&str] = &["editor", "sharing"];
+/// Most recent changes shown in a quiz's history
+pub const QUIZ_CHANGES_SHOWN: i64 = 50;
+
+pub fn quiz_collaborators_url(public_id: &str) -> String {
+    format!("/quiz/{public_id}/collaborators")
+}
+
+pub fn quiz_collaborator_url(public_id: &str, user_id: i32) -> String {
+    format!("/quiz/{public_id}/collaborators/{user_id}")
+}
+
+pub fn quiz_invitation_url(public_id: &str, invitation_id: i32) -> String {
+    format!("/quiz/{public_id}/invitations/{invitation_id}")
+}
+
+/// Invitations the user received, loaded into their quiz list
+pub const INVITATIONS_URL: &str = "/invitations";
+
+pub fn accept_invitation_url(invitation_id: i32) -> String {
+    format!("/invitations/{invitation_id}/accept")
+}
+
+pub fn decline_invitation_url(invitation_id: i32) -> String {
+    format!("/invitations/{invitation_id}/decline")
+}
 
 // Admin
 pub const ADMIN_URL: &str = "/admin";
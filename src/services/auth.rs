@@ -1,6 +1,6 @@
 use color_eyre::Result;
 
-use crate::db::models::AuthUser;
+use crate::db::models::{AuthUser, DigestRecipient, WeeklyDigest};
 use crate::db::Db;
 use crate::email::ResendEmailSender;
 
@@ -106,6 +106,14 @@ pub trait EmailSender: Send + Sync {
         to_email: &str,
         reset_url: &str,
     ) -> impl std::future::Future<Output = Result<()>> + Send;
+
+    /// Weekly progress summary; links point into the app at `base_url`.
+    fn send_weekly_digest(
+        &self,
+        recipient: &DigestRecipient,
+        digest: &WeeklyDigest,
+        base_url: &str,
+    ) -> impl std::future::Future<Output = Result<()>> + Send;
 }
 
 // ---------------------------------------------------------------------------
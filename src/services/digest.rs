@@ -0,0 +1,269 @@
+use std::time::Duration;
+
+use color_eyre::Result;
+
+use crate::db::models::{DigestRecipient, WeeklyDigest};
+use crate::db::Db;
+use crate::email::ResendEmailSender;
+use crate::names;
+use crate::services::auth::EmailSender;
+
+// ---------------------------------------------------------------------------
+// DigestRepository trait (DIP: service defines the abstraction it needs)
+// ---------------------------------------------------------------------------
+
+#[cfg_attr(test, mockall::automock)]
+pub trait DigestRepository: Send + Sync {
+    fn users_due_for_digest(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<DigestRecipient>>> + Send;
+
+    fn get_weekly_digest(
+        &self,
+        user_id: i32,
+    ) -> impl std::future::Future<Output = Result<WeeklyDigest>> + Send;
+
+    fn mark_digest_sent(
+        &self,
+        user_id: i32,
+    ) -> impl std::future::Future<Output = Result<()>> + Send;
+}
+
+// ---------------------------------------------------------------------------
+// DigestService
+// ---------------------------------------------------------------------------
+
+pub struct DigestService<R: DigestRepository = Db, E: EmailSender = ResendEmailSender> {
+    repo: R,
+    email: E,
+    base_url: String,
+}
+
+impl<R: DigestRepository, E: EmailSender> DigestService<R, E> {
+    pub fn new(repo: R, email: E, base_url: String) -> Self {
+        Self {
+            repo,
+            email,
+            base_url,
+        }
+    }
+
+    /// Email every user whose weekly digest is due. A failed send is logged and retried on
+    /// the next run; a week without any activity or pending review is skipped silently. A
+    /// user that cannot be marked as sent is logged and does not stop the rest of the batch.
+    /// Returns the number of emails sent.
+    pub async fn send_due_digests(&self) -> Result<usize> {
+        if !self.email.is_enabled() {
+            return Ok(0);
+        }
+
+        let mut sent = 0;
+        for recipient in self.repo.users_due_for_digest().await? {
+            let digest = match self.repo.get_weekly_digest(recipient.user_id).await {
+                Ok(digest) => digest,
+                Err(e) => {
+                    tracing::error!("failed to build weekly digest for {}: {e}", recipient.email);
+                    continue;
+                }
+            };
+
+            if digest.questions_answered == 0 && digest.due_reviews == 0 {
+                self.mark_sent(&recipient).await;
+                continue;
+            }
+
+            if let Err(e) = self
+                .email
+                .send_weekly_digest(&recipient, &digest, &self.base_url)
+                .await
+            {
+                tracing::error!("failed to send weekly digest to {}: {e}", recipient.email);
+                continue;
+            }
+
+            self.mark_sent(&recipient).await;
+            sent += 1;
+        }
+
+        Ok(sent)
+    }
+
+    async fn mark_sent(&self, recipient: &DigestRecipient) {
+        if let Err(e) = self.repo.mark_digest_sent(recipient.user_id).await {
+            tracing::error!(
+                "failed to mark weekly digest sent for {}: {e}",
+                recipient.email
+            );
+        }
+    }
+
+    /// Check for due digests every `names::DIGEST_CHECK_INTERVAL_SECS`, forever.
+    pub async fn run(self) {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(names::DIGEST_CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match self.send_due_digests().await {
+                Ok(0) => {}
+                Ok(sent) => tracing::info!("sent {sent} weekly digest emails"),
+                Err(e) => tracing::error!("weekly digest job failed: {e}"),
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::services::auth::MockEmailSender;
+    use mockall::predicate::eq;
+
+    fn recipient(user_id: i32) -> DigestRecipient {
+        DigestRecipient {
+            user_id,
+            email: format!("user{user_id}@example.com"),
+            display_name: format!("User {user_id}"),
+            locale: "en".to_string(),
+        }
+    }
+
+    fn active_week() -> WeeklyDigest {
+        WeeklyDigest {
+            questions_answered: 12,
+            active_ms: 600_000,
+            accuracy: Some(75.0),
+            previous_accuracy: Some(60.0),
+            weakest: vec![],
+            due_reviews: 3,
+        }
+    }
+
+    fn repo_with(recipients: Vec<DigestRecipient>, digest: WeeklyDigest) -> MockDigestRepository {
+        let mut repo = MockDigestRepository::new();
+        repo.expect_users_due_for_digest().returning(move || {
+            let recipients = recipients.clone();
+            Box::pin(async move { Ok(recipients) })
+        });
+        repo.expect_get_weekly_digest().returning(move |_| {
+            let digest = digest.clone();
+            Box::pin(async move { Ok(digest) })
+        });
+        repo
+    }
+
+    fn service(
+        repo: MockDigestRepository,
+        email: MockEmailSender,
+    ) -> DigestService<MockDigestRepository, MockEmailSender> {
+        DigestService::new(repo, email, "http://localhost".to_string())
+    }
+
+    #[tokio::test]
+    async fn sends_and_marks_each_due_user() {
+        let mut repo = repo_with(vec![recipient(1), recipient(2)], active_week());
+        repo.expect_mark_digest_sent()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        repo.expect_mark_digest_sent()
+            .with(eq(2))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let mut email = MockEmailSender::new();
+        email.expect_is_enabled().returning(|| true);
+        email
+            .expect_send_weekly_digest()
+            .withf(|_, digest, base_url| {
+                digest.questions_answered == 12 && base_url == "http://localhost"
+            })
+            .times(2)
+            .returning(|_, _, _| Box::pin(async { Ok(()) }));
+
+        let sent = service(repo, email).send_due_digests().await.unwrap();
+        assert_eq!(sent, 2);
+    }
+
+    #[tokio::test]
+    async fn failed_send_is_not_marked_and_others_continue() {
+        let mut repo = repo_with(vec![recipient(1), recipient(2)], active_week());
+        repo.expect_mark_digest_sent()
+            .with(eq(2))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let mut email = MockEmailSender::new();
+        email.expect_is_enabled().returning(|| true);
+        email
+            .expect_send_weekly_digest()
+            .returning(|recipient, _, _| {
+                let failed = recipient.user_id == 1;
+                Box::pin(async move {
+                    if failed {
+                        color_eyre::eyre::bail!("Resend API returned 500");
+                    }
+                    Ok(())
+                })
+            });
+
+        let sent = service(repo, email).send_due_digests().await.unwrap();
+        assert_eq!(sent, 1);
+    }
+
+    #[tokio::test]
+    async fn failed_mark_does_not_stop_the_batch() {
+        let mut repo = repo_with(vec![recipient(1), recipient(2)], active_week());
+        repo.expect_mark_digest_sent()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| Box::pin(async { color_eyre::eyre::bail!("connection reset") }));
+        repo.expect_mark_digest_sent()
+            .with(eq(2))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let mut email = MockEmailSender::new();
+        email.expect_is_enabled().returning(|| true);
+        email
+            .expect_send_weekly_digest()
+            .times(2)
+            .returning(|_, _, _| Box::pin(async { Ok(()) }));
+
+        let sent = service(repo, email).send_due_digests().await.unwrap();
+        assert_eq!(sent, 2);
+    }
+
+    #[tokio::test]
+    async fn quiet_week_is_skipped_but_marked() {
+        let mut repo = repo_with(vec![recipient(1)], WeeklyDigest::default());
+        repo.expect_mark_digest_sent()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let mut email = MockEmailSender::new();
+        email.expect_is_enabled().returning(|| true);
+        email.expect_send_weekly_digest().never();
+
+        let sent = service(repo, email).send_due_digests().await.unwrap();
+        assert_eq!(sent, 0);
+    }
+
+    #[tokio::test]
+    async fn disabled_email_sends_nothing() {
+        let mut repo = MockDigestRepository::new();
+        repo.expect_users_due_for_digest().never();
+
+        let mut email = MockEmailSender::new();
+        email.expect_is_enabled().returning(|| false);
+        email.expect_send_weekly_digest().never();
+
+        let sent = service(repo, email).send_due_digests().await.unwrap();
+        assert_eq!(sent, 0);
+    }
+}
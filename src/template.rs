@@ -0,0 +1,559 @@
+//! Parameterized question templates.
+//!
+//! A templated question declares integer variables, and any of its texts may embed
+//! `{expression}` placeholders such as `{a * b}` or `{km * 0.621:2}` (two decimals).
+//! Values are drawn once per session and stored with the session's questions, so
+//! the same numbers come back on resume and review.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::models::TemplateVariable;
+
+/// Drawn value of every variable of a question.
+pub type TemplateValues = BTreeMap<String, i64>;
+
+/// Redraws allowed when two options render to the same text.
+const MAX_DRAWS: usize = 20;
+
+/// Nesting allowed in an expression, so an uploaded formula cannot exhaust the stack.
+const MAX_NESTING: usize = 32;
+
+/// Largest magnitude of a variable bound, so drawing never overflows.
+const MAX_VARIABLE_BOUND: i64 = 1_000_000_000;
+
+/// Most decimals a placeholder may round to; `format!` panics on huge precisions.
+const MAX_DECIMALS: usize = 10;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum TemplateError {
+    #[error("unclosed placeholder in \"{0}\"")]
+    Unclosed(String),
+    #[error("invalid expression \"{0}\"")]
+    Syntax(String),
+    #[error("unknown variable \"{0}\"")]
+    UnknownVariable(String),
+    #[error("unknown function \"{0}\"")]
+    UnknownFunction(String),
+    #[error("variable \"{0}\" needs min <= max, both within ±1000000000, and a positive step")]
+    InvalidRange(String),
+    #[error("expression \"{0}\" is nested too deeply")]
+    TooDeep(String),
+    #[error("placeholder \"{0}\" rounds to more than 10 decimals")]
+    TooManyDecimals(String),
+}
+
+#[derive(Debug)]
+enum Expr {
+    Number(f64),
+    Variable(String),
+    Negate(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+const FUNCTIONS: &[&str] = &["abs", "round", "floor", "ceil", "sqrt", "min", "max"];
+
+struct Parser<'a> {
+    src: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(src: &'a str) -> Result<Expr, TemplateError> {
+        let mut parser = Parser {
+            src,
+            chars: src.chars().collect(),
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.expr()?;
+        parser.skip_spaces();
+        if parser.pos != parser.chars.len() {
+            return Err(parser.error());
+        }
+        Ok(expr)
+    }
+
+    fn error(&self) -> TemplateError {
+        TemplateError::Syntax(self.src.trim().to_string())
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_spaces();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, TemplateError> {
+        let mut lhs = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr, TemplateError> {
+        let mut lhs = self.unary()?;
+        while let Some(op @ ('*' | '/' | '%')) = self.peek() {
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    /// Every nested parenthesis, negation, power and call goes through here.
+    fn unary(&mut self) -> Result<Expr, TemplateError> {
+        if self.depth >= MAX_NESTING {
+            return Err(TemplateError::TooDeep(self.src.trim().to_string()));
+        }
+        self.depth += 1;
+        let expr = self.unary_inner();
+        self.depth -= 1;
+        expr
+    }
+
+    fn unary_inner(&mut self) -> Result<Expr, TemplateError> {
+        if self.eat('-') {
+            return Ok(Expr::Negate(Box::new(self.unary()?)));
+        }
+        let base = self.atom()?;
+        if self.eat('^') {
+            return Ok(Expr::Binary('^', Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expr, TemplateError> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let inner = self.expr()?;
+                if !self.eat(')') {
+                    return Err(self.error());
+                }
+                Ok(inner)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let start = self.pos;
+                while self
+                    .chars
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_ascii_digit() || *c == '.')
+                {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                text.parse().map(Expr::Number).map_err(|_| self.error())
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let start = self.pos;
+                while self
+                    .chars
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+                {
+                    self.pos += 1;
+                }
+                let name: String = self.chars[start..self.pos].iter().collect();
+                if !self.eat('(') {
+                    return Ok(Expr::Variable(name));
+                }
+                let mut args = vec![self.expr()?];
+                while self.eat(',') {
+                    args.push(self.expr()?);
+                }
+                if !self.eat(')') {
+                    return Err(self.error());
+                }
+                Ok(Expr::Call(name, args))
+            }
+            _ => Err(self.error()),
+        }
+    }
+}
+
+impl Expr {
+    fn check(&self, variables: &BTreeSet<&str>) -> Result<(), TemplateError> {
+        match self {
+            Expr::Number(_) => Ok(()),
+            Expr::Variable(name) if variables.contains(name.as_str()) => Ok(()),
+            Expr::Variable(name) => Err(TemplateError::UnknownVariable(name.clone())),
+            Expr::Negate(inner) => inner.check(variables),
+            Expr::Binary(_, lhs, rhs) => {
+                lhs.check(variables)?;
+                rhs.check(variables)
+            }
+            Expr::Call(name, args) => {
+                let arity_ok = match name.as_str() {
+                    "min" | "max" => args.len() == 2,
+                    _ => args.len() == 1,
+                };
+                if !FUNCTIONS.contains(&name.as_str()) || !arity_ok {
+                    return Err(TemplateError::UnknownFunction(name.clone()));
+                }
+                args.iter().try_for_each(|arg| arg.check(variables))
+            }
+        }
+    }
+
+    fn eval(&self, values: &TemplateValues) -> f64 {
+        match self {
+            Expr::Number(n) => *n,
+            Expr::Variable(name) => values.get(name).map_or(f64::NAN, |v| *v as f64),
+            Expr::Negate(inner) => -inner.eval(values),
+            Expr::Binary(op, lhs, rhs) => {
+                let (a, b) = (lhs.eval(values), rhs.eval(values));
+                match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => a / b,
+                    '%' => a % b,
+                    _ => a.powf(b),
+                }
+            }
+            Expr::Call(name, args) => {
+                let a = args.first().map_or(f64::NAN, |arg| arg.eval(values));
+                let b = args.get(1).map_or(f64::NAN, |arg| arg.eval(values));
+                match name.as_str() {
+                    "abs" => a.abs(),
+                    "round" => a.round(),
+                    "floor" => a.floor(),
+                    "ceil" => a.ceil(),
+                    "sqrt" => a.sqrt(),
+                    "min" => a.min(b),
+                    "max" => a.max(b),
+                    _ => f64::NAN,
+                }
+            }
+        }
+    }
+}
+
+/// A piece of template text: literal text or a `{expression:decimals}` placeholder.
+enum Segment<'a> {
+    Text(String),
+    Placeholder(&'a str, Option<usize>),
+}
+
+fn segments(text: &str) -> Result<Vec<Segment<'_>>, TemplateError> {
+    let mut out = Vec::new();
+    let mut literal = String::new();
+    let mut rest = text;
+    while let Some(idx) = rest.find(['{', '}']) {
+        literal.push_str(&rest[..idx]);
+        let brace = &rest[idx..idx + 1];
+        rest = &rest[idx + 1..];
+        // `{{` and `}}` stand for literal braces
+        if rest.starts_with(brace) {
+            literal.push_str(brace);
+            rest = &rest[1..];
+            continue;
+        }
+        if brace == "}" {
+            literal.push('}');
+            continue;
+        }
+        let end = rest
+            .find('}')
+            .ok_or_else(|| TemplateError::Unclosed(text.to_string()))?;
+        let inner = &rest[..end];
+        rest = &rest[end + 1..];
+        let (expr, decimals) = match inner.rsplit_once(':') {
+            Some((expr, digits)) => {
+                let decimals: usize = digits
+                    .trim()
+                    .parse()
+                    .map_err(|_| TemplateError::Syntax(inner.to_string()))?;
+                if decimals > MAX_DECIMALS {
+                    return Err(TemplateError::TooManyDecimals(inner.to_string()));
+                }
+                (expr, Some(decimals))
+            }
+            None => (inner, None),
+        };
+        out.push(Segment::Text(std::mem::take(&mut literal)));
+        out.push(Segment::Placeholder(expr, decimals));
+    }
+    literal.push_str(rest);
+    out.push(Segment::Text(literal));
+    Ok(out)
+}
+
+fn format_number(value: f64, decimals: Option<usize>) -> String {
+    if !value.is_finite() {
+        return "?".to_string();
+    }
+    match decimals {
+        Some(decimals) => format!("{value:.decimals$}"),
+        None if (value - value.round()).abs() < 1e-9 => format!("{}", value.round() as i64),
+        None => {
+            let text = format!("{value:.4}");
+            text.trim_end_matches('0').trim_end_matches('.').to_string()
+        }
+    }
+}
+
+/// Check that every placeholder in `text` parses and only uses declared variables.
+pub fn validate(
+    text: &str,
+    variables: &BTreeMap<String, TemplateVariable>,
+) -> Result<(), TemplateError> {
+    let names: BTreeSet<&str> = variables.keys().map(String::as_str).collect();
+    for segment in segments(text)? {
+        if let Segment::Placeholder(expr, _) = segment {
+            Parser::parse(expr)?.check(&names)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_variables(
+    variables: &BTreeMap<String, TemplateVariable>,
+) -> Result<(), TemplateError> {
+    for (name, var) in variables {
+        let in_bounds = |n: i64| (-MAX_VARIABLE_BOUND..=MAX_VARIABLE_BOUND).contains(&n);
+        if var.min > var.max || var.step <= 0 || !in_bounds(var.min) || !in_bounds(var.max) {
+            return Err(TemplateError::InvalidRange(name.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Substitute drawn values into `text`. Text that fails to parse is returned unchanged.
+pub fn render(text: &str, values: &TemplateValues) -> String {
+    render_checked(text, values).0
+}
+
+/// Render `text`, also reporting whether every placeholder evaluated to a finite number.
+fn render_checked(text: &str, values: &TemplateValues) -> (String, bool) {
+    let Ok(segments) = segments(text) else {
+        return (text.to_string(), true);
+    };
+    let mut finite = true;
+    let rendered = segments
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => text,
+            Segment::Placeholder(expr, decimals) => match Parser::parse(expr) {
+                Ok(expr) => {
+                    let value = expr.eval(values);
+                    finite &= value.is_finite();
+                    format_number(value, decimals)
+                }
+                Err(_) => format!("{{{expr}}}"),
+            },
+        })
+        .collect();
+    (rendered, finite)
+}
+
+fn draw(variables: &BTreeMap<String, TemplateVariable>, rng: &mut StdRng) -> TemplateValues {
+    variables
+        .iter()
+        .map(|(name, var)| {
+            // Stored variables are validated, but stay in range even if they were not
+            let step = var.step.max(1);
+            let steps = var
+                .max
+                .checked_sub(var.min)
+                .map_or(0, |span| span.max(0) / step);
+            let offset = step.saturating_mul(rng.gen_range(0..=steps));
+            (name.clone(), var.min.saturating_add(offset))
+        })
+        .collect()
+}
+
+/// Draw variable values for one question from `seed`. Draws are repeated (deterministically)
+/// until every option renders to a distinct, computable value, so a distractor never
+/// duplicates the correct answer.
+pub fn sample_values(
+    variables: &BTreeMap<String, TemplateVariable>,
+    option_texts: &[String],
+    seed: u64,
+) -> TemplateValues {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut values = draw(variables, &mut rng);
+    for _ in 1..MAX_DRAWS {
+        let mut seen = HashSet::new();
+        let distinct = option_texts.iter().all(|text| {
+            let (rendered, finite) = render_checked(text, &values);
+            finite && seen.insert(rendered)
+        });
+        if distinct {
+            break;
+        }
+        values = draw(variables, &mut rng);
+    }
+    values
+}
+
+/// Parse stored values; `None` for questions that are not templated.
+pub fn parse_values(json: Option<&str>) -> Option<TemplateValues> {
+    json.and_then(|json| serde_json::from_str(json).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(spec: &[(&str, i64, i64)]) -> BTreeMap<String, TemplateVariable> {
+        spec.iter()
+            .map(|(name, min, max)| {
+                (
+                    name.to_string(),
+                    TemplateVariable {
+                        min: *min,
+                        max: *max,
+                        step: 1,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn values(spec: &[(&str, i64)]) -> TemplateValues {
+        spec.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn render_substitutes_expressions() {
+        let v = values(&[("a", 7), ("b", 6)]);
+        assert_eq!(render("What is {a} × {b}?", &v), "What is 7 × 6?");
+        assert_eq!(render("{a * b}", &v), "42");
+        assert_eq!(render("{(a + b) * 2 - 1}", &v), "25");
+        assert_eq!(render("{a / b:2} km", &v), "1.17 km");
+        assert_eq!(render("{a / 4}", &v), "1.75");
+        assert_eq!(render("{-a + 2^3}", &v), "1");
+        assert_eq!(render("{max(a, b) % 4}", &v), "3");
+    }
+
+    #[test]
+    fn render_keeps_escaped_braces() {
+        let v = values(&[("a", 2)]);
+        assert_eq!(render("{{a}} = {a}", &v), "{a} = 2");
+    }
+
+    #[test]
+    fn division_by_zero_renders_placeholder() {
+        let v = values(&[("a", 1)]);
+        assert_eq!(render("{a / 0}", &v), "?");
+    }
+
+    #[test]
+    fn validate_rejects_bad_templates() {
+        let v = vars(&[("a", 1, 9)]);
+        assert!(validate("What is {a} squared?", &v).is_ok());
+        assert_eq!(
+            validate("{b}", &v),
+            Err(TemplateError::UnknownVariable("b".to_string()))
+        );
+        assert!(matches!(
+            validate("{a +}", &v),
+            Err(TemplateError::Syntax(_))
+        ));
+        assert!(matches!(
+            validate("{a", &v),
+            Err(TemplateError::Unclosed(_))
+        ));
+        assert!(matches!(
+            validate("{pow(a)}", &v),
+            Err(TemplateError::UnknownFunction(_))
+        ));
+        assert!(validate_variables(&vars(&[("a", 5, 1)])).is_err());
+        assert!(validate_variables(&vars(&[("a", i64::MIN, i64::MAX)])).is_err());
+    }
+
+    #[test]
+    fn validate_rejects_huge_precision() {
+        let v = vars(&[("a", 1, 9)]);
+        assert!(validate("{a / 3:10}", &v).is_ok());
+        assert_eq!(
+            validate("{a:70000}", &v),
+            Err(TemplateError::TooManyDecimals("a:70000".to_string()))
+        );
+    }
+
+    #[test]
+    fn validate_rejects_deep_nesting() {
+        let v = vars(&[("a", 1, 9)]);
+        let parens = format!("{{{}a{}}}", "(".repeat(10_000), ")".repeat(10_000));
+        assert!(matches!(
+            validate(&parens, &v),
+            Err(TemplateError::TooDeep(_))
+        ));
+        let negations = format!("{{{}a}}", "-".repeat(10_000));
+        assert!(matches!(
+            validate(&negations, &v),
+            Err(TemplateError::TooDeep(_))
+        ));
+        assert!(validate("{((a + 1) * -(a - 1))}", &v).is_ok());
+    }
+
+    #[test]
+    fn extreme_ranges_do_not_overflow() {
+        let v = vars(&[("a", i64::MIN, i64::MAX), ("b", i64::MAX - 1, i64::MAX)]);
+        for seed in 0..20 {
+            let drawn = sample_values(&v, &[], seed);
+            assert!(drawn["b"] >= i64::MAX - 1);
+        }
+    }
+
+    #[test]
+    fn sampling_is_deterministic_and_in_range() {
+        let v = vars(&[("a", 2, 12), ("b", 2, 12)]);
+        let options = vec!["{a * b}".to_string(), "{a + b}".to_string()];
+        let first = sample_values(&v, &options, 42);
+        assert_eq!(first, sample_values(&v, &options, 42));
+        for value in first.values() {
+            assert!((2..=12).contains(value));
+        }
+    }
+
+    #[test]
+    fn sampling_avoids_duplicate_options() {
+        // a * 2 and a + 2 collide only at a = 2
+        let v = vars(&[("a", 2, 3)]);
+        let options = vec!["{a * 2}".to_string(), "{a + 2}".to_string()];
+        for seed in 0..20 {
+            assert_eq!(sample_values(&v, &options, seed)["a"], 3);
+        }
+    }
+
+    #[test]
+    fn steps_are_respected() {
+        let v: BTreeMap<String, TemplateVariable> = [(
+            "n".to_string(),
+            TemplateVariable {
+                min: 10,
+                max: 50,
+                step: 10,
+            },
+        )]
+        .into_iter()
+        .collect();
+        for seed in 0..20 {
+            assert_eq!(sample_values(&v, &[], seed)["n"] % 10, 0);
+        }
+    }
+}
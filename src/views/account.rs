@@ -11,7 +11,12 @@ pub enum ChangePasswordState {
     DemoUser,
 }
 
-pub fn account_page(user: &AuthUser, state: ChangePasswordState, locale: &str) -> Markup {
+pub fn account_page(
+    user: &AuthUser,
+    state: ChangePasswordState,
+    weekly_digest: bool,
+    locale: &str,
+) -> Markup {
     let (error_msg, success_msg) = match state {
         ChangePasswordState::NoError => (None, None),
         ChangePasswordState::IncorrectPassword => (
@@ -98,5 +103,39 @@ pub fn account_page(user: &AuthUser, state: ChangePasswordState, locale: &str) -
                 }
             }
         }
+
+        @if !user.is_demo {
+            h2 { (t!("account.weekly_digest_title", locale = locale)) }
+            (weekly_digest_form(weekly_digest, false, locale))
+        }
+    }
+}
+
+/// Opt-in switch for the weekly progress email, saved as soon as it is toggled.
+pub fn weekly_digest_form(enabled: bool, saved: bool, locale: &str) -> Markup {
+    html! {
+        article."article-narrow" id="weekly-digest" {
+            form hx-post=(names::WEEKLY_DIGEST_URL)
+                 hx-trigger="change"
+                 hx-target="#weekly-digest"
+                 hx-swap="outerHTML" {
+                label {
+                    input type="checkbox" role="switch" name="enabled" value="true" checked[enabled];
+                    (t!("account.weekly_digest_label", locale = locale))
+                }
+                small style="color: var(--color-muted);" {
+                    (t!("account.weekly_digest_help", locale = locale))
+                }
+            }
+            @if saved {
+                p style="color: var(--pico-ins-color);" {
+                    @if enabled {
+                        (t!("account.weekly_digest_on", locale = locale))
+                    } @else {
+                        (t!("account.weekly_digest_off", locale = locale))
+                    }
+                }
+            }
+        }
     }
 }
@@ -1,4 +1,4 @@
-use maud::{html, Markup};
+use maud::{html, Markup, PreEscaped};
 
 /// htmx navigation link with href fallback + hx-get for in-page swap.
 pub fn nav_link(href: &str, body: Markup) -> Markup {
@@ -12,3 +12,71 @@ pub fn nav_link(href: &str, body: Markup) -> Markup {
         }
     }
 }
+
+/// Render user-written Markdown. Raw HTML is shown as text and script links are dropped.
+pub fn markdown(source: &str) -> Markup {
+    use pulldown_cmark::{html::push_html, CowStr, Event, Options, Parser, Tag};
+
+    let events = Parser::new_ext(
+        source,
+        Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES,
+    )
+    .map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if !is_safe_url(&dest_url) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: CowStr::Borrowed("#"),
+            title,
+            id,
+        }),
+        other => other,
+    });
+
+    let mut out = String::new();
+    push_html(&mut out, events);
+    PreEscaped(out)
+}
+
+fn is_safe_url(url: &str) -> bool {
+    let scheme = url.split_once(':').map(|(scheme, _)| scheme);
+    match scheme {
+        Some(scheme) if !scheme.contains(['/', '?', '#']) => {
+            matches!(
+                scheme.to_ascii_lowercase().as_str(),
+                "http" | "https" | "mailto"
+            )
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_renders_formatting() {
+        let html = markdown("**bold** and `code`").into_string();
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains("<code>code</code>"));
+    }
+
+    #[test]
+    fn markdown_escapes_raw_html() {
+        let html = markdown("<script>alert(1)</script>").into_string();
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn markdown_drops_script_links() {
+        let html = markdown("[x](javascript:alert(1)) [y](https://example.com)").into_string();
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains(r#"href="https://example.com""#));
+    }
+}
@@ -0,0 +1,334 @@
+use crate::db::{
+    GroupMember, GroupModel, GroupQuiz, GroupQuizProgress, GroupSummary, ProgressCell, Quiz,
+};
+use crate::names;
+use crate::views::quiz as quiz_views;
+use maud::{html, Markup};
+use rust_i18n::t;
+
+/// Accuracy below which a progress cell is highlighted
+const WEAK_ACCURACY: f64 = 0.6;
+
+/// What a group page shows: everything for the instructor, the quizzes for a member.
+pub enum GroupView {
+    Owner {
+        members: Vec<GroupMember>,
+        progress: Vec<GroupQuizProgress>,
+        /// Quizzes of the instructor's library not assigned yet
+        assignable: Vec<Quiz>,
+    },
+    Member {
+        user_id: i32,
+        quizzes: Vec<GroupQuiz>,
+    },
+}
+
+pub fn groups_page(groups: &[GroupSummary], locale: &str) -> Markup {
+    html! {
+        h1 { (t!("group.title", locale = locale)) }
+        p style="color: var(--color-muted);" { (t!("group.intro", locale = locale)) }
+
+        article."article-narrow" {
+            form hx-post=(names::GROUPS_URL) hx-target="main" hx-swap="innerHTML" {
+                label {
+                    (t!("group.name_label", locale = locale))
+                    input type="text" name="name" required="true"
+                          maxlength=(names::MAX_GROUP_NAME_LENGTH)
+                          placeholder=(t!("group.name_placeholder", locale = locale));
+                }
+                button type="submit" style="width: fit-content;" { (t!("group.create", locale = locale)) }
+            }
+        }
+
+        @if groups.is_empty() {
+            p { (t!("group.empty", locale = locale)) }
+        } @else {
+            article {
+                table {
+                    thead { tr {
+                        th { (t!("group.name_label", locale = locale)) }
+                        th { (t!("group.instructor", locale = locale)) }
+                        th { (t!("group.members_heading", locale = locale)) }
+                        th { (t!("group.quizzes_heading", locale = locale)) }
+                    } }
+                    tbody {
+                        @for g in groups {
+                            tr {
+                                td {
+                                    a hx-get=(names::group_url(&g.public_id))
+                                      hx-push-url="true"
+                                      hx-target="main"
+                                      href=(names::group_url(&g.public_id)) { (g.name) }
+                                    @if g.is_owner {
+                                        " " span."badge-status badge-progress" { (t!("group.you_lead", locale = locale)) }
+                                    }
+                                }
+                                td { (g.owner_name) }
+                                td { (g.member_count) }
+                                td { (g.quiz_count) }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn group_page(group: &GroupModel, view: GroupView, locale: &str) -> Markup {
+    html! {
+        h1 { (group.name) }
+        p style="color: var(--color-muted);" {
+            (t!("group.led_by", locale = locale, name = group.owner_name))
+        }
+        @match view {
+            GroupView::Owner { members, progress, assignable } => {
+                (owner_sections(group, &members, &progress, &assignable, locale))
+            }
+            GroupView::Member { user_id, quizzes } => {
+                (member_sections(group, user_id, &quizzes, locale))
+            }
+        }
+    }
+}
+
+fn owner_sections(
+    group: &GroupModel,
+    members: &[GroupMember],
+    progress: &[GroupQuizProgress],
+    assignable: &[Quiz],
+    locale: &str,
+) -> Markup {
+    html! {
+        article {
+            h4 { (t!("group.invite_heading", locale = locale)) }
+            small style="color: var(--color-muted);" { (t!("group.invite_desc", locale = locale)) }
+            div style="display: flex; gap: 0.5rem; align-items: center;" {
+                input type="text" id="group-invite-url" readonly
+                      value=(names::group_invite_url(&group.invite_code))
+                      style="margin-bottom: 0;";
+                button type="button" class="secondary" data-copy-url="group-invite-url"
+                       style="width: fit-content; margin-bottom: 0;" {
+                    span."material-symbols-rounded" style="font-size: 1rem; vertical-align: middle;" { "content_copy" }
+                    " " (t!("challenge.copy", locale = locale))
+                }
+            }
+        }
+
+        article {
+            h4 { (t!("group.quizzes_heading", locale = locale)) }
+            @if progress.is_empty() {
+                p { (t!("group.no_quizzes", locale = locale)) }
+            } @else {
+                ul."group-quizzes" {
+                    @for table in progress {
+                        li {
+                            a hx-get=(names::quiz_dashboard_url(&table.quiz.public_id))
+                              hx-push-url="true"
+                              hx-target="main"
+                              href=(names::quiz_dashboard_url(&table.quiz.public_id)) { (table.quiz.name) }
+                            " "
+                            button."secondary outline"
+                                   hx-delete=(names::group_quiz_url(&group.public_id, &table.quiz.public_id))
+                                   hx-target="main"
+                                   hx-confirm=(t!("group.unassign_confirm", locale = locale))
+                                   aria-label=(t!("group.unassign", locale = locale)) {
+                                span."material-symbols-rounded" { "close" }
+                            }
+                        }
+                    }
+                }
+            }
+            @if assignable.is_empty() {
+                small style="color: var(--color-muted);" { (t!("group.nothing_to_assign", locale = locale)) }
+            } @else {
+                form."group-assign" hx-post=(names::group_quizzes_url(&group.public_id))
+                     hx-target="main" hx-swap="innerHTML" {
+                    select name="quiz_id" aria-label=(t!("group.assign_label", locale = locale)) {
+                        @for quiz in assignable {
+                            option value=(quiz.public_id) { (quiz.name) }
+                        }
+                    }
+                    button type="submit" { (t!("group.assign", locale = locale)) }
+                }
+            }
+            small style="color: var(--color-muted);" {
+                (t!("group.assignments_hint", locale = locale)) " "
+                a hx-get=(names::ASSIGNMENTS_URL)
+                  hx-push-url="true"
+                  hx-target="main"
+                  href=(names::ASSIGNMENTS_URL) { (t!("assignment.title", locale = locale)) }
+            }
+        }
+
+        h2 { (t!("group.progress_heading", locale = locale)) }
+        @if members.is_empty() {
+            p { (t!("group.no_members", locale = locale)) }
+        } @else {
+            @for table in progress {
+                article {
+                    h4 { (table.quiz.name) }
+                    (progress_table(table, locale))
+                }
+            }
+        }
+
+        article {
+            h4 { (t!("group.members_heading", locale = locale)) " (" (members.len()) ")" }
+            @if members.is_empty() {
+                p style="margin-bottom: 0;" { (t!("group.no_members", locale = locale)) }
+            } @else {
+                ul."group-members" {
+                    @for member in members {
+                        li {
+                            (member.display_name)
+                            " "
+                            button."secondary outline"
+                                   hx-delete=(names::group_member_url(&group.public_id, member.user_id))
+                                   hx-target="main"
+                                   hx-confirm=(t!("group.remove_confirm", locale = locale, name = member.display_name))
+                                   aria-label=(t!("group.remove_member", locale = locale)) {
+                                span."material-symbols-rounded" { "person_remove" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        button."secondary outline"
+               hx-delete=(names::group_url(&group.public_id))
+               hx-target="main"
+               hx-confirm=(t!("group.delete_confirm", locale = locale))
+               style="width: fit-content;" {
+            (t!("group.delete", locale = locale))
+        }
+    }
+}
+
+/// A row per member with their whole-quiz progress, then one column per category.
+fn progress_table(table: &GroupQuizProgress, locale: &str) -> Markup {
+    html! {
+        div."compare-table group-progress" {
+            table {
+                thead { tr {
+                    th { (t!("group.member", locale = locale)) }
+                    th { (t!("group.overall", locale = locale)) }
+                    @for category in &table.categories {
+                        th { (category.as_deref().unwrap_or("-")) }
+                    }
+                    th { (t!("dashboard.study_time", locale = locale)) }
+                } }
+                tbody {
+                    @for member in &table.members {
+                        tr {
+                            td { (member.display_name) }
+                            (progress_cell(&member.total))
+                            @for cell in &member.categories {
+                                (progress_cell(cell))
+                            }
+                            td { (quiz_views::format_study_time(member.active_ms)) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn progress_cell(cell: &ProgressCell) -> Markup {
+    let accuracy = cell.accuracy();
+    let weak = accuracy.is_some_and(|a| a < WEAK_ACCURACY);
+    html! {
+        td class=[weak.then_some("group-weak")] {
+            @if let Some(accuracy) = accuracy {
+                strong { (format!("{:.0}%", accuracy * 100.0)) }
+            } @else {
+                "—"
+            }
+            br;
+            small style="color: var(--color-muted);" { (cell.seen) "/" (cell.questions) }
+        }
+    }
+}
+
+fn member_sections(
+    group: &GroupModel,
+    user_id: i32,
+    quizzes: &[GroupQuiz],
+    locale: &str,
+) -> Markup {
+    html! {
+        article {
+            h4 { (t!("group.quizzes_heading", locale = locale)) }
+            @if quizzes.is_empty() {
+                p { (t!("group.no_quizzes", locale = locale)) }
+            } @else {
+                ul."group-quizzes" {
+                    @for quiz in quizzes {
+                        li {
+                            a hx-get=(names::quiz_dashboard_url(&quiz.public_id))
+                              hx-push-url="true"
+                              hx-target="main"
+                              href=(names::quiz_dashboard_url(&quiz.public_id)) { (quiz.name) }
+                            small style="color: var(--color-muted);" {
+                                " · " (quiz.question_count) (t!("share.questions_suffix", locale = locale))
+                            }
+                        }
+                    }
+                }
+            }
+            small style="color: var(--color-muted);" { (t!("group.visibility", locale = locale)) }
+        }
+        button."secondary outline"
+               hx-delete=(names::group_member_url(&group.public_id, user_id))
+               hx-target="main"
+               hx-confirm=(t!("group.leave_confirm", locale = locale))
+               style="width: fit-content;" {
+            (t!("group.leave", locale = locale))
+        }
+    }
+}
+
+/// Landing page of an invite link.
+pub fn invite_page(group: &GroupModel, is_member: bool, locale: &str) -> Markup {
+    html! {
+        h1 { (t!("group.invite_title", locale = locale)) }
+        article {
+            p {
+                (t!("group.invited_to", locale = locale))
+                strong { (group.name) }
+            }
+            p { (t!("group.led_by", locale = locale, name = group.owner_name)) }
+            @if is_member {
+                button hx-get=(names::group_url(&group.public_id))
+                       hx-push-url="true"
+                       hx-target="main"
+                       style="width: fit-content;" {
+                    (t!("group.open", locale = locale))
+                }
+            } @else {
+                p style="color: var(--color-muted);" { (t!("group.join_desc", locale = locale)) }
+                button hx-post=(names::group_invite_url(&group.invite_code))
+                       hx-target="main"
+                       hx-swap="innerHTML"
+                       style="width: fit-content;" {
+                    (t!("group.join", locale = locale))
+                }
+            }
+        }
+    }
+}
+
+pub fn group_not_available(locale: &str) -> Markup {
+    html! {
+        h1 { (t!("group.not_found_title", locale = locale)) }
+        p { (t!("group.not_found_desc", locale = locale)) }
+        button hx-get=(names::GROUPS_URL)
+               hx-push-url="true"
+               hx-target="main"
+               style="width: fit-content;" {
+            (t!("group.back_to_groups", locale = locale))
+        }
+    }
+}
@@ -463,6 +463,9 @@ pub fn quiz_list_with_error(quizzes: Vec<Quiz>, locale: &str, error: Option<&str
     html! {
         h1 { (t!("homepage.my_quizzes", locale = locale)) }
 
+        div hx-get=(names::ACTIVITY_TODAY_URL) hx-trigger="load" hx-swap="outerHTML" {}
+        div hx-get=(names::INVITATIONS_URL) hx-trigger="load" hx-swap="outerHTML" {}
+
         @if let Some(msg) = error {
             article style="border-left: 4px solid var(--color-danger); padding: 0.75rem 1rem; margin-bottom: 1rem;" {
                 p style="margin: 0; color: var(--color-danger);" { (msg) }
@@ -511,15 +514,27 @@ pub fn quiz_list_with_error(quizzes: Vec<Quiz>, locale: &str, error: Option<&str
                         }
                     }
                     div."card-actions" style="display: flex; align-items: center; gap: 0.75rem;" {
-                        span."card-actions material-symbols-rounded"
-                             data-rename-name=(quiz.name)
-                             data-rename-url=(names::rename_quiz_url(&quiz.public_id))
-                             title=(t!("homepage.rename", locale = locale))
-                             style="cursor: pointer; font-size: 1.2rem; opacity: 0.4; transition: opacity 0.15s;" {
-                            "edit"
+                        @if quiz.can_edit {
+                            span."card-actions material-symbols-rounded"
+                                 data-rename-name=(quiz.name)
+                                 data-rename-url=(names::rename_quiz_url(&quiz.public_id))
+                                 title=(t!("homepage.rename", locale = locale))
+                                 style="cursor: pointer; font-size: 1.2rem; opacity: 0.4; transition: opacity 0.15s;" {
+                                "edit"
+                            }
                         }
                         span style="flex: 1;" {}
-                        @if quiz.is_owner {
+                        @if quiz.can_edit {
+                            a."material-symbols-rounded"
+                              hx-get=(names::quiz_settings_url(&quiz.public_id))
+                              hx-push-url="true"
+                              hx-target="main"
+                              title=(t!("session_sizes.title", locale = locale))
+                              style="cursor: pointer; font-size: 1.2rem; opacity: 0.5; transition: opacity 0.15s;" {
+                                "tune"
+                            }
+                        }
+                        @if quiz.can_share {
                             (quiz_views::share_toggle_icon(&quiz.public_id, quiz.is_shared, locale))
                         }
                         @if quiz.is_owner {
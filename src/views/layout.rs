@@ -66,6 +66,30 @@ fn header(locale: &str, nav_user: Option<&NavUser<'_>>) -> Markup {
                                 html! { (t!("layout.marketplace", locale = locale)) },
                             ))
                         }
+                        li."secondary"."nav-feature-link" {
+                            (super::components::nav_link(
+                                names::BOOKMARKS_URL,
+                                html! { (t!("layout.bookmarks", locale = locale)) },
+                            ))
+                        }
+                        li."secondary"."nav-feature-link" {
+                            (super::components::nav_link(
+                                names::ACTIVITY_URL,
+                                html! { (t!("layout.activity", locale = locale)) },
+                            ))
+                        }
+                        li."secondary"."nav-feature-link" {
+                            (super::components::nav_link(
+                                names::GROUPS_URL,
+                                html! { (t!("layout.groups", locale = locale)) },
+                            ))
+                        }
+                        li."secondary"."nav-feature-link" {
+                            (super::components::nav_link(
+                                names::ASSIGNMENTS_URL,
+                                html! { (t!("layout.assignments", locale = locale)) },
+                            ))
+                        }
                     }
                     li."secondary"."nav-toggle-item" {
                         button
@@ -86,6 +110,30 @@ fn header(locale: &str, nav_user: Option<&NavUser<'_>>) -> Markup {
                                 html! { (t!("layout.marketplace", locale = locale)) },
                             ))
                         }
+                        li."secondary"."nav-menu-mobile-only" {
+                            (super::components::nav_link(
+                                names::BOOKMARKS_URL,
+                                html! { (t!("layout.bookmarks", locale = locale)) },
+                            ))
+                        }
+                        li."secondary"."nav-menu-mobile-only" {
+                            (super::components::nav_link(
+                                names::ACTIVITY_URL,
+                                html! { (t!("layout.activity", locale = locale)) },
+                            ))
+                        }
+                        li."secondary"."nav-menu-mobile-only" {
+                            (super::components::nav_link(
+                                names::GROUPS_URL,
+                                html! { (t!("layout.groups", locale = locale)) },
+                            ))
+                        }
+                        li."secondary"."nav-menu-mobile-only" {
+                            (super::components::nav_link(
+                                names::ASSIGNMENTS_URL,
+                                html! { (t!("layout.assignments", locale = locale)) },
+                            ))
+                        }
                     }
                     li."secondary" {
                         select."theme-select"
@@ -1,6 +1,7 @@
 pub mod account;
 pub mod admin;
 pub mod components;
+pub mod group;
 pub mod homepage;
 pub mod layout;
 pub mod marketplace;
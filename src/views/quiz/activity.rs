@@ -0,0 +1,166 @@
+use crate::{
+    db::{ActivitySummary, DailyActivity},
+    names,
+};
+use maud::{html, Markup};
+use rust_i18n::t;
+
+/// Heatmap shade for a day, from 0 (nothing answered) to 4.
+fn heat_level(questions: i32) -> u8 {
+    match questions {
+        0 => 0,
+        1..=9 => 1,
+        10..=24 => 2,
+        25..=49 => 3,
+        _ => 4,
+    }
+}
+
+/// Today's progress toward the daily goal as (done, target), in the goal's unit.
+fn goal_progress(summary: &ActivitySummary) -> Option<(i32, i32)> {
+    let goal = summary.goal.as_ref()?;
+    let today = summary.days.last().filter(|d| d.days_ago == 0);
+    let done = match goal.kind.as_str() {
+        "minutes" => today.map_or(0, |d| (d.active_ms / 60_000) as i32),
+        _ => today.map_or(0, |d| d.questions),
+    };
+    Some((done, goal.target))
+}
+
+fn goal_unit(kind: &str, locale: &str) -> String {
+    match kind {
+        "minutes" => t!("activity.unit_minutes", locale = locale).to_string(),
+        _ => t!("activity.unit_questions", locale = locale).to_string(),
+    }
+}
+
+fn streak_badge(summary: &ActivitySummary, locale: &str) -> Markup {
+    html! {
+        span."streak-badge" title=(t!("activity.current_streak", locale = locale)) {
+            span."material-symbols-rounded" { "local_fire_department" }
+            (summary.current_streak)
+            (t!("activity.days_suffix", locale = locale))
+        }
+    }
+}
+
+fn goal_bar(summary: &ActivitySummary, locale: &str) -> Markup {
+    html! {
+        @if let (Some((done, target)), Some(goal)) = (goal_progress(summary), &summary.goal) {
+            @let pct = (done.min(target) as f64 / target as f64 * 100.0) as u32;
+            div."goal-progress" {
+                div."quiz-progress-bar" {
+                    div."quiz-progress-fill" style=(format!("width: {pct}%;")) {}
+                }
+                span {
+                    @if done >= target {
+                        span."material-symbols-rounded" style="color: var(--color-success); font-size: 1rem; vertical-align: middle;" { "check_circle" }
+                        " "
+                    }
+                    (done) " / " (target) " " (goal_unit(&goal.kind, locale))
+                }
+            }
+        }
+    }
+}
+
+/// Compact goal and streak card shown above the quiz list.
+pub fn today_progress(summary: &ActivitySummary, locale: &str) -> Markup {
+    html! {
+        article."today-progress" {
+            div style="display: flex; align-items: center; gap: 1rem; flex-wrap: wrap;" {
+                strong { (t!("activity.today", locale = locale)) }
+                (streak_badge(summary, locale))
+                a hx-get=(names::ACTIVITY_URL)
+                  hx-push-url="true"
+                  hx-target="main"
+                  href=(names::ACTIVITY_URL)
+                  style="margin-left: auto; font-size: 0.9rem;" {
+                    @if summary.goal.is_some() {
+                        (t!("activity.view_activity", locale = locale))
+                    } @else {
+                        (t!("activity.set_goal_link", locale = locale))
+                    }
+                }
+            }
+            (goal_bar(summary, locale))
+        }
+    }
+}
+
+fn heatmap(days: &[DailyActivity], locale: &str) -> Markup {
+    let leading_blanks = days.first().map_or(0, |d| d.weekday - 1);
+    html! {
+        div."heatmap" role="img" aria-label=(t!("activity.heatmap_title", locale = locale)) {
+            @for _ in 0..leading_blanks {
+                span."heatmap-cell heatmap-blank" {}
+            }
+            @for day in days {
+                span class=(format!("heatmap-cell heatmap-{}", heat_level(day.questions)))
+                     title=(format!("{}: {}{}", day.date_label, day.questions, t!("activity.questions_suffix", locale = locale))) {}
+            }
+        }
+        div."heatmap-legend" {
+            (t!("activity.less", locale = locale))
+            @for level in 0..=4 {
+                span class=(format!("heatmap-cell heatmap-{level}")) {}
+            }
+            (t!("activity.more", locale = locale))
+        }
+    }
+}
+
+/// Heatmap of questions answered per day, streaks and the daily goal setting.
+pub fn activity_page(summary: &ActivitySummary, locale: &str) -> Markup {
+    let total: i32 = summary.days.iter().map(|d| d.questions).sum();
+    let goal_kind = summary
+        .goal
+        .as_ref()
+        .map_or(names::NO_DAILY_GOAL, |g| g.kind.as_str());
+
+    html! {
+        h1 { (t!("activity.title", locale = locale)) }
+
+        div."activity-stats" {
+            article {
+                small { (t!("activity.current_streak", locale = locale)) }
+                p { (streak_badge(summary, locale)) }
+            }
+            article {
+                small { (t!("activity.longest_streak", locale = locale)) }
+                p { strong { (summary.longest_streak) (t!("activity.days_suffix", locale = locale)) } }
+            }
+            article {
+                small { (t!("activity.total_answered", locale = locale)) }
+                p { strong { (total) } }
+            }
+        }
+
+        article {
+            h4 { (t!("activity.heatmap_title", locale = locale)) }
+            (heatmap(&summary.days, locale))
+            p style="color: var(--color-muted); font-size: 0.8rem; margin-bottom: 0;" {
+                (t!("activity.utc_note", locale = locale))
+            }
+        }
+
+        article id="daily-goal" {
+            h4 { (t!("activity.goal_title", locale = locale)) }
+            (goal_bar(summary, locale))
+            form hx-post=(names::DAILY_GOAL_URL) hx-target="main" hx-swap="innerHTML" {
+                div."goal-form" {
+                    select name="kind" aria-label=(t!("activity.goal_kind", locale = locale)) {
+                        option value=(names::NO_DAILY_GOAL) selected[goal_kind == names::NO_DAILY_GOAL] { (t!("activity.no_goal", locale = locale)) }
+                        @for kind in names::DAILY_GOAL_KINDS {
+                            option value=(kind) selected[goal_kind == *kind] { (goal_unit(kind, locale)) }
+                        }
+                    }
+                    input type="number" name="target" min="1" max=(names::MAX_DAILY_GOAL)
+                          value=[summary.goal.as_ref().map(|g| g.target)]
+                          aria-label=(t!("activity.goal_target", locale = locale));
+                    button type="submit" style="width: fit-content;" { (t!("activity.save_goal", locale = locale)) }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use maud::{html, Markup};
+use rust_i18n::t;
+
+use super::selection_mode_label;
+use crate::db::{AssignmentLearner, AssignmentModel, AssignmentStatus, GroupSummary, Quiz};
+use crate::names;
+
+/// An assignment as one of its learners sees it.
+pub struct LearnerAssignmentRow {
+    pub assignment: AssignmentModel,
+    pub best_score: Option<i32>,
+    pub completed_on: Option<String>,
+    pub status: AssignmentStatus,
+}
+
+/// An assignment as its instructor sees it in the list.
+pub struct CreatedAssignmentRow {
+    pub assignment: AssignmentModel,
+    pub learners: usize,
+    pub completed: usize,
+    pub overdue: usize,
+}
+
+pub struct AssignmentsPageData {
+    pub to_do: Vec<LearnerAssignmentRow>,
+    pub completed: Vec<LearnerAssignmentRow>,
+    pub created: Vec<CreatedAssignmentRow>,
+    /// Quizzes of the user's library, to assign
+    pub library: Vec<Quiz>,
+    /// Groups the user leads
+    pub groups: Vec<GroupSummary>,
+    pub min_questions: i32,
+    pub max_questions: i32,
+    pub error: Option<String>,
+}
+
+pub fn status_badge(status: AssignmentStatus, locale: &str) -> Markup {
+    let (class, label) = match status {
+        AssignmentStatus::Completed { late: false } => (
+            "badge-complete",
+            t!("assignment.status_completed", locale = locale),
+        ),
+        AssignmentStatus::Completed { late: true } => {
+            ("badge-late", t!("assignment.status_late", locale = locale))
+        }
+        AssignmentStatus::Overdue => (
+            "badge-overdue",
+            t!("assignment.status_overdue", locale = locale),
+        ),
+        AssignmentStatus::Pending => (
+            "badge-progress",
+            t!("assignment.status_pending", locale = locale),
+        ),
+    };
+    html! {
+        span class=(format!("badge-status {class}")) { (label) }
+    }
+}
+
+fn due_label(assignment: &AssignmentModel, locale: &str) -> Markup {
+    html! {
+        (assignment.due_date)
+        br;
+        small style="color: var(--color-muted);" {
+            @if assignment.days_left > 0 {
+                (t!("assignment.days_left", locale = locale, days = assignment.days_left))
+            } @else if assignment.days_left == 0 {
+                (t!("assignment.due_today", locale = locale))
+            } @else {
+                (t!("assignment.days_overdue", locale = locale, days = -assignment.days_left))
+            }
+        }
+    }
+}
+
+fn requirement(assignment: &AssignmentModel, locale: &str) -> Markup {
+    html! {
+        (t!("assignment.requirement", locale = locale,
+            count = assignment.question_count,
+            target = assignment.target_score))
+        br;
+        small style="color: var(--color-muted);" {
+            (selection_mode_label(&assignment.selection_mode, locale))
+        }
+    }
+}
+
+fn quiz_link(assignment: &AssignmentModel) -> Markup {
+    html! {
+        a hx-get=(names::quiz_dashboard_url(&assignment.quiz_public_id))
+          hx-push-url="true"
+          hx-target="main"
+          href=(names::quiz_dashboard_url(&assignment.quiz_public_id)) { (assignment.quiz_name) }
+    }
+}
+
+fn assignee_label(assignment: &AssignmentModel) -> &str {
+    assignment
+        .group_name
+        .as_deref()
+        .or(assignment.assignee_name.as_deref())
+        .unwrap_or("-")
+}
+
+fn learner_table(rows: &[LearnerAssignmentRow], locale: &str) -> Markup {
+    html! {
+        div."compare-table" {
+            table {
+                thead { tr {
+                    th { (t!("assignment.quiz", locale = locale)) }
+                    th { (t!("assignment.assigned_by", locale = locale)) }
+                    th { (t!("assignment.due_date", locale = locale)) }
+                    th { (t!("assignment.required", locale = locale)) }
+                    th { (t!("assignment.best_score", locale = locale)) }
+                    th { (t!("assignment.status", locale = locale)) }
+                    th {}
+                } }
+                tbody {
+                    @for row in rows {
+                        tr {
+                            td { (quiz_link(&row.assignment)) }
+                            td {
+                                (row.assignment.creator_name)
+                                @if let Some(group) = &row.assignment.group_name {
+                                    br;
+                                    small style="color: var(--color-muted);" { (group) }
+                                }
+                            }
+                            td { (due_label(&row.assignment, locale)) }
+                            td { (requirement(&row.assignment, locale)) }
+                            td {
+                                @if let Some(score) = row.best_score {
+                                    (score) "%"
+                                } @else {
+                                    "—"
+                                }
+                            }
+                            td {
+                                (status_badge(row.status, locale))
+                                @if let Some(day) = &row.completed_on {
+                                    br;
+                                    small style="color: var(--color-muted);" { (day) }
+                                }
+                            }
+                            td {
+                                @if !matches!(row.status, AssignmentStatus::Completed { .. }) {
+                                    button hx-post=(names::start_assignment_url(&row.assignment.public_id))
+                                           hx-target="main"
+                                           hx-swap="innerHTML"
+                                           style="width: fit-content; margin-bottom: 0;" {
+                                        (t!("assignment.start", locale = locale))
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn assignments_page(data: AssignmentsPageData, locale: &str) -> Markup {
+    html! {
+        h1 { (t!("assignment.title", locale = locale)) }
+
+        article {
+            h4 { (t!("assignment.to_do", locale = locale)) }
+            @if data.to_do.is_empty() {
+                p style="margin-bottom: 0;" { (t!("assignment.nothing_to_do", locale = locale)) }
+            } @else {
+                (learner_table(&data.to_do, locale))
+            }
+        }
+
+        @if !data.completed.is_empty() {
+            article {
+                h4 { (t!("assignment.completed", locale = locale)) }
+                (learner_table(&data.completed, locale))
+            }
+        }
+
+        h2 { (t!("assignment.given_title", locale = locale)) }
+
+        article id="new-assignment" {
+            h4 { (t!("assignment.new", locale = locale)) }
+            @if let Some(error) = &data.error {
+                p."assignment-error" { (error) }
+            }
+            @if data.library.is_empty() {
+                p style="margin-bottom: 0;" { (t!("assignment.empty_library", locale = locale)) }
+            } @else {
+                form hx-post=(names::ASSIGNMENTS_URL) hx-target="main" hx-swap="innerHTML" {
+                    label {
+                        (t!("assignment.quiz", locale = locale))
+                        select name="quiz_id" required="true" {
+                            @for quiz in &data.library {
+                                option value=(quiz.public_id) { (quiz.name) }
+                            }
+                        }
+                    }
+                    div."grid" {
+                        label {
+                            (t!("assignment.group", locale = locale))
+                            select name="group" {
+                                option value="" { (t!("assignment.single_learner", locale = locale)) }
+                                @for group in data.groups.iter().filter(|g| g.is_owner) {
+                                    option value=(group.public_id) { (group.name) }
+                                }
+                            }
+                        }
+                        label {
+                            (t!("assignment.learner_email", locale = locale))
+                            input type="email" name="email"
+                                  placeholder=(t!("assignment.learner_email_hint", locale = locale));
+                        }
+                    }
+                    div."grid" {
+                        label {
+                            (t!("assignment.due_date", locale = locale))
+                            input type="date" name="due_date" required="true";
+                        }
+                        label {
+                            (t!("assignment.question_count", locale = locale))
+                            input type="number" name="question_count" required="true"
+                                  min=(data.min_questions) max=(data.max_questions)
+                                  value=(names::DEFAULT_QUESTION_COUNT.clamp(data.min_questions, data.max_questions));
+                        }
+                        label {
+                            (t!("assignment.selection_mode", locale = locale))
+                            select name="selection_mode" {
+                                @for mode in names::SELECTION_MODES {
+                                    option value=(mode) selected[*mode == "random"] {
+                                        (selection_mode_label(mode, locale))
+                                    }
+                                }
+                            }
+                        }
+                        label {
+                            (t!("assignment.target_score", locale = locale))
+                            input type="number" name="target_score" required="true" min="1" max="100"
+                                  value=(names::DEFAULT_PASS_THRESHOLD);
+                        }
+                    }
+                    button type="submit" style="width: fit-content;" { (t!("assignment.assign", locale = locale)) }
+                }
+            }
+        }
+
+        @if !data.created.is_empty() {
+            article {
+                div."compare-table" {
+                    table {
+                        thead { tr {
+                            th { (t!("assignment.quiz", locale = locale)) }
+                            th { (t!("assignment.assigned_to", locale = locale)) }
+                            th { (t!("assignment.due_date", locale = locale)) }
+                            th { (t!("assignment.required", locale = locale)) }
+                            th { (t!("assignment.completed", locale = locale)) }
+                            th { (t!("assignment.status_overdue", locale = locale)) }
+                        } }
+                        tbody {
+                            @for row in &data.created {
+                                tr {
+                                    td {
+                                        a hx-get=(names::assignment_url(&row.assignment.public_id))
+                                          hx-push-url="true"
+                                          hx-target="main"
+                                          href=(names::assignment_url(&row.assignment.public_id)) {
+                                            (row.assignment.quiz_name)
+                                        }
+                                    }
+                                    td { (assignee_label(&row.assignment)) }
+                                    td { (due_label(&row.assignment, locale)) }
+                                    td { (requirement(&row.assignment, locale)) }
+                                    td { (row.completed) " / " (row.learners) }
+                                    td {
+                                        @if row.overdue > 0 {
+                                            span."badge-status badge-overdue" { (row.overdue) }
+                                        } @else {
+                                            "0"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Completion of every learner of an assignment, for its instructor.
+pub fn assignment_page(
+    assignment: &AssignmentModel,
+    learners: &[(AssignmentLearner, AssignmentStatus)],
+    locale: &str,
+) -> Markup {
+    html! {
+        h1 { (assignment.quiz_name) }
+        div style="margin-bottom: 1rem;" {
+            button hx-get=(names::ASSIGNMENTS_URL)
+                   hx-push-url="true"
+                   hx-target="main"
+                   style="width: fit-content;" {
+                (t!("assignment.back", locale = locale))
+            }
+        }
+        article {
+            p {
+                (t!("assignment.assigned_to", locale = locale)) ": "
+                strong { (assignee_label(assignment)) }
+            }
+            p { (t!("assignment.due_date", locale = locale)) ": " (due_label(assignment, locale)) }
+            p style="margin-bottom: 0;" { (requirement(assignment, locale)) }
+        }
+        article {
+            @if learners.is_empty() {
+                p style="margin-bottom: 0;" { (t!("group.no_members", locale = locale)) }
+            } @else {
+                div."compare-table" {
+                    table {
+                        thead { tr {
+                            th { (t!("group.member", locale = locale)) }
+                            th { (t!("assignment.attempts", locale = locale)) }
+                            th { (t!("assignment.best_score", locale = locale)) }
+                            th { (t!("assignment.status", locale = locale)) }
+                            th { (t!("assignment.completed_on", locale = locale)) }
+                        } }
+                        tbody {
+                            @for (learner, status) in learners {
+                                tr {
+                                    td { (learner.display_name) }
+                                    td { (learner.attempts) }
+                                    td {
+                                        @if let Some(score) = learner.best_score {
+                                            (score) "%"
+                                        } @else {
+                                            "—"
+                                        }
+                                    }
+                                    td { (status_badge(*status, locale)) }
+                                    td { (learner.completed_on.as_deref().unwrap_or("—")) }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        button."secondary outline"
+               hx-delete=(names::assignment_url(&assignment.public_id))
+               hx-target="main"
+               hx-confirm=(t!("assignment.delete_confirm", locale = locale))
+               style="width: fit-content;" {
+            (t!("assignment.delete", locale = locale))
+        }
+    }
+}
+
+pub fn assignment_not_available(locale: &str) -> Markup {
+    html! {
+        h1 { (t!("assignment.not_found_title", locale = locale)) }
+        p { (t!("assignment.not_found_desc", locale = locale)) }
+        button hx-get=(names::ASSIGNMENTS_URL)
+               hx-push-url="true"
+               hx-target="main"
+               style="width: fit-content;" {
+            (t!("assignment.back", locale = locale))
+        }
+    }
+}
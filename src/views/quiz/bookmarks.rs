@@ -0,0 +1,50 @@
+use crate::{db::BookmarkedQuestion, names, views::components};
+use maud::{html, Markup};
+use rust_i18n::t;
+
+pub fn bookmarks_page(bookmarks: &[BookmarkedQuestion], locale: &str) -> Markup {
+    html! {
+        h1 { (t!("bookmarks.title", locale = locale)) }
+
+        @if bookmarks.is_empty() {
+            p { (t!("bookmarks.empty", locale = locale)) }
+        }
+
+        @for quiz in bookmarks.chunk_by(|a, b| a.quiz_id == b.quiz_id) {
+            @let first = &quiz[0];
+            article {
+                header style="display: flex; align-items: center; gap: 1rem; flex-wrap: wrap;" {
+                    h4 style="margin: 0;" {
+                        a hx-get=(names::quiz_dashboard_url(&first.quiz_public_id))
+                          hx-push-url="true"
+                          hx-target="main"
+                          href=(names::quiz_dashboard_url(&first.quiz_public_id)) {
+                            (first.quiz_name)
+                        }
+                    }
+                    button hx-post=(names::start_bookmarks_session_url(&first.quiz_public_id))
+                           hx-target="main"
+                           style="width: fit-content; margin-left: auto;" {
+                        (t!("bookmarks.start_session_1", locale = locale))
+                        (quiz.len())
+                        (t!("bookmarks.start_session_2", locale = locale))
+                    }
+                }
+                @for q in quiz {
+                    div."bookmark-item" {
+                        p style="margin-bottom: 0.25rem;" {
+                            span."material-symbols-rounded" style="font-size: 1rem; vertical-align: middle; color: var(--color-warning);" { "bookmark" }
+                            " " (q.question)
+                        }
+                        @if let Some(category) = &q.category {
+                            small style="color: var(--color-muted);" { (category) }
+                        }
+                        @if let Some(note) = &q.note {
+                            div."note-body" { (components::markdown(note)) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{db::ChallengeStanding, names};
+use maud::{html, Markup};
+use rust_i18n::t;
+
+pub struct ChallengePageData {
+    pub challenge_id: String,
+    pub quiz_name: String,
+    pub creator_name: String,
+    pub questions_count: usize,
+    pub standings: Vec<ChallengeStanding>,
+    pub user_id: i32,
+    pub can_join: bool,
+}
+
+pub fn challenge_page(data: ChallengePageData, locale: &str) -> Markup {
+    let own_session = data.standings.iter().find(|s| s.user_id == data.user_id);
+
+    html! {
+        h1 { (t!("challenge.title", locale = locale)) }
+        article {
+            p {
+                (t!("challenge.challenged_by", locale = locale))
+                strong { (data.creator_name) }
+            }
+            p {
+                mark { (data.quiz_name) }
+                " · "
+                (data.questions_count)
+                (t!("share.questions_suffix", locale = locale))
+            }
+            @if let Some(own) = own_session {
+                button hx-get=(names::results_url(own.session_id))
+                       hx-push-url="true"
+                       hx-target="main"
+                       style="width: fit-content;" {
+                    (t!("challenge.view_results", locale = locale))
+                }
+                (challenge_link(&data.challenge_id, locale))
+            } @else if data.can_join {
+                p style="color: var(--color-muted);" { (t!("challenge.accept_desc", locale = locale)) }
+                button hx-post=(names::accept_challenge_url(&data.challenge_id))
+                       hx-target="main"
+                       hx-swap="innerHTML"
+                       style="width: fit-content; background: var(--btn-gradient); color: white; border: none; font-weight: 500;" {
+                    (t!("challenge.accept", locale = locale))
+                }
+            } @else {
+                p { (t!("challenge.not_joinable", locale = locale)) }
+            }
+        }
+        article {
+            h4 { (t!("challenge.standings", locale = locale)) }
+            (challenge_standings(&data.standings, own_session.map(|s| s.session_id), locale))
+        }
+    }
+}
+
+/// Head-to-head table: every participant's first-attempt score on the same questions.
+pub fn challenge_standings(
+    standings: &[ChallengeStanding],
+    own_session_id: Option<i32>,
+    locale: &str,
+) -> Markup {
+    html! {
+        table."challenge-standings" {
+            thead { tr {
+                th { "#" }
+                th { (t!("challenge.player", locale = locale)) }
+                th { (t!("result.correct_total", locale = locale)) }
+                th { (t!("challenge.status", locale = locale)) }
+            } }
+            tbody {
+                @for (rank, standing) in standings.iter().enumerate() {
+                    tr class=[(Some(standing.session_id) == own_session_id).then_some("challenge-self")] {
+                        td { (rank + 1) }
+                        td {
+                            (standing.display_name)
+                            @if standing.is_creator {
+                                " "
+                                small."challenge-creator" { (t!("challenge.creator", locale = locale)) }
+                            }
+                        }
+                        td { (standing.correct) " / " (standing.total) }
+                        td {
+                            @if standing.is_complete {
+                                (t!("challenge.finished", locale = locale))
+                            } @else {
+                                (t!("challenge.in_progress_1", locale = locale))
+                                (standing.answered)
+                                (t!("challenge.in_progress_2", locale = locale))
+                                (standing.total)
+                                ")"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Copyable link to a challenge, swapped into the results page once created.
+pub fn challenge_link(challenge_id: &str, locale: &str) -> Markup {
+    html! {
+        div."challenge-link" {
+            small style="color: var(--color-muted);" { (t!("challenge.link_desc", locale = locale)) }
+            div style="display: flex; gap: 0.5rem; align-items: center;" {
+                input type="text" id="challenge-url" readonly
+                      value=(names::challenge_url(challenge_id))
+                      style="margin-bottom: 0;";
+                button type="button" class="secondary" data-copy-url="challenge-url"
+                       style="width: fit-content; margin-bottom: 0;" {
+                    span."material-symbols-rounded" style="font-size: 1rem; vertical-align: middle;" { "content_copy" }
+                    " " (t!("challenge.copy", locale = locale))
+                }
+            }
+        }
+    }
+}
+
+pub fn challenge_not_available(locale: &str) -> Markup {
+    html! {
+        h1 { (t!("challenge.not_found_title", locale = locale)) }
+        p { (t!("challenge.not_found_desc", locale = locale)) }
+        button hx-get="/"
+               hx-push-url="true"
+               hx-target="main"
+               style="width: fit-content;" {
+            (t!("share.back_to_home", locale = locale))
+        }
+    }
+}
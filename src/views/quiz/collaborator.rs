@@ -0,0 +1,228 @@
+use maud::{html, Markup};
+use rust_i18n::t;
+
+use crate::db::{QuizAccess, QuizChange, QuizCollaborator, QuizInvitation, ReceivedInvitation};
+use crate::names;
+
+pub struct CollaboratorsPageData {
+    pub quiz_name: String,
+    pub quiz_id: String,
+    pub user_id: i32,
+    /// The viewing user's access
+    pub access: QuizAccess,
+    pub collaborators: Vec<QuizCollaborator>,
+    /// Pending invitations, for the owner only
+    pub invitations: Vec<QuizInvitation>,
+    pub changes: Vec<QuizChange>,
+    pub error: Option<String>,
+}
+
+fn role_label(role: &str, locale: &str) -> String {
+    match role {
+        "editor" => t!("collaborator.role_editor", locale = locale).to_string(),
+        "sharing" => t!("collaborator.role_sharing", locale = locale).to_string(),
+        _ => role.to_string(),
+    }
+}
+
+fn change_label(change: &QuizChange, locale: &str) -> String {
+    let detail = change.detail.as_str();
+    match change.action.as_str() {
+        "rename" => t!(
+            "collaborator.change_rename",
+            locale = locale,
+            detail = detail
+        ),
+        "session_sizes" => t!(
+            "collaborator.change_session_sizes",
+            locale = locale,
+            detail = detail
+        ),
+        "share" => t!("collaborator.change_share", locale = locale),
+        "unshare" => t!("collaborator.change_unshare", locale = locale),
+        "collaborator_editor" => t!(
+            "collaborator.change_editor",
+            locale = locale,
+            detail = detail
+        ),
+        "collaborator_sharing" => t!(
+            "collaborator.change_sharing",
+            locale = locale,
+            detail = detail
+        ),
+        "collaborator_removed" => t!(
+            "collaborator.change_removed",
+            locale = locale,
+            detail = detail
+        ),
+        other => format!("{other} {detail}").into(),
+    }
+    .to_string()
+}
+
+pub fn collaborators_page(data: CollaboratorsPageData, locale: &str) -> Markup {
+    let is_owner = data.access == QuizAccess::Owner;
+    html! {
+        h1 { (data.quiz_name) }
+        div style="margin-bottom: 1rem;" {
+            button hx-get=(names::quiz_dashboard_url(&data.quiz_id))
+                   hx-push-url="true"
+                   hx-target="main"
+                   style="width: fit-content;" {
+                (t!("dashboard.back_to_dashboard", locale = locale))
+            }
+        }
+        h4 { (t!("collaborator.title", locale = locale)) }
+        p style="color: var(--color-muted); font-size: 0.9rem;" {
+            (t!("collaborator.desc", locale = locale))
+        }
+
+        @if is_owner {
+            article."article-narrow" {
+                @if let Some(error) = &data.error {
+                    p."collaborator-error" { (error) }
+                }
+                form hx-post=(names::quiz_collaborators_url(&data.quiz_id))
+                     hx-target="main" hx-swap="innerHTML" {
+                    div."grid" {
+                        label {
+                            (t!("collaborator.email", locale = locale))
+                            input type="email" name="email" required="true";
+                        }
+                        label {
+                            (t!("collaborator.role", locale = locale))
+                            select name="role" {
+                                @for role in names::COLLABORATOR_ROLES {
+                                    option value=(role) { (role_label(role, locale)) }
+                                }
+                            }
+                        }
+                    }
+                    small style="display: block; color: var(--color-muted); margin-bottom: 0.5rem;" {
+                        (t!("collaborator.invite_hint", locale = locale))
+                    }
+                    button type="submit" style="width: fit-content;" {
+                        (t!("collaborator.invite", locale = locale))
+                    }
+                }
+                @if !data.invitations.is_empty() {
+                    h5 { (t!("collaborator.pending", locale = locale)) }
+                    ul."group-members" {
+                        @for invitation in &data.invitations {
+                            li {
+                                (invitation.email)
+                                " "
+                                span."badge-status badge-progress" { (role_label(&invitation.role, locale)) }
+                                " "
+                                button."secondary outline"
+                                       hx-delete=(names::quiz_invitation_url(&data.quiz_id, invitation.id))
+                                       hx-target="main"
+                                       aria-label=(t!("collaborator.cancel_invitation", locale = locale)) {
+                                    span."material-symbols-rounded" { "close" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        article {
+            @if data.collaborators.is_empty() {
+                p style="margin-bottom: 0;" { (t!("collaborator.empty", locale = locale)) }
+            } @else {
+                ul."group-members" {
+                    @for collaborator in &data.collaborators {
+                        li {
+                            (collaborator.display_name)
+                            " "
+                            span."badge-status badge-progress" { (role_label(&collaborator.role, locale)) }
+                            @if is_owner {
+                                " "
+                                button."secondary outline"
+                                       hx-delete=(names::quiz_collaborator_url(&data.quiz_id, collaborator.user_id))
+                                       hx-target="main"
+                                       hx-confirm=(t!("collaborator.revoke_confirm", locale = locale, name = collaborator.display_name))
+                                       aria-label=(t!("collaborator.revoke", locale = locale)) {
+                                    span."material-symbols-rounded" { "person_remove" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        h4 { (t!("collaborator.history", locale = locale)) }
+        article {
+            @if data.changes.is_empty() {
+                p style="margin-bottom: 0;" { (t!("collaborator.no_changes", locale = locale)) }
+            } @else {
+                div."compare-table" {
+                    table {
+                        thead { tr {
+                            th { (t!("collaborator.when", locale = locale)) }
+                            th { (t!("collaborator.who", locale = locale)) }
+                            th { (t!("collaborator.what", locale = locale)) }
+                        } }
+                        tbody {
+                            @for change in &data.changes {
+                                tr {
+                                    td { (change.changed_at) }
+                                    td { (change.display_name.as_deref().unwrap_or("—")) }
+                                    td { (change_label(change, locale)) }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        @if !is_owner {
+            button."secondary outline"
+                   hx-delete=(names::quiz_collaborator_url(&data.quiz_id, data.user_id))
+                   hx-target="main"
+                   hx-confirm=(t!("collaborator.leave_confirm", locale = locale))
+                   style="width: fit-content;" {
+                (t!("collaborator.leave", locale = locale))
+            }
+        }
+    }
+}
+
+/// Invitations to collaborate, above the invitee's quiz list. Empty when there are none.
+pub fn received_invitations(invitations: &[ReceivedInvitation], locale: &str) -> Markup {
+    html! {
+        @if !invitations.is_empty() {
+            article id="invitations" {
+                h4 { (t!("collaborator.invitations_title", locale = locale)) }
+                ul."group-members" {
+                    @for invitation in invitations {
+                        li {
+                            (t!("collaborator.invitation",
+                                locale = locale,
+                                name = invitation.invited_by,
+                                quiz = invitation.quiz_name,
+                                role = role_label(&invitation.role, locale)))
+                            " "
+                            button hx-post=(names::accept_invitation_url(invitation.id))
+                                   hx-target="main"
+                                   style="width: fit-content; margin-bottom: 0;" {
+                                (t!("collaborator.accept", locale = locale))
+                            }
+                            " "
+                            button."secondary outline"
+                                   hx-post=(names::decline_invitation_url(invitation.id))
+                                   hx-target="#invitations"
+                                   hx-swap="outerHTML"
+                                   style="width: fit-content; margin-bottom: 0;" {
+                                (t!("collaborator.decline", locale = locale))
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
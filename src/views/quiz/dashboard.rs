@@ -1,8 +1,11 @@
-use super::selection_mode_label;
+use super::{challenge_standings, confidence_label, selection_mode_label};
 use crate::{
+    analytics,
     db::{
-        AnswerModel, CategoryStats, DailyAccuracy, QuizCategoryOverallStats, QuizOverallStats,
-        SessionReportModel,
+        AnswerModel, CategoryStats, ChallengeStanding, ConfidenceStats, ConfidentWrongQuestion,
+        DailyAccuracy, DistractorQuestion, ItemAnalysis, PaceReport, QuizAccess,
+        QuizCategoryOverallStats, QuizOverallStats, Readiness, SessionComparison,
+        SessionReportModel, StudyPlan,
     },
     names,
 };
@@ -17,6 +20,10 @@ pub struct DashboardData {
     pub cat_stats: Vec<QuizCategoryOverallStats>,
     pub daily_accuracy: Vec<DailyAccuracy>,
     pub study_time_ms: i64,
+    /// Author access, which opens the reports and collaborators pages
+    pub access: Option<QuizAccess>,
+    pub readiness: Option<Readiness>,
+    pub study_plan: Option<StudyPlan>,
 }
 
 pub struct SessionHistoryData {
@@ -25,6 +32,37 @@ pub struct SessionHistoryData {
     pub sessions: Vec<SessionReportModel>,
 }
 
+pub struct SessionComparisonData {
+    pub quiz_name: String,
+    pub quiz_id: String,
+    pub comparison: SessionComparison,
+}
+
+pub struct CalibrationData {
+    pub quiz_name: String,
+    pub quiz_id: String,
+    pub confidence_stats: Vec<ConfidenceStats>,
+    pub confident_wrong: Vec<ConfidentWrongQuestion>,
+}
+
+pub struct PaceData {
+    pub quiz_name: String,
+    pub quiz_id: String,
+    pub report: PaceReport,
+}
+
+pub struct ItemAnalysisData {
+    pub quiz_name: String,
+    pub quiz_id: String,
+    pub items: Vec<ItemAnalysis>,
+}
+
+pub struct DistractorAnalysisData {
+    pub quiz_name: String,
+    pub quiz_id: String,
+    pub questions: Vec<DistractorQuestion>,
+}
+
 pub struct SessionResultData {
     pub session_name: String,
     pub session_id: i32,
@@ -33,11 +71,171 @@ pub struct SessionResultData {
     pub selection_mode: String,
     pub questions_count: i32,
     pub answered_count: i32,
+    pub current_idx: i32,
     pub is_complete: bool,
     pub correct_answers: i32,
     pub answers: Vec<AnswerModel>,
     pub category_stats: Vec<CategoryStats>,
     pub study_time_ms: i64,
+    pub idle_time_ms: i64,
+    pub challenge_id: Option<String>,
+    pub challenge_standings: Vec<ChallengeStanding>,
+}
+
+fn percent(share: f64) -> String {
+    format!("{:.0}%", share * 100.0)
+}
+
+fn readiness_panel(readiness: Option<&Readiness>, locale: &str) -> Markup {
+    html! {
+        article."readiness" {
+            h4 { (t!("readiness.title", locale = locale)) }
+            @if let Some(r) = readiness {
+                @let pass_mark = r.pass_mark;
+                @let (verdict_class, verdict) = if r.low >= pass_mark {
+                    ("readiness-ready", t!("readiness.ready", locale = locale))
+                } else if r.high >= pass_mark {
+                    ("readiness-borderline", t!("readiness.borderline", locale = locale))
+                } else {
+                    ("readiness-not-ready", t!("readiness.not_ready", locale = locale))
+                };
+                div."readiness-summary" {
+                    strong."readiness-score" { (percent(r.score)) }
+                    div {
+                        span class=(verdict_class) { (verdict) }
+                        div style="color: var(--color-muted); font-size: 0.9rem;" {
+                            (t!("readiness.band", locale = locale, low = percent(r.low), high = percent(r.high)))
+                        }
+                    }
+                }
+                div."readiness-meter" role="img"
+                    aria-label=(t!("readiness.band", locale = locale, low = percent(r.low), high = percent(r.high))) {
+                    div."readiness-band" style=(format!("left: {:.1}%; width: {:.1}%;", r.low * 100.0, (r.high - r.low) * 100.0)) {}
+                    div."readiness-point" style=(format!("left: {:.1}%;", r.score * 100.0)) {}
+                    div."readiness-pass" style=(format!("left: {:.1}%;", pass_mark * 100.0))
+                        title=(t!("readiness.pass_mark", locale = locale, mark = percent(pass_mark))) {}
+                }
+                small style="color: var(--color-muted);" {
+                    (t!("readiness.pass_mark", locale = locale, mark = percent(pass_mark)))
+                }
+                table style="margin-top: 1rem;" {
+                    tbody {
+                        tr {
+                            td { (t!("readiness.coverage", locale = locale)) }
+                            td { strong { (percent(r.coverage)) } }
+                        }
+                        tr {
+                            td { (t!("readiness.recent_accuracy", locale = locale)) }
+                            td { strong { (percent(r.recent_accuracy)) } }
+                        }
+                        tr {
+                            td { (t!("readiness.last_practice", locale = locale)) }
+                            td { strong {
+                                @if r.days_since_practice == 0 {
+                                    (t!("readiness.today", locale = locale))
+                                } @else {
+                                    (t!("readiness.days_ago", locale = locale, days = r.days_since_practice))
+                                }
+                            } }
+                        }
+                    }
+                }
+                @if !r.holding_back.is_empty() {
+                    h6 { (t!("readiness.holding_back", locale = locale)) }
+                    ul."readiness-weak" {
+                        @for weak in &r.holding_back {
+                            li {
+                                strong { (weak.category) }
+                                " — "
+                                (t!("readiness.category_detail", locale = locale,
+                                    estimate = percent(weak.estimate), coverage = percent(weak.coverage)))
+                            }
+                        }
+                    }
+                }
+                p style="color: var(--color-muted); font-size: 0.85rem; margin-bottom: 0;" {
+                    (t!("readiness.note", locale = locale))
+                }
+            } @else {
+                p style="margin-bottom: 0;" { (t!("readiness.empty", locale = locale)) }
+            }
+        }
+    }
+}
+
+fn study_plan_panel(quiz_id: &str, plan: Option<&StudyPlan>, locale: &str) -> Markup {
+    html! {
+        article id="study-plan" {
+            h4 { (t!("plan.title", locale = locale)) }
+            @if let Some(plan) = plan {
+                p {
+                    @if plan.days_left == 0 {
+                        strong { (t!("plan.exam_today", locale = locale)) }
+                    } @else {
+                        strong { (t!("plan.days_left", locale = locale, days = plan.days_left)) }
+                    }
+                    span style="color: var(--color-muted);" {
+                        " — " (plan.exam_date) " · "
+                        (t!("readiness.pass_mark", locale = locale, mark = format!("{}%", plan.pass_threshold)))
+                    }
+                }
+                @if plan.behind {
+                    p."plan-behind" role="alert" {
+                        span."material-symbols-rounded" { "warning" }
+                        (t!("plan.behind", locale = locale,
+                            done = plan.answered_since_start, expected = plan.expected_by_now, daily = plan.daily_target))
+                    }
+                }
+                h6 { (t!("plan.today_title", locale = locale, count = plan.daily_target)) }
+                @let pct = (plan.answered_today * 100 / i64::from(plan.daily_target.max(1))).min(100);
+                div."quiz-progress-bar" {
+                    div."quiz-progress-fill" style=(format!("width: {pct}%;")) {}
+                }
+                small style="color: var(--color-muted);" {
+                    (t!("plan.today_progress", locale = locale, done = plan.answered_today, target = plan.daily_target))
+                }
+                ul."plan-mix" {
+                    @for (mode, count) in [("unanswered", plan.unseen_per_day), ("incorrect", plan.review_per_day), ("random", plan.mixed_per_day)] {
+                        @if count > 0 {
+                            li { strong { (count) } " × " (selection_mode_label(mode, locale)) }
+                        }
+                    }
+                }
+                @if !plan.focus.is_empty() {
+                    p style="margin-bottom: 0.5rem;" {
+                        (t!("plan.focus", locale = locale)) " "
+                        strong { (plan.focus.join(", ")) }
+                    }
+                }
+            } @else {
+                p style="color: var(--color-muted);" { (t!("plan.empty", locale = locale)) }
+            }
+            form hx-post=(names::quiz_study_plan_url(quiz_id)) hx-target="main" hx-swap="innerHTML" {
+                div."goal-form" {
+                    label {
+                        (t!("plan.exam_date", locale = locale)) " "
+                        input type="date" name="exam_date" required
+                              value=[plan.map(|p| p.exam_date.as_str())];
+                    }
+                    label {
+                        (t!("plan.pass_threshold", locale = locale)) " "
+                        input type="number" name="pass_threshold" min="1" max="100"
+                              value=(plan.map_or(names::DEFAULT_PASS_THRESHOLD, |p| p.pass_threshold));
+                        " %"
+                    }
+                    button type="submit" style="width: fit-content;" { (t!("plan.save", locale = locale)) }
+                    @if plan.is_some() {
+                        button type="button" class="secondary outline" style="width: fit-content;"
+                               hx-post=(names::quiz_study_plan_url(quiz_id))
+                               hx-vals=r#"{"exam_date": ""}"#
+                               hx-target="main" hx-swap="innerHTML" {
+                            (t!("plan.clear", locale = locale))
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 pub fn dashboard(data: DashboardData, locale: &str) -> Markup {
@@ -67,8 +265,43 @@ pub fn dashboard(data: DashboardData, locale: &str) -> Markup {
                    style="width: fit-content;" {
                 (t!("dashboard.open_session_history", locale = locale))
             }
+            button hx-get=(names::quiz_calibration_url(&data.quiz_id))
+                   hx-push-url="true"
+                   hx-target="main"
+                   style="width: fit-content;" {
+                (t!("dashboard.open_calibration", locale = locale))
+            }
+            button hx-get=(names::quiz_pace_url(&data.quiz_id))
+                   hx-push-url="true"
+                   hx-target="main"
+                   style="width: fit-content;" {
+                (t!("dashboard.open_pace", locale = locale))
+            }
+            @if data.access.is_some() {
+                button hx-get=(names::quiz_item_analysis_url(&data.quiz_id))
+                       hx-push-url="true"
+                       hx-target="main"
+                       style="width: fit-content;" {
+                    (t!("dashboard.open_item_analysis", locale = locale))
+                }
+                button hx-get=(names::quiz_distractors_url(&data.quiz_id))
+                       hx-push-url="true"
+                       hx-target="main"
+                       style="width: fit-content;" {
+                    (t!("dashboard.open_distractors", locale = locale))
+                }
+                button hx-get=(names::quiz_collaborators_url(&data.quiz_id))
+                       hx-push-url="true"
+                       hx-target="main"
+                       style="width: fit-content;" {
+                    (t!("dashboard.open_collaborators", locale = locale))
+                }
+            }
         }
 
+        (readiness_panel(data.readiness.as_ref(), locale))
+        (study_plan_panel(&data.quiz_id, data.study_plan.as_ref(), locale))
+
         article {
             h4 { (t!("dashboard.overall_stats", locale = locale)) }
             div style="display:flex; align-items:center; gap:2rem; flex-wrap:wrap; justify-content:center;" {
@@ -201,6 +434,30 @@ pub fn dashboard(data: DashboardData, locale: &str) -> Markup {
             }
         }
 
+        article {
+            h4 { (t!("export.title", locale = locale)) }
+            p style="color: var(--color-muted); font-size: 0.9rem;" {
+                (t!("export.desc", locale = locale))
+            }
+            div."export-links" {
+                @for file in names::EXPORT_FILES {
+                    a href=(names::quiz_export_url(&data.quiz_id, file)) download role="button" class="secondary outline" {
+                        span."material-symbols-rounded" { "download" }
+                        (export_label(file, locale))
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn export_label(file: &str, locale: &str) -> String {
+    match file {
+        "answers.csv" => t!("export.answers_csv", locale = locale).to_string(),
+        "answers.json" => t!("export.answers_json", locale = locale).to_string(),
+        "categories.csv" => t!("export.categories_csv", locale = locale).to_string(),
+        "categories.json" => t!("export.categories_json", locale = locale).to_string(),
+        _ => file.to_string(),
     }
 }
 
@@ -221,6 +478,20 @@ pub fn session_history(data: SessionHistoryData, locale: &str) -> Markup {
                 p { (t!("dashboard.no_sessions", locale = locale)) }
             } @else {
                 (session_history_table(&data.sessions, locale))
+                @if data.sessions.len() > 1 {
+                    form id="compare-form"
+                         hx-get=(names::quiz_compare_url(&data.quiz_id))
+                         hx-push-url="true"
+                         hx-target="main"
+                         style="margin-bottom: 0;" {
+                        button type="submit" class="secondary" style="width: fit-content;" {
+                            (t!("compare.compare_selected", locale = locale))
+                        }
+                        small style="margin-left: 0.75rem; color: var(--color-muted);" {
+                            (t!("compare.pick_hint", locale = locale, max = names::MAX_COMPARED_SESSIONS))
+                        }
+                    }
+                }
             }
         }
 
@@ -235,10 +506,584 @@ pub fn session_history(data: SessionHistoryData, locale: &str) -> Markup {
     }
 }
 
+fn outcome_mark(outcome: Option<bool>) -> Markup {
+    html! {
+        @match outcome {
+            Some(true) => span."material-symbols-rounded" style="color: var(--color-success);" { "check_circle" },
+            Some(false) => span."material-symbols-rounded" style="color: var(--color-danger);" { "cancel" },
+            None => span style="color: var(--color-muted);" { "–" },
+        }
+    }
+}
+
+/// Sessions of a quiz side by side: score, time, accuracy per category, and the questions
+/// whose outcome changed between them.
+pub fn session_comparison(data: SessionComparisonData, locale: &str) -> Markup {
+    let comparison = &data.comparison;
+    html! {
+        h1 { (data.quiz_name) }
+        div style="margin-bottom: 1rem;" {
+            button hx-get=(names::quiz_session_history_url(&data.quiz_id))
+                   hx-push-url="true"
+                   hx-target="main"
+                   style="width: fit-content;" {
+                (t!("compare.back_to_history", locale = locale))
+            }
+        }
+        @if comparison.sessions.len() < 2 {
+            article {
+                h4 { (t!("compare.title", locale = locale)) }
+                p style="margin-bottom: 0;" {
+                    (t!("compare.need_two", locale = locale, max = names::MAX_COMPARED_SESSIONS))
+                }
+            }
+        } @else {
+            article {
+                h4 { (t!("compare.title", locale = locale)) }
+                div."compare-table" {
+                    table {
+                        thead { tr {
+                            th {}
+                            @for s in &comparison.sessions {
+                                th {
+                                    a hx-get=(names::results_url(s.id))
+                                      hx-push-url="true"
+                                      hx-target="main"
+                                      href=(names::results_url(s.id)) { (s.name) }
+                                }
+                            }
+                        } }
+                        tbody {
+                            tr {
+                                td { (t!("dashboard.score", locale = locale)) }
+                                @for s in &comparison.sessions {
+                                    td {
+                                        strong { (s.score) "/" (s.answered_questions) }
+                                        @if s.answered_questions > 0 {
+                                            small style="color: var(--color-muted);" {
+                                                (format!(" ({:.0}%)", s.score as f64 * 100.0 / s.answered_questions as f64))
+                                            }
+                                        }
+                                        @if !s.is_complete {
+                                            br;
+                                            span."badge-status badge-progress" { (t!("dashboard.in_progress", locale = locale)) }
+                                        }
+                                    }
+                                }
+                            }
+                            tr {
+                                td { (t!("dashboard.study_time", locale = locale)) }
+                                @for s in &comparison.sessions {
+                                    td { (format_study_time(s.active_ms)) }
+                                }
+                            }
+                            tr {
+                                td { (t!("compare.time_per_question", locale = locale)) }
+                                @for s in &comparison.sessions {
+                                    td {
+                                        @if s.answered_questions > 0 {
+                                            (format_study_time(s.active_ms / i64::from(s.answered_questions)))
+                                        } @else {
+                                            "–"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            @if !comparison.categories.is_empty() {
+                article {
+                    h4 { (t!("compare.categories_title", locale = locale)) }
+                    div."compare-table" {
+                        table {
+                            thead { tr {
+                                th { (t!("dashboard.category", locale = locale)) }
+                                @for s in &comparison.sessions {
+                                    th { (s.name) }
+                                }
+                                th { (t!("compare.change", locale = locale)) }
+                            } }
+                            tbody {
+                                @for c in &comparison.categories {
+                                    @let known: Vec<f64> = c.accuracy.iter().flatten().copied().collect();
+                                    @let change = match (known.first(), known.last()) {
+                                        (Some(first), Some(last)) if known.len() > 1 => last - first,
+                                        _ => 0.0,
+                                    };
+                                    tr {
+                                        td { (c.category) }
+                                        @for accuracy in &c.accuracy {
+                                            td {
+                                                @match accuracy {
+                                                    Some(a) => (format!("{:.0}%", a * 100.0)),
+                                                    None => span style="color: var(--color-muted);" { "–" },
+                                                }
+                                            }
+                                        }
+                                        td {
+                                            @if change >= 0.005 {
+                                                span."compare-up" { (t!("compare.points", locale = locale, change = format!("+{:.0}", change * 100.0))) }
+                                            } @else if change <= -0.005 {
+                                                span."compare-down" { (t!("compare.points", locale = locale, change = format!("{:.0}", change * 100.0))) }
+                                            } @else {
+                                                span style="color: var(--color-muted);" { "±0" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            article {
+                h4 { (t!("compare.flipped_title", locale = locale)) }
+                @if comparison.flipped.is_empty() {
+                    p style="margin-bottom: 0;" { (t!("compare.no_flipped", locale = locale)) }
+                } @else {
+                    @let fixed = comparison.flipped.iter().filter(|q| !q.was_correct && q.now_correct).count();
+                    @let regressed = comparison.flipped.iter().filter(|q| q.was_correct && !q.now_correct).count();
+                    p style="color: var(--color-muted); font-size: 0.9rem;" {
+                        (t!("compare.flipped_summary", locale = locale, fixed = fixed, regressed = regressed))
+                    }
+                    div."compare-table" {
+                        table {
+                            thead { tr {
+                                th { (t!("result.question_col", locale = locale)) }
+                                @for s in &comparison.sessions {
+                                    th { (s.name) }
+                                }
+                                th { (t!("compare.change", locale = locale)) }
+                            } }
+                            tbody {
+                                @for q in &comparison.flipped {
+                                    tr {
+                                        td {
+                                            (q.question)
+                                            @if let Some(category) = &q.category {
+                                                br;
+                                                small style="color: var(--color-muted);" { (category) }
+                                            }
+                                        }
+                                        @for outcome in &q.outcomes {
+                                            td { (outcome_mark(*outcome)) }
+                                        }
+                                        td {
+                                            @if q.now_correct && !q.was_correct {
+                                                span."compare-up" { (t!("compare.fixed", locale = locale)) }
+                                            } @else if !q.now_correct && q.was_correct {
+                                                span."compare-down" { (t!("compare.regressed", locale = locale)) }
+                                            } @else {
+                                                span style="color: var(--color-warning);" { (t!("compare.unsteady", locale = locale)) }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn calibration(data: CalibrationData, locale: &str) -> Markup {
+    let has_rated = data
+        .confidence_stats
+        .iter()
+        .any(|c| c.confidence != "unrated");
+
+    html! {
+        h1 { (data.quiz_name) }
+        div style="margin-bottom: 1rem;" {
+            button hx-get=(names::quiz_dashboard_url(&data.quiz_id))
+                   hx-push-url="true"
+                   hx-target="main"
+                   style="width: fit-content;" {
+                (t!("dashboard.back_to_dashboard", locale = locale))
+            }
+        }
+        article {
+            h4 { (t!("calibration.title", locale = locale)) }
+            @if !has_rated {
+                p { (t!("calibration.no_data", locale = locale)) }
+            } @else {
+                p style="color: var(--color-muted); font-size: 0.9rem;" {
+                    (t!("calibration.desc", locale = locale))
+                }
+                table {
+                    thead { tr {
+                        th { (t!("calibration.level", locale = locale)) }
+                        th { (t!("dashboard.total_answers", locale = locale)) }
+                        th { (t!("dashboard.accuracy", locale = locale)) }
+                    } }
+                    tbody {
+                        @for c in &data.confidence_stats {
+                            @let acc = if c.total > 0 {
+                                c.correct as f64 * 100.0 / c.total as f64
+                            } else {
+                                0.0
+                            };
+                            tr {
+                                td { (confidence_label(&c.confidence, locale)) }
+                                td { (c.total) }
+                                td {
+                                    (format!("{:.1}%", acc))
+                                    small style="color: var(--color-muted);" {
+                                        " (" (c.correct) "/" (c.total) ")"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        article {
+            h4 { (t!("calibration.confident_wrong_title", locale = locale)) }
+            p style="color: var(--color-muted); font-size: 0.9rem;" {
+                (t!("calibration.confident_wrong_desc", locale = locale))
+            }
+            @if data.confident_wrong.is_empty() {
+                p { (t!("calibration.no_confident_wrong", locale = locale)) }
+            } @else {
+                table {
+                    thead { tr {
+                        th { (t!("result.question_col", locale = locale)) }
+                        th { (t!("dashboard.category", locale = locale)) }
+                        th { (t!("calibration.times_wrong", locale = locale)) }
+                    } }
+                    tbody {
+                        @for q in &data.confident_wrong {
+                            tr {
+                                td { (q.question) }
+                                td { (q.category.as_deref().unwrap_or("-")) }
+                                td { (q.times_wrong) }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A learner's pace in one quiz: median answer time per session with its trend, the
+/// slowest questions, and time on correct against incorrect answers per category.
+pub fn pace(data: PaceData, locale: &str) -> Markup {
+    let report = &data.report;
+    let config = serde_json::json!({
+        "paceLabels": report.sessions.iter().map(|s| s.session_name.as_str()).collect::<Vec<_>>(),
+        "paceData": report.sessions.iter().map(|s| s.median_ms as f64 / 1000.0).collect::<Vec<_>>(),
+        "paceYLabel": t!("pace.chart_yaxis", locale = locale).to_string(),
+    });
+
+    html! {
+        h1 { (data.quiz_name) }
+        div style="margin-bottom: 1rem;" {
+            button hx-get=(names::quiz_dashboard_url(&data.quiz_id))
+                   hx-push-url="true"
+                   hx-target="main"
+                   style="width: fit-content;" {
+                (t!("dashboard.back_to_dashboard", locale = locale))
+            }
+        }
+        @if report.sessions.is_empty() {
+            article {
+                h4 { (t!("pace.title", locale = locale)) }
+                p { (t!("pace.no_data", locale = locale)) }
+            }
+        } @else {
+            article {
+                h4 { (t!("pace.sessions_title", locale = locale)) }
+                @if let Some(trend) = report.trend_ms_per_session {
+                    p."pace-trend" {
+                        @if trend.abs() < 500.0 {
+                            (t!("pace.trend_steady", locale = locale))
+                        } @else if trend < 0.0 {
+                            span."material-symbols-rounded" { "trending_down" }
+                            (t!("pace.trend_faster", locale = locale))
+                            strong { (format!("{:.1}s", -trend / 1000.0)) }
+                            (t!("pace.trend_suffix", locale = locale))
+                        } @else {
+                            span."material-symbols-rounded" { "trending_up" }
+                            (t!("pace.trend_slower", locale = locale))
+                            strong { (format!("{:.1}s", trend / 1000.0)) }
+                            (t!("pace.trend_suffix", locale = locale))
+                        }
+                    }
+                }
+                @if report.sessions.len() > 1 {
+                    canvas id="pace-chart" {}
+                    div id="chart-data" data-config=(config.to_string()) style="display:none;" {}
+                }
+                table {
+                    thead { tr {
+                        th { (t!("dashboard.name", locale = locale)) }
+                        th { (t!("pace.answered", locale = locale)) }
+                        th { (t!("pace.median_time", locale = locale)) }
+                    } }
+                    tbody {
+                        @for session in report.sessions.iter().rev() {
+                            tr {
+                                td {
+                                    a hx-get=(names::results_url(session.session_id))
+                                      hx-push-url="true"
+                                      hx-target="main"
+                                      href=(names::results_url(session.session_id)) {
+                                        (session.session_name)
+                                    }
+                                }
+                                td { (session.answered) }
+                                td { (format_study_time(session.median_ms)) }
+                            }
+                        }
+                    }
+                }
+            }
+            article {
+                h4 { (t!("pace.slowest_title", locale = locale)) }
+                table {
+                    thead { tr {
+                        th { (t!("result.question_col", locale = locale)) }
+                        th { (t!("pace.median_time", locale = locale)) }
+                        th { (t!("dashboard.accuracy", locale = locale)) }
+                    } }
+                    tbody {
+                        @for q in &report.slowest {
+                            tr {
+                                td {
+                                    (q.question)
+                                    @if let Some(category) = &q.category {
+                                        br;
+                                        small style="color: var(--color-muted);" { (category) }
+                                    }
+                                }
+                                td { (format_study_time(q.median_ms)) }
+                                td { (q.correct) " / " (q.attempts) }
+                            }
+                        }
+                    }
+                }
+            }
+            @if !report.categories.is_empty() {
+                article {
+                    h4 { (t!("pace.categories_title", locale = locale)) }
+                    p style="color: var(--color-muted); font-size: 0.9rem;" {
+                        (t!("pace.categories_desc", locale = locale))
+                    }
+                    table {
+                        thead { tr {
+                            th { (t!("dashboard.category", locale = locale)) }
+                            th { (t!("pace.when_correct", locale = locale)) }
+                            th { (t!("pace.when_incorrect", locale = locale)) }
+                        } }
+                        tbody {
+                            @for c in &report.categories {
+                                tr {
+                                    td { (c.category) }
+                                    td {
+                                        @if let Some(ms) = c.correct_median_ms {
+                                            (format_study_time(ms))
+                                            small style="color: var(--color-muted);" { " (" (c.correct) ")" }
+                                        } @else { "-" }
+                                    }
+                                    td {
+                                        @if let Some(ms) = c.incorrect_median_ms {
+                                            (format_study_time(ms))
+                                            small style="color: var(--color-muted);" { " (" (c.incorrect) ")" }
+                                        } @else { "-" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Owner report of per-question difficulty, discrimination and timing, with likely
+/// miskeyed questions first.
+pub fn item_analysis(data: ItemAnalysisData, locale: &str) -> Markup {
+    let answered: Vec<&ItemAnalysis> = data.items.iter().filter(|i| i.responses > 0).collect();
+    let flagged = answered.iter().filter(|i| i.likely_miskeyed).count();
+
+    html! {
+        h1 { (data.quiz_name) }
+        div style="display: flex; gap: 1rem; margin-bottom: 1rem; flex-wrap: wrap;" {
+            button hx-get=(names::quiz_dashboard_url(&data.quiz_id))
+                   hx-push-url="true"
+                   hx-target="main"
+                   style="width: fit-content;" {
+                (t!("dashboard.back_to_dashboard", locale = locale))
+            }
+            button hx-get=(names::quiz_distractors_url(&data.quiz_id))
+                   hx-push-url="true"
+                   hx-target="main"
+                   style="width: fit-content;" {
+                (t!("dashboard.open_distractors", locale = locale))
+            }
+        }
+        h4 { (t!("items.title", locale = locale)) }
+        p style="color: var(--color-muted); font-size: 0.9rem;" {
+            (t!("items.desc", locale = locale))
+        }
+        @if answered.is_empty() {
+            p { (t!("distractors.no_data", locale = locale)) }
+        } @else {
+            @if flagged > 0 {
+                p."item-flag-summary" {
+                    span."material-symbols-rounded" { "warning" }
+                    (flagged) (t!("items.flagged_suffix", locale = locale))
+                }
+            }
+            div style="overflow-x: auto;" {
+                table."item-analysis" {
+                    thead { tr {
+                        th { (t!("result.question_col", locale = locale)) }
+                        th { (t!("distractors.responses", locale = locale)) }
+                        th { (t!("items.difficulty", locale = locale)) }
+                        th { (t!("items.discrimination", locale = locale)) }
+                        th { (t!("items.median_time", locale = locale)) }
+                        th { (t!("items.top_distractor", locale = locale)) }
+                    } }
+                    tbody {
+                        @for item in answered {
+                            tr class=[item.likely_miskeyed.then_some("distractor-misleading")] {
+                                td {
+                                    (item.question)
+                                    @if let Some(category) = &item.category {
+                                        br;
+                                        small style="color: var(--color-muted);" { (category) }
+                                    }
+                                    @if item.likely_miskeyed {
+                                        br;
+                                        span class="badge-incorrect" {
+                                            span."material-symbols-rounded" style="font-size: 0.9rem;" { "warning" }
+                                            (t!("items.miskeyed", locale = locale))
+                                        }
+                                    }
+                                }
+                                td { (item.responses) }
+                                td {
+                                    @if let Some(p) = item.difficulty {
+                                        (format!("{:.0}%", p * 100.0))
+                                    }
+                                }
+                                td class=[item.discrimination.is_some_and(|d| d < 0.2).then_some("item-poor")] {
+                                    @match item.discrimination {
+                                        Some(d) => (format!("{d:+.2}")),
+                                        None => small style="color: var(--color-muted);" { (t!("items.not_enough", locale = locale)) },
+                                    }
+                                }
+                                td {
+                                    @if let Some(ms) = item.median_ms {
+                                        (format_study_time(ms))
+                                    }
+                                }
+                                td {
+                                    @if let Some((option, share)) = &item.top_distractor {
+                                        (option)
+                                        small style="color: var(--color-muted);" { (format!(" ({:.0}%)", share * 100.0)) }
+                                    } @else {
+                                        "-"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Owner view of how often each option is picked, flagging wrong options that draw
+/// at least as many learners as the correct answer.
+pub fn distractor_analysis(data: DistractorAnalysisData, locale: &str) -> Markup {
+    let answered: Vec<&DistractorQuestion> =
+        data.questions.iter().filter(|q| q.responses > 0).collect();
+
+    html! {
+        h1 { (data.quiz_name) }
+        div style="margin-bottom: 1rem;" {
+            button hx-get=(names::quiz_dashboard_url(&data.quiz_id))
+                   hx-push-url="true"
+                   hx-target="main"
+                   style="width: fit-content;" {
+                (t!("dashboard.back_to_dashboard", locale = locale))
+            }
+        }
+        h4 { (t!("distractors.title", locale = locale)) }
+        p style="color: var(--color-muted); font-size: 0.9rem;" {
+            (t!("distractors.desc", locale = locale))
+        }
+        @if answered.is_empty() {
+            p { (t!("distractors.no_data", locale = locale)) }
+        }
+        @for q in answered {
+            @let top_correct = q.options.iter().filter(|o| o.is_answer).map(|o| o.picks).max().unwrap_or(0);
+            article."distractor-question" {
+                header style="display: flex; gap: 0.5rem; align-items: baseline; flex-wrap: wrap;" {
+                    strong { (q.question) }
+                    @if let Some(category) = &q.category {
+                        small style="color: var(--color-muted);" { (category) }
+                    }
+                    small style="margin-left: auto; color: var(--color-muted);" {
+                        (q.responses) (t!("distractors.responses_suffix", locale = locale))
+                    }
+                }
+                table {
+                    tbody {
+                        @for opt in &q.options {
+                            @let pct = f64::from(opt.picks) * 100.0 / f64::from(q.responses);
+                            @let is_misleading = !opt.is_answer && opt.picks > 0 && opt.picks >= top_correct;
+                            tr class=[is_misleading.then_some("distractor-misleading")] {
+                                td {
+                                    (opt.option)
+                                    @if opt.is_answer {
+                                        span class="badge-correct" {
+                                            span."material-symbols-rounded" style="font-size: 0.9rem;" { "check" }
+                                            (t!("quiz.correct", locale = locale))
+                                        }
+                                    }
+                                    @if is_misleading {
+                                        span class="badge-incorrect" {
+                                            span."material-symbols-rounded" style="font-size: 0.9rem;" { "warning" }
+                                            (t!("distractors.misleading", locale = locale))
+                                        }
+                                    }
+                                }
+                                td style="width: 40%;" {
+                                    div."option-share-bar" {
+                                        div."option-share-fill" style=(format!("width: {pct:.0}%;")) {}
+                                    }
+                                }
+                                td style="white-space: nowrap;" {
+                                    (format!("{pct:.0}%"))
+                                    small style="color: var(--color-muted);" { " (" (opt.picks) ")" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 fn session_history_table(sessions: &[SessionReportModel], locale: &str) -> Markup {
     html! {
         table {
             thead { tr {
+                th { span."material-symbols-rounded" title=(t!("compare.compare_selected", locale = locale)) { "compare_arrows" } }
                 th { (t!("dashboard.name", locale = locale)) }
                 th { (t!("dashboard.mode", locale = locale)) }
                 th { (t!("dashboard.progress", locale = locale)) }
@@ -249,6 +1094,10 @@ fn session_history_table(sessions: &[SessionReportModel], locale: &str) -> Marku
             tbody {
                 @for s in sessions {
                     tr {
+                        td {
+                            input type="checkbox" name="sessions" value=(s.id) form="compare-form"
+                                  aria-label=(t!("compare.pick_session", locale = locale, name = s.name));
+                        }
                         td { (s.name) }
                         td {
                             (selection_mode_label(s.selection_mode.as_deref().unwrap_or("random"), locale))
@@ -393,8 +1242,17 @@ pub fn format_study_time(ms: i64) -> String {
 
 pub fn session_result(data: SessionResultData, locale: &str) -> Markup {
     let mode_label = selection_mode_label(&data.selection_mode, locale);
-    let incorrect_count = data.answers.iter().filter(|a| !a.is_correct).count();
-    let bookmarked_count = data.answers.iter().filter(|a| a.is_bookmarked).count();
+    let first_attempts = || data.answers.iter().filter(|a| a.attempt == 1);
+    let incorrect_count = first_attempts().filter(|a| !a.is_correct).count();
+    let bookmarked_count = first_attempts().filter(|a| a.is_bookmarked).count();
+    let total_attempts = data.answers.len() as i32;
+    let hints_used: i32 = data.answers.iter().map(|a| a.hints_used).sum();
+    let median_answer_ms = analytics::median(
+        first_attempts()
+            .filter(|a| a.active_ms > 0)
+            .map(|a| i64::from(a.active_ms))
+            .collect(),
+    );
     let percentage = if data.answered_count > 0 {
         data.correct_answers as f64 * 100.0 / data.answered_count as f64
     } else {
@@ -439,12 +1297,102 @@ pub fn session_result(data: SessionResultData, locale: &str) -> Markup {
                 }
                 " (" mark { (format!("{:.0}%", percentage)) } ")"
             }
+            @if total_attempts > data.answered_count {
+                p {
+                    (t!("result.total_attempts", locale = locale))
+                    mark { (total_attempts) }
+                }
+            }
             @if data.study_time_ms > 0 {
                 p {
                     (t!("result.study_time", locale = locale))
                     mark { (format_study_time(data.study_time_ms)) }
                 }
             }
+            @if let Some(median_ms) = median_answer_ms {
+                p {
+                    (t!("result.median_time", locale = locale))
+                    mark { (format_study_time(median_ms)) }
+                    " "
+                    a hx-get=(names::quiz_pace_url(&data.quiz_id))
+                      hx-push-url="true"
+                      hx-target="main"
+                      href=(names::quiz_pace_url(&data.quiz_id))
+                      style="font-size: 0.9rem;" {
+                        (t!("result.pace_link", locale = locale))
+                    }
+                }
+            }
+            @if data.idle_time_ms > 0 {
+                p style="color: var(--color-muted); font-size: 0.9rem;" {
+                    (t!("result.idle_time", locale = locale))
+                    (format_study_time(data.idle_time_ms))
+                }
+            }
+            @if hints_used > 0 {
+                p style="color: var(--color-muted); font-size: 0.9rem;" {
+                    (t!("result.hints_used", locale = locale))
+                    (hints_used)
+                }
+            }
+        }
+
+        @if !data.answers.is_empty() {
+            p {
+                a hx-get=(names::session_review_url(data.session_id, "all"))
+                  hx-push-url="true"
+                  hx-target="main"
+                  href=(names::session_review_url(data.session_id, "all")) {
+                    span."material-symbols-rounded" style="font-size: 1.1rem; vertical-align: middle;" { "fact_check" }
+                    " " (t!("result.review_link", locale = locale))
+                }
+                @if incorrect_count > 0 {
+                    " · "
+                    a hx-get=(names::session_review_url(data.session_id, "incorrect"))
+                      hx-push-url="true"
+                      hx-target="main"
+                      href=(names::session_review_url(data.session_id, "incorrect")) {
+                        (t!("result.review_incorrect_link", locale = locale))
+                    }
+                }
+            }
+        }
+
+        @if let Some(challenge_id) = &data.challenge_id {
+            article."article-narrow" {
+                h4 { (t!("challenge.head_to_head", locale = locale)) }
+                (challenge_standings(&data.challenge_standings, Some(data.session_id), locale))
+                a hx-get=(names::challenge_url(challenge_id))
+                  hx-push-url="true"
+                  hx-target="main"
+                  href=(names::challenge_url(challenge_id)) {
+                    (t!("challenge.open", locale = locale))
+                }
+            }
+        }
+
+        @if data.is_complete {
+            article."article-narrow" {
+                h4 { (t!("result.again_title", locale = locale)) }
+                div style="display: flex; gap: 0.5rem; flex-wrap: wrap;" {
+                    button hx-post=(names::retake_session_url(data.session_id))
+                           hx-target="main"
+                           hx-swap="innerHTML"
+                           style="width: fit-content;" {
+                        (t!("result.retake_btn", locale = locale))
+                    }
+                    @if data.challenge_id.is_none() {
+                        button hx-post=(names::create_challenge_url(data.session_id))
+                               hx-target="#challenge-link"
+                               hx-swap="innerHTML"
+                               class="secondary"
+                               style="width: fit-content;" {
+                            (t!("result.challenge_btn", locale = locale))
+                        }
+                    }
+                }
+                div id="challenge-link" {}
+            }
         }
 
         @if incorrect_count > 0 && data.is_complete {
@@ -521,14 +1469,21 @@ pub fn session_result(data: SessionResultData, locale: &str) -> Markup {
                         @let url = if data.is_complete {
                             format!("/question/{}?question_idx={}&from=report", data.session_id, a.question_idx)
                         } else {
-                            format!("/question/{}?question_idx={}&from=report&current_idx={}", data.session_id, a.question_idx, data.answered_count)
+                            format!("/question/{}?question_idx={}&from=report&current_idx={}", data.session_id, a.question_idx, data.current_idx)
                         };
                         tr style="cursor: pointer;"
                            hx-get=(url)
                            hx-push-url="true"
                            hx-target="main" {
                             td { (a.question_idx + 1) }
-                            td { (a.question) }
+                            td {
+                                (a.question)
+                                @if a.attempt > 1 {
+                                    small style="color: var(--color-muted);" {
+                                        " (" (t!("quiz.cram_attempt", locale = locale)) (a.attempt) ")"
+                                    }
+                                }
+                            }
                             td {
                                 span."material-symbols-rounded" style=(if a.is_correct { "color: var(--color-success); font-size: 1.1rem;" } else { "color: var(--color-danger); font-size: 1.1rem;" }) {
                                     (if a.is_correct { "check_circle" } else { "cancel" })
@@ -540,6 +1495,13 @@ pub fn session_result(data: SessionResultData, locale: &str) -> Markup {
                                         "bookmark"
                                     }
                                 }
+                                @if a.hints_used > 0 {
+                                    span."material-symbols-rounded"
+                                         style="font-size: 1.1rem; color: var(--color-warning);"
+                                         title=(format!("{}{}", t!("quiz.hints_used", locale = locale), a.hints_used)) {
+                                        "lightbulb"
+                                    }
+                                }
                             }
                          }
                     }
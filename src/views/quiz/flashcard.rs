@@ -0,0 +1,187 @@
+use super::{bookmark_button, explanation_panel, note_panel, self_grade_label};
+use crate::{db::QuestionModel, names};
+use maud::{html, Markup};
+use rust_i18n::t;
+
+pub struct FlashcardData {
+    pub quiz_name: String,
+    pub question: QuestionModel,
+    pub question_idx: i32,
+    pub questions_count: i32,
+    pub session_id: i32,
+    pub quiz_id: String,
+    pub question_id: i32,
+    pub is_bookmarked: bool,
+    pub self_grade: Option<String>,
+    pub idempotency_key: String,
+    pub from_context: Option<String>,
+    pub current_idx: Option<i32>,
+    pub note: Option<String>,
+}
+
+/// Self-graded card: the answer is hidden until revealed, then the learner grades their recall.
+pub fn flashcard(data: FlashcardData, locale: &str) -> Markup {
+    let is_final = data.question_idx + 1 == data.questions_count;
+    let is_graded = data.self_grade.is_some();
+
+    html! {
+        @if !(is_graded && is_final) {
+            div data-quiz-active-msg=(t!("quiz.abandon_confirm", locale = locale)) hidden {}
+        }
+        p { (t!("quiz.doing_quiz", locale = locale)) mark { (data.quiz_name) } "." }
+        article style="width: fit-content;" {
+            @let done = if is_graded { data.question_idx + 1 } else { data.question_idx };
+            @let progress_pct = if data.questions_count > 0 {
+                (done as f64 / data.questions_count as f64 * 100.0) as u32
+            } else { 0 };
+            div."question-progress" {
+                div."question-progress-fill" style=(format!("width: {}%;", progress_pct)) {}
+            }
+            div style="display: flex; align-items: center; margin-bottom: 0.5rem;" {
+                p style="color: var(--color-muted); font-size: 0.9rem; margin-bottom: 0;" {
+                    (t!("quiz.question_prefix", locale = locale))
+                    strong { (data.question_idx + 1) }
+                    (t!("quiz.question_of", locale = locale))
+                    (data.questions_count)
+                }
+                span style="margin-left: auto;" {
+                    (bookmark_button(data.session_id, data.question_id, data.is_bookmarked, locale))
+                }
+            }
+
+            h3 { (data.question.question) }
+
+            @if data.question.is_multiple_choice {
+                p style="color: var(--color-info); font-weight: 500;" { (t!("quiz.multiple_choice", locale = locale)) }
+            }
+
+            @if let Some(grade) = &data.self_grade {
+                (revealed_options(&data.question, locale))
+                p style="color: var(--color-muted); font-size: 0.9rem;" {
+                    (t!("flashcard.your_grade", locale = locale))
+                    strong { (self_grade_label(grade, locale)) }
+                }
+            } @else {
+                ul."flashcard-options" {
+                    @for opt in &data.question.options {
+                        li { (opt.option) }
+                    }
+                }
+                details."flashcard-reveal" {
+                    summary role="button" class="secondary" {
+                        (t!("flashcard.show_answer", locale = locale))
+                    }
+                    (revealed_options(&data.question, locale))
+                    form hx-post=(names::SUBMIT_SELF_GRADE_URL)
+                         hx-target="main"
+                         hx-swap="innerHTML"
+                         id="question-form" {
+                        input type="hidden" name="question_id" value=(data.question_id);
+                        input type="hidden" name="idempotency_key" value=(data.idempotency_key);
+                        p style="margin-bottom: 0.5rem;" { (t!("flashcard.grade_prompt", locale = locale)) }
+                        div."self-grade-buttons" {
+                            @for grade in names::SELF_GRADES {
+                                button type="submit" name="grade" value=(grade)
+                                       class=(format!("self-grade-btn self-grade-{grade}")) {
+                                    (self_grade_label(grade, locale))
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div style="display: flex; gap: 1rem; margin-top: 1rem; align-items: center;" {
+                @if data.from_context.as_deref() == Some("report") {
+                    button class="nav-btn nav-btn-back"
+                           hx-get=(names::results_url(data.session_id))
+                           hx-push-url="true"
+                           hx-target="main" {
+                        (t!("quiz.back_to_results", locale = locale))
+                    }
+                    @if let Some(current) = data.current_idx {
+                        span style="margin-left: auto;" {
+                            button class="nav-btn nav-btn-next"
+                                   hx-get=(format!("/question/{}?question_idx={}", data.session_id, current))
+                                   hx-push-url="true"
+                                   hx-target="main" {
+                                (t!("quiz.return_to_current", locale = locale))
+                            }
+                        }
+                    }
+                } @else {
+                    @if data.question_idx > 0 {
+                        button type="button" class="nav-btn nav-btn-back"
+                               hx-get=(format!("/question/{}?question_idx={}", data.session_id, data.question_idx - 1))
+                               hx-target="main"
+                               hx-swap="innerHTML" {
+                            (t!("quiz.previous", locale = locale))
+                        }
+                    }
+                    @if is_graded {
+                        span style="margin-left: auto;" {
+                            @if is_final {
+                                button class="nav-btn nav-btn-next"
+                                       hx-get=(names::results_url(data.session_id))
+                                       hx-push-url="true"
+                                       hx-target="main" { (t!("quiz.see_results", locale = locale)) }
+                            } @else {
+                                button class="nav-btn nav-btn-next"
+                                       hx-get=(format!("/question/{}?question_idx={}", data.session_id, data.question_idx + 1))
+                                       hx-target="main"
+                                       hx-swap="innerHTML" { (t!("quiz.next", locale = locale)) }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        (note_panel(data.question_id, data.note.as_deref(), locale))
+        p style="margin-top: 0.5rem; font-size: 0.8rem;" {
+            a data-dialog-open="abandon-dialog"
+              style="color: var(--color-muted-light); text-decoration: underline; cursor: pointer;" {
+                (t!("quiz.abandon", locale = locale))
+            }
+        }
+        dialog id="abandon-dialog" {
+            article {
+                p { (t!("quiz.abandon_confirm", locale = locale)) }
+                footer style="display: flex; gap: 0.5rem; justify-content: flex-end;" {
+                    button data-dialog-close="abandon-dialog"
+                           class="secondary" {
+                        (t!("quiz.abandon_cancel", locale = locale))
+                    }
+                    button hx-get=(names::abandon_quiz_url(&data.quiz_id))
+                           hx-target="main" {
+                        (t!("quiz.abandon", locale = locale))
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn revealed_options(question: &QuestionModel, locale: &str) -> Markup {
+    html! {
+        @for opt in &question.options {
+            @let css_class = if opt.is_answer { "option-correct" } else { "option-neutral" };
+            div class=(css_class) {
+                p style="margin-bottom: 0;" {
+                    (opt.option)
+                    @if opt.is_answer {
+                        span class="badge-correct" {
+                            span."material-symbols-rounded" style="font-size: 0.9rem;" { "check" }
+                            (t!("quiz.correct", locale = locale))
+                        }
+                    }
+                }
+                @if let Some(explanation) = &opt.explanation {
+                    div class="explanation" {
+                        (explanation)
+                    }
+                }
+            }
+        }
+        (explanation_panel(question.explanation.as_deref(), &question.references, locale))
+    }
+}
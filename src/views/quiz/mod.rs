@@ -1,14 +1,41 @@
+mod activity;
+mod assignment;
+mod bookmarks;
+mod challenge;
+mod collaborator;
 mod dashboard;
+mod flashcard;
 mod question;
+mod review;
 mod session;
 mod sharing;
 
+pub use activity::{activity_page, today_progress};
+pub use assignment::{
+    assignment_not_available, assignment_page, assignments_page, AssignmentsPageData,
+    CreatedAssignmentRow, LearnerAssignmentRow,
+};
+pub use bookmarks::bookmarks_page;
+pub use challenge::{
+    challenge_link, challenge_not_available, challenge_page, challenge_standings, ChallengePageData,
+};
+pub use collaborator::{collaborators_page, received_invitations, CollaboratorsPageData};
 pub use dashboard::{
-    dashboard, format_study_time, session_history, session_result, DashboardData,
-    SessionHistoryData, SessionResultData,
+    calibration, dashboard, distractor_analysis, format_study_time, item_analysis, pace,
+    session_comparison, session_history, session_result, CalibrationData, DashboardData,
+    DistractorAnalysisData, ItemAnalysisData, PaceData, SessionComparisonData, SessionHistoryData,
+    SessionResultData,
+};
+pub use flashcard::{flashcard, FlashcardData};
+pub use question::{
+    answer, bookmark_button, explanation_panel, hint_panel, note_panel, question, AnswerData,
+    QuestionData,
+};
+pub use review::{session_review, SessionReviewData};
+pub use session::{
+    session_name_error_page, session_size_settings, start_page, SessionSizeRange,
+    SessionSizeSettingsData, StartPageData,
 };
-pub use question::{answer, bookmark_button, question, AnswerData, QuestionData};
-pub use session::{session_name_error_page, start_page, StartPageData};
 pub use sharing::{share_toggle_icon, shared_quiz_not_available, shared_quiz_page};
 
 use rust_i18n::t;
@@ -19,6 +46,36 @@ pub(crate) fn selection_mode_label(mode: &str, locale: &str) -> String {
         "incorrect" => t!("mode.incorrect", locale = locale).to_string(),
         "random" => t!("mode.random", locale = locale).to_string(),
         "bookmarked" => t!("mode.bookmarked", locale = locale).to_string(),
+        "retake" => t!("mode.retake", locale = locale).to_string(),
+        "challenge" => t!("mode.challenge", locale = locale).to_string(),
         _ => mode.to_string(),
     }
 }
+
+pub(crate) fn study_mode_label(mode: &str, locale: &str) -> String {
+    match mode {
+        "quiz" => t!("mode.study_quiz", locale = locale).to_string(),
+        "flashcard" => t!("mode.study_flashcard", locale = locale).to_string(),
+        "cram" => t!("mode.study_cram", locale = locale).to_string(),
+        _ => mode.to_string(),
+    }
+}
+
+pub(crate) fn confidence_label(level: &str, locale: &str) -> String {
+    match level {
+        "sure" => t!("quiz.confidence_sure", locale = locale).to_string(),
+        "unsure" => t!("quiz.confidence_unsure", locale = locale).to_string(),
+        "guess" => t!("quiz.confidence_guess", locale = locale).to_string(),
+        _ => t!("calibration.unrated", locale = locale).to_string(),
+    }
+}
+
+pub(crate) fn self_grade_label(grade: &str, locale: &str) -> String {
+    match grade {
+        "again" => t!("flashcard.again", locale = locale).to_string(),
+        "hard" => t!("flashcard.hard", locale = locale).to_string(),
+        "good" => t!("flashcard.good", locale = locale).to_string(),
+        "easy" => t!("flashcard.easy", locale = locale).to_string(),
+        _ => grade.to_string(),
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{db::QuestionModel, names};
+use super::confidence_label;
+use crate::{
+    db::{AnswerDistribution, QuestionModel},
+    names,
+    views::components,
+};
 use maud::{html, Markup};
 use rust_i18n::t;
 
@@ -12,8 +17,12 @@ pub struct QuestionData {
     pub is_resuming: bool,
     pub session_id: i32,
     pub question_id: i32,
+    pub attempt: i32,
     pub is_bookmarked: bool,
     pub quiz_id: String,
+    pub idempotency_key: String,
+    pub note: Option<String>,
+    pub hints_used: i32,
 }
 
 pub struct AnswerData {
@@ -27,7 +36,44 @@ pub struct AnswerData {
     pub from_context: Option<String>,
     pub current_idx: Option<i32>,
     pub question_id: i32,
+    pub attempt: i32,
     pub is_bookmarked: bool,
+    pub confidence: Option<String>,
+    pub note: Option<String>,
+    pub hints_used: i32,
+    /// Other learners' picks, when the quiz is shared and has enough responses
+    pub distribution: Option<AnswerDistribution>,
+}
+
+/// Share of learners who picked one option, drawn as a thin bar under it.
+fn option_share(distribution: &AnswerDistribution, option_id: i32, locale: &str) -> Markup {
+    let picks = distribution.picks.get(&option_id).copied().unwrap_or(0);
+    let pct = if distribution.responses > 0 {
+        (f64::from(picks) * 100.0 / f64::from(distribution.responses)).round() as u32
+    } else {
+        0
+    };
+    html! {
+        div."option-share" {
+            div."option-share-bar" {
+                div."option-share-fill" style=(format!("width: {pct}%;")) {}
+            }
+            small { (pct) "% " (t!("quiz.chose_this", locale = locale)) }
+        }
+    }
+}
+
+/// Marks a cram-mode re-queue of a question that was missed earlier in the session.
+fn attempt_badge(attempt: i32, locale: &str) -> Markup {
+    html! {
+        @if attempt > 1 {
+            span."cram-attempt" {
+                span."material-symbols-rounded" style="font-size: 0.9rem;" { "replay" }
+                (t!("quiz.cram_attempt", locale = locale))
+                (attempt)
+            }
+        }
+    }
 }
 
 pub fn bookmark_button(
@@ -56,6 +102,95 @@ pub fn bookmark_button(
     }
 }
 
+/// Private note on a question, shown collapsed until the user has written one.
+pub fn note_panel(question_id: i32, note: Option<&str>, locale: &str) -> Markup {
+    html! {
+        details."question-note" id="question-note" open[note.is_some()] {
+            summary {
+                span."material-symbols-rounded" style="font-size: 1.1rem; vertical-align: middle;" { "edit_note" }
+                " " (t!("quiz.note", locale = locale))
+            }
+            @if let Some(note) = note {
+                div."note-body" { (components::markdown(note)) }
+            }
+            form hx-post=(names::question_note_url(question_id))
+                 hx-target="#question-note"
+                 hx-swap="outerHTML" {
+                textarea name="note"
+                         rows="3"
+                         maxlength=(names::MAX_NOTE_LENGTH)
+                         placeholder=(t!("quiz.note_placeholder", locale = locale)) {
+                    (note.unwrap_or_default())
+                }
+                button type="submit" class="secondary" style="width: fit-content;" {
+                    (t!("quiz.save_note", locale = locale))
+                }
+            }
+        }
+    }
+}
+
+/// Hints revealed so far, with a button for the next one while any remain.
+pub fn hint_panel(
+    session_id: i32,
+    question_idx: i32,
+    hints: &[String],
+    hints_used: i32,
+    locale: &str,
+) -> Markup {
+    let revealed = (hints_used.max(0) as usize).min(hints.len());
+    let remaining = hints.len() - revealed;
+
+    html! {
+        div."hint-panel" id="hint-panel" {
+            @for hint in &hints[..revealed] {
+                p."hint" {
+                    span."material-symbols-rounded" style="font-size: 1rem; vertical-align: middle;" { "lightbulb" }
+                    " " (hint)
+                }
+            }
+            @if remaining > 0 {
+                button type="button" class="secondary outline"
+                       hx-post=(names::reveal_hint_url(session_id, question_idx))
+                       hx-target="#hint-panel"
+                       hx-swap="outerHTML"
+                       style="width: fit-content;" {
+                    (t!("quiz.show_hint", locale = locale))
+                    " (" (remaining) ")"
+                }
+            }
+        }
+    }
+}
+
+/// Question-level explanation and references, shown once the answer is revealed.
+pub fn explanation_panel(explanation: Option<&str>, references: &[String], locale: &str) -> Markup {
+    html! {
+        @if let Some(explanation) = explanation {
+            div."question-explanation" {
+                strong { (t!("quiz.explanation", locale = locale)) }
+                p { (explanation) }
+            }
+        }
+        @if !references.is_empty() {
+            div."question-references" {
+                strong { (t!("quiz.references", locale = locale)) }
+                ul {
+                    @for reference in references {
+                        li {
+                            @if reference.starts_with("https://") || reference.starts_with("http://") {
+                                a href=(reference) target="_blank" rel="noopener noreferrer" { (reference) }
+                            } @else {
+                                (reference)
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub fn question(data: QuestionData, locale: &str) -> Markup {
     html! {
         div data-quiz-active-msg=(t!("quiz.abandon_confirm", locale = locale)) hidden {}
@@ -74,6 +209,7 @@ pub fn question(data: QuestionData, locale: &str) -> Markup {
                     (t!("quiz.question_of", locale = locale))
                     (data.questions_count)
                 }
+                (attempt_badge(data.attempt, locale))
                 span style="margin-left: auto;" {
                     (bookmark_button(data.session_id, data.question_id, data.is_bookmarked, locale))
                 }
@@ -91,10 +227,16 @@ pub fn question(data: QuestionData, locale: &str) -> Markup {
                 p style="color: var(--color-info); font-weight: 500;" { (t!("quiz.multiple_choice", locale = locale)) }
             }
 
+            @if !data.question.hints.is_empty() {
+                (hint_panel(data.session_id, data.question_idx, &data.question.hints, data.hints_used, locale))
+            }
+
             form hx-post=(names::SUBMIT_ANSWER_URL)
                  hx-target="main"
                  hx-swap="innerHTML"
                  id="question-form" {
+                input type="hidden" name="question_id" value=(data.question_id);
+                input type="hidden" name="idempotency_key" value=(data.idempotency_key);
                 fieldset {
                     @for opt in data.question.options {
                         div."option-card" {
@@ -111,6 +253,17 @@ pub fn question(data: QuestionData, locale: &str) -> Markup {
                         }
                     }
                 }
+                fieldset."confidence-picker" {
+                    legend style="color: var(--color-muted); font-size: 0.9rem;" {
+                        (t!("quiz.confidence", locale = locale))
+                    }
+                    @for level in names::CONFIDENCE_LEVELS {
+                        label style="display: inline-block; margin-right: 1rem;" {
+                            input type="radio" name="confidence" value=(level);
+                            (confidence_label(level, locale))
+                        }
+                    }
+                }
                 div style="display: flex; gap: 1rem; margin-top: 1rem; align-items: center;" {
                     @if data.question_idx > 0 {
                         button type="button" class="nav-btn nav-btn-back"
@@ -126,6 +279,7 @@ pub fn question(data: QuestionData, locale: &str) -> Markup {
                 }
             }
         }
+        (note_panel(data.question_id, data.note.as_deref(), locale))
         p style="margin-top: 0.5rem; font-size: 0.8rem;" {
             a data-dialog-open="abandon-dialog"
               style="color: var(--color-muted-light); text-decoration: underline; cursor: pointer;" {
@@ -172,6 +326,7 @@ pub fn answer(data: AnswerData, locale: &str) -> Markup {
                     (t!("quiz.question_of", locale = locale))
                     (data.questions_count)
                 }
+                (attempt_badge(data.attempt, locale))
                 span style="margin-left: auto;" {
                     (bookmark_button(data.session_id, data.question_id, data.is_bookmarked, locale))
                 }
@@ -180,7 +335,7 @@ pub fn answer(data: AnswerData, locale: &str) -> Markup {
 
             form {
                 fieldset disabled="true" {
-                    @for opt in data.question.options {
+                    @for opt in &data.question.options {
                         @let is_selected = data.selected.contains(&opt.id);
                         @let css_class = if opt.is_answer {
                             "option-correct"
@@ -218,6 +373,9 @@ pub fn answer(data: AnswerData, locale: &str) -> Markup {
                                     }
                                 }
                             }
+                            @if let Some(distribution) = &data.distribution {
+                                (option_share(distribution, opt.id, locale))
+                            }
                             @if let Some(explanation) = &opt.explanation {
                                 div class="explanation" {
                                     (explanation)
@@ -227,6 +385,32 @@ pub fn answer(data: AnswerData, locale: &str) -> Markup {
                     }
                 }
             }
+            @if let Some(distribution) = &data.distribution {
+                p."option-share-note" {
+                    (t!("quiz.distribution_note_1", locale = locale))
+                    (distribution.responses)
+                    (t!("quiz.distribution_note_2", locale = locale))
+                }
+            }
+
+            (explanation_panel(
+                data.question.explanation.as_deref(),
+                &data.question.references,
+                locale,
+            ))
+
+            @if let Some(confidence) = &data.confidence {
+                p style="color: var(--color-muted); font-size: 0.9rem;" {
+                    (t!("quiz.your_confidence", locale = locale))
+                    strong { (confidence_label(confidence, locale)) }
+                }
+            }
+            @if data.hints_used > 0 {
+                p style="color: var(--color-muted); font-size: 0.9rem;" {
+                    (t!("quiz.hints_used", locale = locale))
+                    strong { (data.hints_used) }
+                }
+            }
 
             @if data.from_context.as_deref() == Some("report") {
                 div style="display: flex; gap: 1rem; margin-top: 1rem; align-items: center;" {
@@ -275,6 +459,7 @@ pub fn answer(data: AnswerData, locale: &str) -> Markup {
                 }
             }
         }
+        (note_panel(data.question_id, data.note.as_deref(), locale))
         p style="margin-top: 0.5rem; font-size: 0.8rem;" {
             a data-dialog-open="abandon-dialog"
               style="color: var(--color-muted-light); text-decoration: underline; cursor: pointer;" {
@@ -0,0 +1,153 @@
+use super::{confidence_label, explanation_panel, format_study_time, self_grade_label};
+use crate::{db::ReviewItem, names};
+use maud::{html, Markup};
+use rust_i18n::t;
+
+pub struct SessionReviewData {
+    pub session_name: String,
+    pub session_id: i32,
+    pub quiz_name: String,
+    pub filter: String,
+    pub items: Vec<ReviewItem>,
+}
+
+fn filter_label(filter: &str, locale: &str) -> String {
+    match filter {
+        "incorrect" => t!("review.filter_incorrect", locale = locale).to_string(),
+        "bookmarked" => t!("review.filter_bookmarked", locale = locale).to_string(),
+        _ => t!("review.filter_all", locale = locale).to_string(),
+    }
+}
+
+fn matches_filter(item: &ReviewItem, filter: &str) -> bool {
+    match filter {
+        "incorrect" => !item.is_correct,
+        "bookmarked" => item.is_bookmarked,
+        _ => true,
+    }
+}
+
+/// Every answered question of a session on one page, laid out to print cleanly.
+pub fn session_review(data: SessionReviewData, locale: &str) -> Markup {
+    let shown: Vec<&ReviewItem> = data
+        .items
+        .iter()
+        .filter(|item| matches_filter(item, &data.filter))
+        .collect();
+
+    html! {
+        div."review-toolbar no-print" {
+            a."back-link" hx-get=(names::results_url(data.session_id))
+                          hx-push-url="true"
+                          hx-target="main"
+                          href=(names::results_url(data.session_id)) {
+                span."material-symbols-rounded" { "arrow_back" }
+                (t!("quiz.back_to_results", locale = locale))
+            }
+            button type="button" class="secondary outline" data-print=""
+                   style="width: fit-content; margin-left: auto;" {
+                span."material-symbols-rounded" style="font-size: 1rem; vertical-align: middle;" { "print" }
+                " " (t!("review.print", locale = locale))
+            }
+        }
+
+        h5 { mark { (data.quiz_name) } }
+        h1 { (t!("review.title", locale = locale)) ": " (data.session_name) }
+
+        nav."review-filters no-print" {
+            @for filter in names::REVIEW_FILTERS {
+                @let count = data.items.iter().filter(|item| matches_filter(item, filter)).count();
+                a hx-get=(names::session_review_url(data.session_id, filter))
+                  hx-push-url="true"
+                  hx-target="main"
+                  href=(names::session_review_url(data.session_id, filter))
+                  aria-current=[(*filter == data.filter).then_some("page")] {
+                    (filter_label(filter, locale)) " (" (count) ")"
+                }
+            }
+        }
+
+        @if shown.is_empty() {
+            p { (t!("review.empty", locale = locale)) }
+        }
+
+        @for item in shown {
+            article."review-item" {
+                header style="display: flex; align-items: center; gap: 0.5rem; flex-wrap: wrap;" {
+                    span."material-symbols-rounded" style=(if item.is_correct { "color: var(--color-success);" } else { "color: var(--color-danger);" }) {
+                        (if item.is_correct { "check_circle" } else { "cancel" })
+                    }
+                    strong {
+                        (t!("quiz.question_prefix", locale = locale))
+                        (item.question_idx + 1)
+                    }
+                    @if item.attempt > 1 {
+                        small style="color: var(--color-muted);" {
+                            "(" (t!("quiz.cram_attempt", locale = locale)) (item.attempt) ")"
+                        }
+                    }
+                    @if let Some(category) = &item.category {
+                        small style="color: var(--color-muted);" { (category) }
+                    }
+                    @if item.is_bookmarked {
+                        span."material-symbols-rounded" style="font-size: 1.1rem; color: var(--color-warning);" { "bookmark" }
+                    }
+                    small style="margin-left: auto; color: var(--color-muted);" {
+                        (t!("review.time_taken", locale = locale))
+                        (format_study_time(i64::from(item.active_ms)))
+                    }
+                }
+
+                p style="font-weight: 500;" { (item.question) }
+
+                @for opt in &item.options {
+                    @let css_class = if opt.is_answer {
+                        "option-correct"
+                    } else if opt.is_selected {
+                        "option-incorrect"
+                    } else {
+                        "option-neutral"
+                    };
+                    div class=(css_class) {
+                        p style="margin-bottom: 0;" {
+                            (opt.option)
+                            @if opt.is_selected {
+                                span."badge-selected" { (t!("review.your_answer", locale = locale)) }
+                            }
+                            @if opt.is_answer {
+                                span class="badge-correct" {
+                                    span."material-symbols-rounded" style="font-size: 0.9rem;" { "check" }
+                                    (t!("quiz.correct", locale = locale))
+                                }
+                            }
+                        }
+                        @if let Some(explanation) = &opt.explanation {
+                            div class="explanation" { (explanation) }
+                        }
+                    }
+                }
+
+                (explanation_panel(item.explanation.as_deref(), &item.references, locale))
+
+                @if item.confidence.is_some() || item.self_grade.is_some() || item.hints_used > 0 {
+                    p style="color: var(--color-muted); font-size: 0.9rem; margin-top: 0.75rem; margin-bottom: 0;" {
+                        @if let Some(confidence) = &item.confidence {
+                            (t!("quiz.your_confidence", locale = locale))
+                            strong { (confidence_label(confidence, locale)) }
+                            " "
+                        }
+                        @if let Some(grade) = &item.self_grade {
+                            (t!("flashcard.your_grade", locale = locale))
+                            strong { (self_grade_label(grade, locale)) }
+                            " "
+                        }
+                        @if item.hints_used > 0 {
+                            (t!("quiz.hints_used", locale = locale))
+                            strong { (item.hints_used) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use crate::names;
+use super::study_mode_label;
+use crate::{
+    db::{InProgressSession, QuizSessionSizes},
+    names,
+};
 use maud::{html, Markup};
 use rust_i18n::t;
 
@@ -6,6 +10,16 @@ pub struct StartPageData {
     pub quiz_name: String,
     pub total_questions: i32,
     pub quiz_id: String,
+    pub sizes: SessionSizeRange,
+    pub in_progress: Vec<InProgressSession>,
+}
+
+/// Session sizes offered on the start page.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SessionSizeRange {
+    pub min: i32,
+    pub max: i32,
+    pub default: i32,
 }
 
 pub fn session_name_error_page(session_name: &str, quiz_id: &str, locale: &str) -> Markup {
@@ -40,6 +54,44 @@ pub fn session_name_error_page(session_name: &str, quiz_id: &str, locale: &str)
     }
 }
 
+fn in_progress_sessions(sessions: &[InProgressSession], locale: &str) -> Markup {
+    html! {
+        article."article-narrow" id="in-progress-sessions" {
+            h4 { (t!("quiz.in_progress_title", locale = locale)) }
+            @for (i, s) in sessions.iter().enumerate() {
+                div."in-progress-session" {
+                    div {
+                        strong { (s.name) }
+                        @if i == 0 {
+                            " " span."badge-last-active" { (t!("quiz.last_active_badge", locale = locale)) }
+                        }
+                        br;
+                        small style="color: var(--color-muted);" {
+                            (s.answered_questions) " / " (s.total_questions)
+                            " · " (study_mode_label(&s.study_mode, locale))
+                            " · " (t!("quiz.last_active", locale = locale)) (s.last_active) " UTC"
+                        }
+                    }
+                    div."in-progress-actions" {
+                        button hx-get=(names::resume_session_url(s.id, &s.session_token))
+                               hx-push-url="true"
+                               hx-target="main"
+                               class=(if i == 0 { "" } else { "secondary" }) {
+                            (t!("quiz.resume", locale = locale))
+                        }
+                        button hx-post=(names::discard_session_url(s.id))
+                               hx-target="main"
+                               hx-confirm=(t!("quiz.discard_confirm", locale = locale))
+                               class="outline secondary" {
+                            (t!("quiz.discard", locale = locale))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub fn start_page(data: StartPageData, locale: &str) -> Markup {
     html! {
         h1 { (t!("quiz.welcome", locale = locale)) }
@@ -50,6 +102,9 @@ pub fn start_page(data: StartPageData, locale: &str) -> Markup {
             (data.total_questions)
             (t!("quiz.doing_quiz_intro_3", locale = locale))
         }
+        @if !data.in_progress.is_empty() {
+            (in_progress_sessions(&data.in_progress, locale))
+        }
         article."article-narrow" {
             form hx-post=(names::start_session_url(&data.quiz_id))
                  hx-ext="json-enc"
@@ -74,19 +129,26 @@ pub fn start_page(data: StartPageData, locale: &str) -> Markup {
                     (t!("quiz.question_count", locale = locale))
                     input name="question_count"
                           type="number"
-                          min=(names::MIN_QUESTION_COUNT)
-                          max=(names::MAX_QUESTION_COUNT)
-                          value=(names::DEFAULT_QUESTION_COUNT)
+                          id="question-count"
+                          min=(data.sizes.min)
+                          max=(data.sizes.max)
+                          value=(data.sizes.default)
                           aria-label=(t!("quiz.question_count", locale = locale))
                           required;
                     small style="display: block; margin-top: 0.5rem; color: var(--color-muted);" {
                         (t!("quiz.question_count_hint",
-                            min = names::MIN_QUESTION_COUNT,
-                            max = names::MAX_QUESTION_COUNT,
-                            default = names::DEFAULT_QUESTION_COUNT,
+                            min = data.sizes.min,
+                            max = data.sizes.max,
+                            default = data.sizes.default,
                             locale = locale))
                     }
                 }
+                label {
+                    input type="checkbox" name="all_questions" data-all-questions="question-count";
+                    (t!("quiz.all_questions_1", locale = locale))
+                    (data.total_questions)
+                    (t!("quiz.all_questions_2", locale = locale))
+                }
                 fieldset {
                     legend { (t!("quiz.selection_mode", locale = locale)) }
                     label {
@@ -102,6 +164,21 @@ pub fn start_page(data: StartPageData, locale: &str) -> Markup {
                         (t!("quiz.mode_random", locale = locale))
                     }
                 }
+                fieldset {
+                    legend { (t!("quiz.study_mode", locale = locale)) }
+                    label {
+                        input type="radio" name="study_mode" value="quiz" checked;
+                        (t!("quiz.study_mode_quiz", locale = locale))
+                    }
+                    label {
+                        input type="radio" name="study_mode" value="flashcard";
+                        (t!("quiz.study_mode_flashcard", locale = locale))
+                    }
+                    label {
+                        input type="radio" name="study_mode" value="cram";
+                        (t!("quiz.study_mode_cram", locale = locale))
+                    }
+                }
                 input type="submit" value=(t!("quiz.start", locale = locale));
             }
         }
@@ -115,3 +192,77 @@ pub fn start_page(data: StartPageData, locale: &str) -> Markup {
         }
     }
 }
+
+pub struct SessionSizeSettingsData {
+    pub quiz_name: String,
+    pub quiz_id: String,
+    /// The owner's saved values; empty inputs fall back to the instance defaults.
+    pub sizes: QuizSessionSizes,
+    /// What the start page currently offers.
+    pub effective: SessionSizeRange,
+    pub min_limit: i32,
+    pub max_limit: i32,
+    pub saved: bool,
+}
+
+pub fn session_size_settings(data: SessionSizeSettingsData, locale: &str) -> Markup {
+    html! {
+        h1 { (t!("session_sizes.title", locale = locale)) }
+        p { mark { (data.quiz_name) } }
+        article."article-narrow" id="session-size-settings" {
+            p style="color: var(--color-muted); font-size: 0.9rem;" {
+                (t!("session_sizes.instance_limits",
+                    min = data.min_limit,
+                    max = data.max_limit,
+                    locale = locale))
+            }
+            form hx-post=(names::quiz_settings_url(&data.quiz_id))
+                 hx-target="main"
+                 hx-swap="innerHTML" {
+                label {
+                    (t!("session_sizes.default_size", locale = locale))
+                    input name="default_size" type="number"
+                          min=(data.min_limit) max=(data.max_limit)
+                          placeholder=(names::DEFAULT_QUESTION_COUNT)
+                          value=[data.sizes.default_size];
+                }
+                label {
+                    (t!("session_sizes.min_size", locale = locale))
+                    input name="min_size" type="number"
+                          min=(data.min_limit) max=(data.max_limit)
+                          placeholder=(data.min_limit)
+                          value=[data.sizes.min_size];
+                }
+                label {
+                    (t!("session_sizes.max_size", locale = locale))
+                    input name="max_size" type="number"
+                          min=(data.min_limit) max=(data.max_limit)
+                          placeholder=(data.max_limit)
+                          value=[data.sizes.max_size];
+                }
+                small style="display: block; margin-bottom: 1rem; color: var(--color-muted);" {
+                    (t!("session_sizes.blank_hint", locale = locale))
+                }
+                input type="submit" value=(t!("session_sizes.save", locale = locale));
+            }
+            @if data.saved {
+                p style="color: var(--color-success);" { (t!("session_sizes.saved", locale = locale)) }
+            }
+            p {
+                (t!("session_sizes.effective",
+                    min = data.effective.min,
+                    max = data.effective.max,
+                    default = data.effective.default,
+                    locale = locale))
+            }
+        }
+        p {
+            button hx-get=(names::quiz_dashboard_url(&data.quiz_id))
+                   hx-push-url="true"
+                   hx-target="main"
+                   style="width: fit-content;" {
+                (t!("quiz.back_to_dashboard", locale = locale))
+            }
+        }
+    }
+}
@@ -59,6 +59,12 @@
       return
     }
 
+    // Print the page: <element data-print>
+    if (e.target.closest('[data-print]')) {
+      window.print()
+      return
+    }
+
     // Dialog open: <element data-dialog-open="dialog-id">
     const dialogOpen = e.target.closest('[data-dialog-open]')
     if (dialogOpen) {
@@ -112,10 +118,10 @@
       return
     }
 
-    // Copy share URL: <element data-copy-url>
+    // Copy share URL: <element data-copy-url="input-id">
     const copyUrl = e.target.closest('[data-copy-url]')
     if (copyUrl) {
-      const urlInput = document.getElementById('share-url')
+      const urlInput = document.getElementById(copyUrl.dataset.copyUrl || 'share-url')
       if (urlInput) {
         navigator.clipboard.writeText(window.location.origin + urlInput.value)
       }
@@ -157,6 +163,8 @@
   // --- Enable submit button on form input change ---
   document.addEventListener('change', (e) => {
     if (e.target.closest('#question-form')) {
+      // Rating confidence alone does not answer the question
+      if (e.target.name === 'confidence') return
       const btn = document.getElementById('submit-btn')
       if (btn) btn.disabled = false
       // Sync option-card selected state (fallback for browsers without :has())
@@ -167,20 +175,12 @@
     }
   })
 
-  // --- Question timing ---
-  let questionStartTime = 0
-
-  const initQuestionTimer = () => {
-    if (document.getElementById('question-form')) {
-      questionStartTime = Date.now()
-    }
-  }
-
-  document.addEventListener('htmx:configRequest', (e) => {
-    if (e.detail.elt?.id === 'question-form' && questionStartTime > 0) {
-      const duration = Date.now() - questionStartTime
-      e.detail.parameters.duration_ms = String(Math.min(duration, 300000))
-    }
+  // --- "All questions" checkbox disables the question count ---
+  document.addEventListener('change', (e) => {
+    const targetId = e.target.dataset?.allQuestions
+    if (!targetId) return
+    const input = document.getElementById(targetId)
+    if (input) input.disabled = e.target.checked
   })
 
   // --- Session name auto-generation ---
@@ -405,6 +405,37 @@
           },
         })
       }
+
+      const pc = document.getElementById('pace-chart')
+      if (pc) {
+        new Chart(pc, {
+          type: 'line',
+          data: {
+            labels: config.paceLabels,
+            datasets: [
+              {
+                data: config.paceData,
+                borderColor: chartPrimary,
+                backgroundColor: `rgba(${hexToRgb(chartPrimary)},0.1)`,
+                fill: true,
+                tension: 0.3,
+                pointRadius: 4,
+                pointHoverRadius: 6,
+              },
+            ],
+          },
+          options: {
+            responsive: true,
+            plugins: { legend: { display: false } },
+            scales: {
+              y: {
+                min: 0,
+                title: { display: true, text: config.paceYLabel },
+              },
+            },
+          },
+        })
+      }
     }
 
     if (chartJsLoaded) {
@@ -423,7 +454,6 @@
   // Run on page load and HTMX content swaps
   const init = () => {
     generateSessionName()
-    initQuestionTimer()
     initCharts()
   }
 
@@ -4,7 +4,9 @@ use axum::{
     body::Body,
     http::{Method, Request, StatusCode},
 };
-use quizinart::{email::ResendEmailSender, names, router, services::auth::AuthService, AppState};
+use quizinart::{
+    email::ResendEmailSender, names, router, services::auth::AuthService, AppState, SessionLimits,
+};
 use tower::ServiceExt;
 
 fn make_state(db: quizinart::db::Db) -> AppState {
@@ -18,6 +20,7 @@ fn make_state(db: quizinart::db::Db) -> AppState {
         db,
         auth,
         secure_cookies: false,
+        session_limits: SessionLimits::default(),
     }
 }
 
@@ -1,9 +1,13 @@
 mod common;
 
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use common::create_test_db;
-use quizinart::db::Db;
+use quizinart::db::{
+    AnswerOutcome, AssignmentSettings, AssignmentTarget, DailyGoal, Db, QuizAccess,
+    QuizSessionSizes, StudyPlanSettings, WeeklyDigest,
+};
 use quizinart::models::{Question, QuestionOption};
 
 /// Helper: create a test user and return their id
@@ -23,13 +27,19 @@ fn sample_questions() -> Vec<Question> {
                 text: "1".to_string(),
                 is_answer: false,
                 explanation: None,
+                is_pinned: false,
             },
             QuestionOption {
                 text: "2".to_string(),
                 is_answer: true,
                 explanation: Some("Basic arithmetic".to_string()),
+                is_pinned: false,
             },
         ],
+        explanation: None,
+        references: vec![],
+        hints: vec![],
+        variables: Default::default(),
     }]
 }
 
@@ -44,13 +54,19 @@ fn make_questions(n: usize) -> Vec<Question> {
                     text: format!("Correct {}", i + 1),
                     is_answer: true,
                     explanation: None,
+                    is_pinned: false,
                 },
                 QuestionOption {
                     text: format!("Wrong {}", i + 1),
                     is_answer: false,
                     explanation: None,
+                    is_pinned: false,
                 },
             ],
+            explanation: None,
+            references: vec![],
+            hints: vec![],
+            variables: Default::default(),
         })
         .collect()
 }
@@ -92,13 +108,19 @@ fn minimal_questions() -> Vec<Question> {
                 text: "A".to_string(),
                 is_answer: true,
                 explanation: None,
+                is_pinned: false,
             },
             QuestionOption {
                 text: "B".to_string(),
                 is_answer: false,
                 explanation: None,
+                is_pinned: false,
             },
         ],
+        explanation: None,
+        references: vec![],
+        hints: vec![],
+        variables: Default::default(),
     }]
 }
 
@@ -161,7 +183,7 @@ async fn test_session_creation() {
 
     let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", minimal_questions(), user_id).await;
     let (token, session_id) = db
-        .create_session("session-1", quiz_id, 5, "random", user_id)
+        .create_session("session-1", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
     assert!(!token.is_empty());
@@ -178,13 +200,13 @@ async fn test_duplicate_session_name() {
     let user_id = create_test_user(&db).await;
 
     let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", minimal_questions(), user_id).await;
-    db.create_session("dupe", quiz_id, 5, "random", user_id)
+    db.create_session("dupe", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
 
     // Same name, same quiz -> should fail
     let result = db
-        .create_session("dupe", quiz_id, 5, "random", user_id)
+        .create_session("dupe", quiz_id, 5, "random", "quiz", user_id)
         .await;
     assert!(result.is_err());
     assert!(result.unwrap_err().to_string().contains("already in use"));
@@ -198,12 +220,12 @@ async fn test_session_count() {
     let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", minimal_questions(), user_id).await;
     assert_eq!(db.sessions_count(quiz_id).await.unwrap(), 0);
 
-    db.create_session("s1", quiz_id, 5, "random", user_id)
+    db.create_session("s1", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
     assert_eq!(db.sessions_count(quiz_id).await.unwrap(), 1);
 
-    db.create_session("s2", quiz_id, 5, "random", user_id)
+    db.create_session("s2", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
     assert_eq!(db.sessions_count(quiz_id).await.unwrap(), 2);
@@ -216,7 +238,7 @@ async fn test_delete_session() {
 
     let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", minimal_questions(), user_id).await;
     let (token, session_id) = db
-        .create_session("to-delete", quiz_id, 5, "random", user_id)
+        .create_session("to-delete", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
 
@@ -236,7 +258,7 @@ async fn test_rename_session() {
 
     let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", minimal_questions(), user_id).await;
     let (_, session_id) = db
-        .create_session("old-name", quiz_id, 5, "random", user_id)
+        .create_session("old-name", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
 
@@ -254,11 +276,11 @@ async fn test_rename_session_duplicate() {
     let user_id = create_test_user(&db).await;
 
     let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", minimal_questions(), user_id).await;
-    db.create_session("existing", quiz_id, 5, "random", user_id)
+    db.create_session("existing", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
     let (_, session_id2) = db
-        .create_session("to-rename", quiz_id, 5, "random", user_id)
+        .create_session("to-rename", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
 
@@ -277,7 +299,7 @@ async fn test_random_mode_no_duplicates() {
     let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(10), user_id).await;
 
     let (_, session_id) = db
-        .create_session("random-session", quiz_id, 5, "random", user_id)
+        .create_session("random-session", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
     let ids = get_session_question_ids(&db, session_id).await;
@@ -301,7 +323,7 @@ async fn test_random_mode_cap_at_total() {
 
     // Request more questions than exist
     let (_, session_id) = db
-        .create_session("random-big", quiz_id, 10, "random", user_id)
+        .create_session("random-big", quiz_id, 10, "random", "quiz", user_id)
         .await
         .unwrap();
     let ids = get_session_question_ids(&db, session_id).await;
@@ -325,7 +347,7 @@ async fn test_unanswered_mode_no_duplicates() {
 
     // Session 1: pick 4 unanswered questions
     let (_, s1_id) = db
-        .create_session("s1", quiz_id, 4, "unanswered", user_id)
+        .create_session("s1", quiz_id, 4, "unanswered", "quiz", user_id)
         .await
         .unwrap();
     let ids1 = get_session_question_ids(&db, s1_id).await;
@@ -341,7 +363,7 @@ async fn test_unanswered_mode_no_duplicates() {
 
     // Session 2: pick 4 more unanswered questions — should NOT overlap with session 1
     let (_, s2_id) = db
-        .create_session("s2", quiz_id, 4, "unanswered", user_id)
+        .create_session("s2", quiz_id, 4, "unanswered", "quiz", user_id)
         .await
         .unwrap();
     let ids2 = get_session_question_ids(&db, s2_id).await;
@@ -372,7 +394,7 @@ async fn test_unanswered_mode_fallback_no_duplicates() {
 
     // Session 1: exhaust all 5 questions
     let (_, s1_id) = db
-        .create_session("s1", quiz_id, 5, "unanswered", user_id)
+        .create_session("s1", quiz_id, 5, "unanswered", "quiz", user_id)
         .await
         .unwrap();
     let ids1 = get_session_question_ids(&db, s1_id).await;
@@ -380,7 +402,7 @@ async fn test_unanswered_mode_fallback_no_duplicates() {
 
     // Session 2: no unanswered left — fallback fills from already-asked
     let (_, s2_id) = db
-        .create_session("s2", quiz_id, 3, "unanswered", user_id)
+        .create_session("s2", quiz_id, 3, "unanswered", "quiz", user_id)
         .await
         .unwrap();
     let ids2 = get_session_question_ids(&db, s2_id).await;
@@ -408,7 +430,7 @@ async fn test_unanswered_mode_partial_fallback() {
 
     // Session 1: use 4 out of 6
     let (_, s1_id) = db
-        .create_session("s1", quiz_id, 4, "unanswered", user_id)
+        .create_session("s1", quiz_id, 4, "unanswered", "quiz", user_id)
         .await
         .unwrap();
     let ids1 = get_session_question_ids(&db, s1_id).await;
@@ -416,7 +438,7 @@ async fn test_unanswered_mode_partial_fallback() {
 
     // Session 2: request 4, only 2 unanswered remain → 2 unanswered + 2 fill
     let (_, s2_id) = db
-        .create_session("s2", quiz_id, 4, "unanswered", user_id)
+        .create_session("s2", quiz_id, 4, "unanswered", "quiz", user_id)
         .await
         .unwrap();
     let ids2 = get_session_question_ids(&db, s2_id).await;
@@ -470,7 +492,7 @@ async fn test_create_session_with_questions_deduplicates_question_ids() {
     ];
 
     let token = db
-        .create_session_with_questions("dedupe", quiz_id, &requested, "incorrect", user_id)
+        .create_session_with_questions("dedupe", quiz_id, &requested, "incorrect", "quiz", user_id)
         .await
         .unwrap();
     let session = db.get_session(&token).await.unwrap();
@@ -490,13 +512,13 @@ async fn test_bookmark_default_false() {
     let user_id = create_test_user(&db).await;
     let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", minimal_questions(), user_id).await;
     let (_, session_id) = db
-        .create_session("bm-test", quiz_id, 5, "random", user_id)
+        .create_session("bm-test", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
     let question_id = db.get_question_by_idx(session_id, 0).await.unwrap();
 
     let is_bm = db
-        .is_question_bookmarked(session_id, question_id)
+        .is_question_bookmarked(user_id, question_id)
         .await
         .unwrap();
     assert!(!is_bm, "New questions should not be bookmarked by default");
@@ -508,21 +530,21 @@ async fn test_bookmark_toggle() {
     let user_id = create_test_user(&db).await;
     let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", minimal_questions(), user_id).await;
     let (_, session_id) = db
-        .create_session("bm-toggle", quiz_id, 5, "random", user_id)
+        .create_session("bm-toggle", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
     let question_id = db.get_question_by_idx(session_id, 0).await.unwrap();
 
     // Toggle on
-    let new_state = db.toggle_bookmark(session_id, question_id).await.unwrap();
+    let new_state = db.toggle_bookmark(user_id, question_id).await.unwrap();
     assert!(new_state, "First toggle should set bookmark to true");
 
     // Toggle off
-    let new_state = db.toggle_bookmark(session_id, question_id).await.unwrap();
+    let new_state = db.toggle_bookmark(user_id, question_id).await.unwrap();
     assert!(!new_state, "Second toggle should set bookmark to false");
 
     // Toggle on again
-    let new_state = db.toggle_bookmark(session_id, question_id).await.unwrap();
+    let new_state = db.toggle_bookmark(user_id, question_id).await.unwrap();
     assert!(new_state, "Third toggle should set bookmark to true");
 }
 
@@ -532,7 +554,7 @@ async fn test_get_bookmarked_questions() {
     let user_id = create_test_user(&db).await;
     let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(5), user_id).await;
     let (_, session_id) = db
-        .create_session("bm-list", quiz_id, 5, "random", user_id)
+        .create_session("bm-list", quiz_id, 5, "random", "quiz", user_id)
         .await
         .unwrap();
     let ids = get_session_question_ids(&db, session_id).await;
@@ -542,8 +564,8 @@ async fn test_get_bookmarked_questions() {
     assert!(bookmarked.is_empty(), "No bookmarks initially");
 
     // Bookmark 2 questions
-    db.toggle_bookmark(session_id, ids[0]).await.unwrap();
-    db.toggle_bookmark(session_id, ids[2]).await.unwrap();
+    db.toggle_bookmark(user_id, ids[0]).await.unwrap();
+    db.toggle_bookmark(user_id, ids[2]).await.unwrap();
 
     let bookmarked = db.get_bookmarked_questions(session_id).await.unwrap();
     assert_eq!(bookmarked.len(), 2);
@@ -552,12 +574,1902 @@ async fn test_get_bookmarked_questions() {
     assert!(bookmarked_set.contains(&ids[2]));
 
     // Un-bookmark one
-    db.toggle_bookmark(session_id, ids[0]).await.unwrap();
+    db.toggle_bookmark(user_id, ids[0]).await.unwrap();
     let bookmarked = db.get_bookmarked_questions(session_id).await.unwrap();
     assert_eq!(bookmarked.len(), 1);
     assert_eq!(bookmarked[0], ids[2]);
 }
 
+#[tokio::test]
+async fn test_bookmarks_persist_across_sessions() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    let (_, first) = db
+        .create_session("bm-first", quiz_id, 3, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, first).await;
+    db.toggle_bookmark(user_id, ids[1]).await.unwrap();
+
+    // A later session over the same questions sees the bookmark
+    let (_, second) = db
+        .create_session("bm-second", quiz_id, 3, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_bookmarked_questions(second).await.unwrap(),
+        vec![ids[1]]
+    );
+    assert_eq!(
+        db.get_quiz_bookmarks(user_id, quiz_id).await.unwrap(),
+        vec![ids[1]]
+    );
+
+    let bookmarks = db.get_user_bookmarks(user_id).await.unwrap();
+    assert_eq!(bookmarks.len(), 1);
+    assert_eq!(bookmarks[0].question_id, ids[1]);
+    assert_eq!(bookmarks[0].quiz_public_id, public_id);
+    assert!(bookmarks[0].note.is_none());
+
+    // Bookmarks are per user
+    let other = db
+        .create_user("other@example.com", "password123", "Other")
+        .await
+        .unwrap();
+    assert!(db.get_user_bookmarks(other).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_question_notes() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", minimal_questions(), user_id).await;
+    let question_id = db.question_id_from_idx(quiz_id, 0).await.unwrap();
+
+    assert_eq!(db.get_note(user_id, question_id).await.unwrap(), None);
+
+    db.save_note(user_id, question_id, "  **remember** this  ")
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_note(user_id, question_id).await.unwrap().as_deref(),
+        Some("**remember** this")
+    );
+
+    db.save_note(user_id, question_id, "updated").await.unwrap();
+    assert_eq!(
+        db.get_note(user_id, question_id).await.unwrap().as_deref(),
+        Some("updated")
+    );
+
+    // A blank note deletes it
+    db.save_note(user_id, question_id, "   ").await.unwrap();
+    assert_eq!(db.get_note(user_id, question_id).await.unwrap(), None);
+}
+
+// --- Answer submission tests ---
+
+/// Helper: return (correct_option_id, wrong_option_id) for a question
+async fn option_ids(db: &Db, question_id: i32) -> (i32, i32) {
+    let options = db.get_options(question_id).await.unwrap();
+    let correct = options.iter().find(|o| o.is_answer).unwrap().id;
+    let wrong = options.iter().find(|o| !o.is_answer).unwrap().id;
+    (correct, wrong)
+}
+
+/// Answer the session's questions in order, correctly where `pattern` is true, and stop
+/// at the end of the pattern. Returns the session's question ids.
+async fn answer_questions(db: &Db, session_id: i32, pattern: &[bool]) -> Vec<i32> {
+    let ids = get_session_question_ids(db, session_id).await;
+    for (question_id, is_correct) in ids.iter().zip(pattern) {
+        let (correct, wrong) = option_ids(db, *question_id).await;
+        let selected = if *is_correct { correct } else { wrong };
+        db.record_answer(
+            session_id,
+            *question_id,
+            &[selected],
+            &format!("k{session_id}-{question_id}"),
+            None,
+        )
+        .await
+        .unwrap();
+    }
+    ids
+}
+
+/// Start a session over every question of the quiz and answer it following `pattern`.
+async fn answer_session(db: &Db, user_id: i32, quiz_id: i32, pattern: &[bool]) -> (i32, Vec<i32>) {
+    static SESSIONS: AtomicUsize = AtomicUsize::new(0);
+    let name = format!("answered-{}", SESSIONS.fetch_add(1, Ordering::Relaxed));
+    let size = db.questions_count(quiz_id).await.unwrap();
+    let (_, session_id) = db
+        .create_session(&name, quiz_id, size, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let ids = answer_questions(db, session_id, pattern).await;
+    (session_id, ids)
+}
+
+#[tokio::test]
+async fn test_record_answer_by_question_id() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    let (_, session_id) = db
+        .create_session("answer", quiz_id, 3, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, session_id).await;
+
+    // Answer the last question first: the answer is keyed by question id, not position
+    let (correct, _) = option_ids(&db, ids[2]).await;
+    let outcome = db
+        .record_answer(session_id, ids[2], &[correct], "key-1", None)
+        .await
+        .unwrap();
+    assert_eq!(outcome, AnswerOutcome::Recorded { is_correct: true });
+    assert!(db.is_question_answered(session_id, ids[2]).await.unwrap());
+    assert!(!db.is_question_answered(session_id, ids[0]).await.unwrap());
+    assert_eq!(db.correct_answers(session_id).await.unwrap(), 1);
+
+    let (_, wrong) = option_ids(&db, ids[0]).await;
+    let outcome = db
+        .record_answer(session_id, ids[0], &[wrong], "key-2", None)
+        .await
+        .unwrap();
+    assert_eq!(outcome, AnswerOutcome::Recorded { is_correct: false });
+    assert_eq!(
+        db.get_incorrect_questions(session_id).await.unwrap(),
+        vec![ids[0]]
+    );
+}
+
+#[tokio::test]
+async fn test_record_answer_is_idempotent() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", minimal_questions(), user_id).await;
+    let (_, session_id) = db
+        .create_session("idem", quiz_id, 1, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let question_id = db.get_question_by_idx(session_id, 0).await.unwrap();
+    let (correct, wrong) = option_ids(&db, question_id).await;
+
+    let first = db
+        .record_answer(session_id, question_id, &[correct], "same-key", None)
+        .await
+        .unwrap();
+    assert_eq!(first, AnswerOutcome::Recorded { is_correct: true });
+
+    // Retry with the same key is replayed, even with a different selection
+    let retry = db
+        .record_answer(session_id, question_id, &[wrong], "same-key", None)
+        .await
+        .unwrap();
+    assert_eq!(retry, AnswerOutcome::Replayed);
+
+    // Another tab (different key) does not overwrite the stored answer
+    let other_tab = db
+        .record_answer(session_id, question_id, &[wrong], "other-key", None)
+        .await
+        .unwrap();
+    assert_eq!(other_tab, AnswerOutcome::AlreadyAnswered);
+
+    assert_eq!(
+        db.get_selected_answers(session_id, question_id, 1)
+            .await
+            .unwrap(),
+        vec![correct]
+    );
+    assert_eq!(db.correct_answers(session_id).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_record_answer_concurrent_submissions() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", minimal_questions(), user_id).await;
+    let (_, session_id) = db
+        .create_session("race", quiz_id, 1, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let question_id = db.get_question_by_idx(session_id, 0).await.unwrap();
+    let (correct, _) = option_ids(&db, question_id).await;
+
+    let selected = [correct];
+    let (a, b) = tokio::join!(
+        db.record_answer(session_id, question_id, &selected, "double-click", None),
+        db.record_answer(session_id, question_id, &selected, "double-click", None),
+    );
+    let outcomes = [a.unwrap(), b.unwrap()];
+    assert_eq!(
+        outcomes
+            .iter()
+            .filter(|o| matches!(o, AnswerOutcome::Recorded { .. }))
+            .count(),
+        1,
+        "exactly one submission should be recorded"
+    );
+    assert!(outcomes.contains(&AnswerOutcome::Replayed));
+    assert_eq!(
+        db.get_selected_answers(session_id, question_id, 1)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_record_answer_rejects_foreign_question_and_option() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    let (_, session_id) = db
+        .create_session("foreign", quiz_id, 1, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let in_session = db.get_question_by_idx(session_id, 0).await.unwrap();
+    let all_ids = {
+        let mut ids = Vec::new();
+        for idx in 0..3 {
+            ids.push(db.question_id_from_idx(quiz_id, idx).await.unwrap());
+        }
+        ids
+    };
+    let outside = *all_ids.iter().find(|id| **id != in_session).unwrap();
+    let (foreign_option, _) = option_ids(&db, outside).await;
+
+    // Question that is not part of the session
+    assert!(db
+        .record_answer(session_id, outside, &[foreign_option], "k1", None)
+        .await
+        .is_err());
+
+    // Option that belongs to another question, or none at all
+    assert_eq!(
+        db.record_answer(session_id, in_session, &[foreign_option], "k2", None)
+            .await
+            .unwrap(),
+        AnswerOutcome::InvalidSelection
+    );
+    assert_eq!(
+        db.record_answer(session_id, in_session, &[], "k3", None)
+            .await
+            .unwrap(),
+        AnswerOutcome::InvalidSelection
+    );
+    assert!(!db
+        .is_question_answered(session_id, in_session)
+        .await
+        .unwrap());
+}
+
+// --- Option shuffling tests ---
+
+fn pinned_option_questions() -> Vec<Question> {
+    let mut options: Vec<QuestionOption> = ["A", "B", "C", "D"]
+        .iter()
+        .map(|text| QuestionOption {
+            text: text.to_string(),
+            is_answer: false,
+            explanation: None,
+            is_pinned: false,
+        })
+        .collect();
+    options.push(QuestionOption {
+        text: "All of the above".to_string(),
+        is_answer: true,
+        explanation: None,
+        is_pinned: true,
+    });
+    vec![Question {
+        question: "Which apply?".to_string(),
+        category: None,
+        is_multiple_choice: false,
+        options,
+        explanation: None,
+        references: vec![],
+        hints: vec![],
+        variables: Default::default(),
+    }]
+}
+
+#[tokio::test]
+async fn test_session_options_are_stable_and_keep_pinned_position() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) =
+        load_quiz_with_id(&db, "Quiz", pinned_option_questions(), user_id).await;
+
+    let mut orders = HashSet::new();
+    for i in 0..20 {
+        let (_, session_id) = db
+            .create_session(
+                &format!("shuffle-{i}"),
+                quiz_id,
+                1,
+                "random",
+                "quiz",
+                user_id,
+            )
+            .await
+            .unwrap();
+        let question_id = db.get_question_by_idx(session_id, 0).await.unwrap();
+
+        let first: Vec<String> = db
+            .get_session_options(session_id, question_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|o| o.option)
+            .collect();
+        let revisit: Vec<String> = db
+            .get_options_with_selection(session_id, question_id, 1)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|o| o.option)
+            .collect();
+
+        assert_eq!(first, revisit, "order must be stable within a session");
+        assert_eq!(first.len(), 5);
+        assert_eq!(first[4], "All of the above", "pinned option stays last");
+        orders.insert(first);
+    }
+
+    assert!(
+        orders.len() > 1,
+        "options should be shuffled across sessions"
+    );
+}
+
+// --- Confidence tests ---
+
+#[tokio::test]
+async fn test_confidence_stats_and_confident_wrong() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    let (_, session_id) = db
+        .create_session("confidence", quiz_id, 3, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, session_id).await;
+
+    let (correct, _) = option_ids(&db, ids[0]).await;
+    db.record_answer(session_id, ids[0], &[correct], "c1", Some("sure"))
+        .await
+        .unwrap();
+    let (_, wrong_1) = option_ids(&db, ids[1]).await;
+    db.record_answer(session_id, ids[1], &[wrong_1], "c2", Some("sure"))
+        .await
+        .unwrap();
+    let (_, wrong_2) = option_ids(&db, ids[2]).await;
+    db.record_answer(session_id, ids[2], &[wrong_2], "c3", None)
+        .await
+        .unwrap();
+
+    let stats = db.get_confidence_stats(quiz_id, user_id).await.unwrap();
+    let sure = stats.iter().find(|s| s.confidence == "sure").unwrap();
+    assert_eq!((sure.total, sure.correct), (2, 1));
+    let unrated = stats.iter().find(|s| s.confidence == "unrated").unwrap();
+    assert_eq!((unrated.total, unrated.correct), (1, 0));
+
+    let confident_wrong = db
+        .get_confident_wrong_questions(quiz_id, user_id)
+        .await
+        .unwrap();
+    assert_eq!(confident_wrong.len(), 1);
+    assert_eq!(confident_wrong[0].question_id, ids[1]);
+    assert_eq!(confident_wrong[0].times_wrong, 1);
+}
+
+#[tokio::test]
+async fn test_incorrect_mode_includes_lucky_guesses() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    let (_, session_id) = db
+        .create_session("first", quiz_id, 3, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, session_id).await;
+
+    let (correct_0, _) = option_ids(&db, ids[0]).await;
+    db.record_answer(session_id, ids[0], &[correct_0], "g1", Some("guess"))
+        .await
+        .unwrap();
+    let (correct_1, _) = option_ids(&db, ids[1]).await;
+    db.record_answer(session_id, ids[1], &[correct_1], "g2", Some("sure"))
+        .await
+        .unwrap();
+
+    let (_, retry_id) = db
+        .create_session("retry", quiz_id, 1, "incorrect", "quiz", user_id)
+        .await
+        .unwrap();
+    let retry_ids = get_session_question_ids(&db, retry_id).await;
+    assert_eq!(retry_ids, vec![ids[0]]);
+}
+
+#[tokio::test]
+async fn test_incorrect_mode_ignores_other_users_guesses() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    let other_id = db
+        .create_user("other@example.com", "password123", "Other")
+        .await
+        .unwrap();
+    db.add_quiz_to_library(other_id, quiz_id).await.unwrap();
+
+    let (_, session_id) = db
+        .create_session("mine", quiz_id, 3, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, session_id).await;
+    let (correct_0, _) = option_ids(&db, ids[0]).await;
+    db.record_answer(session_id, ids[0], &[correct_0], "g1", Some("guess"))
+        .await
+        .unwrap();
+
+    let (_, other_session_id) = db
+        .create_session("theirs", quiz_id, 3, "random", "quiz", other_id)
+        .await
+        .unwrap();
+    let (correct_1, _) = option_ids(&db, ids[1]).await;
+    db.record_answer(other_session_id, ids[1], &[correct_1], "g2", Some("guess"))
+        .await
+        .unwrap();
+
+    // The pool holds only this user's lucky guess, whatever the shuffle seed
+    for attempt in 0..10 {
+        let (_, retry_id) = db
+            .create_session(
+                &format!("retry-{attempt}"),
+                quiz_id,
+                1,
+                "incorrect",
+                "quiz",
+                user_id,
+            )
+            .await
+            .unwrap();
+        assert_eq!(get_session_question_ids(&db, retry_id).await, vec![ids[0]]);
+    }
+}
+
+#[tokio::test]
+async fn test_incorrect_mode_uses_the_latest_answer_in_time() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    let q0 = db.question_id_from_idx(quiz_id, 0).await.unwrap();
+    let q1 = db.question_id_from_idx(quiz_id, 1).await.unwrap();
+    let (correct_0, _) = option_ids(&db, q0).await;
+    let (correct_1, _) = option_ids(&db, q1).await;
+
+    let (_, older) = db
+        .create_session("older", quiz_id, 3, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let (_, newer) = db
+        .create_session("newer", quiz_id, 3, "random", "quiz", user_id)
+        .await
+        .unwrap();
+
+    // Both guessed in the newer session, then the first answered surely in the older one
+    db.record_answer(newer, q0, &[correct_0], "n0", Some("guess"))
+        .await
+        .unwrap();
+    db.record_answer(newer, q1, &[correct_1], "n1", Some("guess"))
+        .await
+        .unwrap();
+    db.record_answer(older, q0, &[correct_0], "o0", Some("sure"))
+        .await
+        .unwrap();
+
+    let (_, retry_id) = db
+        .create_session("retry", quiz_id, 1, "incorrect", "quiz", user_id)
+        .await
+        .unwrap();
+    assert_eq!(get_session_question_ids(&db, retry_id).await, vec![q1]);
+}
+
+// --- Flashcard tests ---
+
+#[tokio::test]
+async fn test_self_grades_count_as_answers() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    let (token, session_id) = db
+        .create_session("cards", quiz_id, 3, "random", "flashcard", user_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_session(&token).await.unwrap().study_mode,
+        "flashcard"
+    );
+    let ids = get_session_question_ids(&db, session_id).await;
+
+    let again = db
+        .record_self_grade(session_id, ids[0], "again", "f1")
+        .await
+        .unwrap();
+    assert_eq!(again, AnswerOutcome::Recorded { is_correct: false });
+    let good = db
+        .record_self_grade(session_id, ids[1], "good", "f2")
+        .await
+        .unwrap();
+    assert_eq!(good, AnswerOutcome::Recorded { is_correct: true });
+
+    // Replays behave like option submissions
+    let replay = db
+        .record_self_grade(session_id, ids[1], "easy", "f2")
+        .await
+        .unwrap();
+    assert_eq!(replay, AnswerOutcome::Replayed);
+
+    assert_eq!(db.current_question_index(session_id).await.unwrap(), 2);
+    assert_eq!(db.correct_answers(session_id).await.unwrap(), 1);
+    assert_eq!(
+        db.get_incorrect_questions(session_id).await.unwrap(),
+        vec![ids[0]]
+    );
+    assert!(db
+        .get_selected_answers(session_id, ids[1], 1)
+        .await
+        .unwrap()
+        .is_empty());
+    // Cards never marked as shown carry no timing
+    assert_eq!(db.session_study_time(session_id).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_incorrect_mode_includes_hard_cards() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    let (_, session_id) = db
+        .create_session("cards", quiz_id, 3, "random", "flashcard", user_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, session_id).await;
+
+    db.record_self_grade(session_id, ids[0], "hard", "h1")
+        .await
+        .unwrap();
+    db.record_self_grade(session_id, ids[1], "easy", "h2")
+        .await
+        .unwrap();
+    db.record_self_grade(session_id, ids[2], "again", "h3")
+        .await
+        .unwrap();
+
+    let (_, retry_id) = db
+        .create_session("retry", quiz_id, 2, "incorrect", "quiz", user_id)
+        .await
+        .unwrap();
+    let mut retry_ids = get_session_question_ids(&db, retry_id).await;
+    retry_ids.sort();
+    let mut expected = vec![ids[0], ids[2]];
+    expected.sort();
+    assert_eq!(retry_ids, expected);
+}
+
+#[tokio::test]
+async fn test_cram_requeues_missed_questions() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(5), user_id).await;
+    let (_, session_id) = db
+        .create_session("cram", quiz_id, 5, "random", "cram", user_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, session_id).await;
+
+    // A miss comes back three positions later as a second attempt
+    let (_, wrong) = option_ids(&db, ids[0]).await;
+    db.record_answer(session_id, ids[0], &[wrong], "c1", None)
+        .await
+        .unwrap();
+    assert_eq!(db.questions_count_for_session(session_id).await.unwrap(), 6);
+    assert_eq!(
+        db.distinct_questions_for_session(session_id).await.unwrap(),
+        5
+    );
+    let requeued = db.get_session_slot(session_id, 4).await.unwrap();
+    assert_eq!((requeued.question_id, requeued.attempt), (ids[0], 2));
+    assert!(!requeued.is_answered);
+    assert_eq!(db.get_question_by_idx(session_id, 5).await.unwrap(), ids[4]);
+
+    // Replaying the miss does not answer the re-queued attempt
+    let replay = db
+        .record_answer(session_id, ids[0], &[wrong], "c1", None)
+        .await
+        .unwrap();
+    assert_eq!(replay, AnswerOutcome::Replayed);
+    let answered = db
+        .get_answered_slot(session_id, ids[0], "c1")
+        .await
+        .unwrap();
+    assert_eq!((answered.question_number, answered.attempt), (0, 1));
+
+    for (n, qid) in ids.iter().enumerate().skip(1) {
+        let (correct, _) = option_ids(&db, *qid).await;
+        db.record_answer(session_id, *qid, &[correct], &format!("c{}", n + 1), None)
+            .await
+            .unwrap();
+    }
+    let (correct, _) = option_ids(&db, ids[0]).await;
+    let retry = db
+        .record_answer(session_id, ids[0], &[correct], "retry", None)
+        .await
+        .unwrap();
+    assert_eq!(retry, AnswerOutcome::Recorded { is_correct: true });
+    assert_eq!(
+        db.get_selected_answers(session_id, ids[0], 2)
+            .await
+            .unwrap(),
+        vec![correct]
+    );
+    assert_eq!(
+        db.get_selected_answers(session_id, ids[0], 1)
+            .await
+            .unwrap(),
+        vec![wrong]
+    );
+
+    // Every slot is answered, but the score only counts first attempts
+    assert_eq!(db.current_question_index(session_id).await.unwrap(), 6);
+    assert_eq!(db.correct_answers(session_id).await.unwrap(), 4);
+    assert_eq!(db.get_answers(session_id).await.unwrap().len(), 6);
+    assert_eq!(
+        db.get_incorrect_questions(session_id).await.unwrap(),
+        vec![ids[0]]
+    );
+}
+
+#[tokio::test]
+async fn test_quiz_mode_does_not_requeue() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(2), user_id).await;
+    let (_, session_id) = db
+        .create_session("quiz", quiz_id, 2, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, session_id).await;
+
+    let (_, wrong) = option_ids(&db, ids[1]).await;
+    db.record_answer(session_id, ids[1], &[wrong], "q1", None)
+        .await
+        .unwrap();
+    assert_eq!(db.questions_count_for_session(session_id).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_answer_timing_is_measured_on_server() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(2), user_id).await;
+    let (_, session_id) = db
+        .create_session("timing", quiz_id, 2, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, session_id).await;
+
+    db.mark_question_served(session_id, 0).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let (correct, wrong) = option_ids(&db, ids[0]).await;
+    db.record_answer(session_id, ids[0], &[correct, wrong], "t1", None)
+        .await
+        .unwrap();
+
+    // Timed once per question, however many options were selected
+    let study_time = db.session_study_time(session_id).await.unwrap();
+    assert!((50..5_000).contains(&study_time), "{study_time}");
+    assert_eq!(db.session_idle_time(session_id).await.unwrap(), 0);
+    assert_eq!(db.quiz_study_time(quiz_id).await.unwrap(), study_time);
+
+    // Serving an answered question again does not restart its clock
+    db.mark_question_served(session_id, 0).await.unwrap();
+    let (_, wrong) = option_ids(&db, ids[1]).await;
+    db.record_answer(session_id, ids[1], &[wrong], "t2", None)
+        .await
+        .unwrap();
+    assert_eq!(db.session_study_time(session_id).await.unwrap(), study_time);
+}
+
+#[tokio::test]
+async fn test_quiz_session_sizes() {
+    let db = create_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let other_id = db
+        .create_user("other@example.com", "password123", "Other")
+        .await
+        .unwrap();
+    let (public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), owner_id).await;
+
+    assert_eq!(
+        db.get_quiz_session_sizes(quiz_id).await.unwrap(),
+        QuizSessionSizes::default()
+    );
+
+    let mock_exam = QuizSessionSizes {
+        default_size: Some(180),
+        min_size: Some(65),
+        max_size: None,
+    };
+    assert!(db
+        .update_quiz_session_sizes(&public_id, mock_exam, owner_id)
+        .await
+        .unwrap());
+    assert_eq!(db.get_quiz_session_sizes(quiz_id).await.unwrap(), mock_exam);
+
+    // Only the owner can change them
+    assert!(!db
+        .update_quiz_session_sizes(&public_id, QuizSessionSizes::default(), other_id)
+        .await
+        .unwrap());
+    assert_eq!(db.get_quiz_session_sizes(quiz_id).await.unwrap(), mock_exam);
+}
+
+#[tokio::test]
+async fn test_in_progress_sessions() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let other_id = db
+        .create_user("other@example.com", "password123", "Other")
+        .await
+        .unwrap();
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(2), user_id).await;
+    let (_, first) = db
+        .create_session("first", quiz_id, 2, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let (_, second) = db
+        .create_session("second", quiz_id, 2, "random", "cram", user_id)
+        .await
+        .unwrap();
+    db.create_session("others", quiz_id, 2, "random", "quiz", other_id)
+        .await
+        .unwrap();
+
+    // Answering makes a session the most recently active one
+    let ids = get_session_question_ids(&db, first).await;
+    let (correct, _) = option_ids(&db, ids[0]).await;
+    db.record_answer(first, ids[0], &[correct], "p1", None)
+        .await
+        .unwrap();
+    let sessions = db.get_in_progress_sessions(quiz_id, user_id).await.unwrap();
+    let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["first", "second"]);
+    assert_eq!(
+        (sessions[0].answered_questions, sessions[0].total_questions),
+        (1, 2)
+    );
+
+    db.touch_session(second).await.unwrap();
+    let sessions = db.get_in_progress_sessions(quiz_id, user_id).await.unwrap();
+    assert_eq!(sessions[0].id, second);
+    assert_eq!(sessions[0].study_mode, "cram");
+
+    // Completed sessions drop off the list
+    let (correct, _) = option_ids(&db, ids[1]).await;
+    db.record_answer(first, ids[1], &[correct], "p2", None)
+        .await
+        .unwrap();
+    let sessions = db.get_in_progress_sessions(quiz_id, user_id).await.unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].id, second);
+}
+
+#[tokio::test]
+async fn test_retake_and_challenge_reuse_questions_and_seed() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let rival_id = db
+        .create_user("rival@example.com", "password123", "Rival")
+        .await
+        .unwrap();
+    let (_public_id, quiz_id) =
+        load_quiz_with_id(&db, "Quiz", pinned_option_questions(), user_id).await;
+    let (_, source) = db
+        .create_session("source", quiz_id, 1, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let question_ids = db.get_session_question_ids(source).await.unwrap();
+    let (correct, wrong) = option_ids(&db, question_ids[0]).await;
+    db.record_answer(source, question_ids[0], &[wrong], "s1", None)
+        .await
+        .unwrap();
+
+    let options = |session_id| {
+        let db = &db;
+        let question_id = question_ids[0];
+        async move {
+            db.get_session_options(session_id, question_id)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|o| o.option)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    // A retake replays the same questions with the same option order
+    let seed = db.get_session_shuffle_seed(source).await.unwrap();
+    let token = db
+        .create_seeded_session(
+            "retake",
+            quiz_id,
+            &question_ids,
+            seed,
+            "retake",
+            "quiz",
+            user_id,
+            None,
+        )
+        .await
+        .unwrap();
+    let retake = db.get_session(&token).await.unwrap().id;
+    assert_eq!(get_session_question_ids(&db, retake).await, question_ids);
+    assert_eq!(options(retake).await, options(source).await);
+
+    // Creating a challenge is idempotent per session
+    let public_id = db.get_or_create_challenge(source, user_id).await.unwrap();
+    assert_eq!(
+        db.get_or_create_challenge(source, user_id).await.unwrap(),
+        public_id
+    );
+    let challenge = db.get_challenge(&public_id).await.unwrap().unwrap();
+    assert_eq!(challenge.question_ids, question_ids);
+    assert_eq!(challenge.shuffle_seed, seed);
+    assert!(!challenge.is_joinable, "the quiz is private");
+    assert_eq!(
+        db.get_session_challenge_public_id(source).await.unwrap(),
+        Some(public_id.clone())
+    );
+
+    db.add_quiz_to_library(rival_id, quiz_id).await.unwrap();
+    let token = db
+        .create_seeded_session(
+            "rival",
+            quiz_id,
+            &challenge.question_ids,
+            challenge.shuffle_seed,
+            "challenge",
+            &challenge.study_mode,
+            rival_id,
+            Some(challenge.id),
+        )
+        .await
+        .unwrap();
+    let rival = db.get_session(&token).await.unwrap().id;
+    assert_eq!(options(rival).await, options(source).await);
+    db.record_answer(rival, question_ids[0], &[correct], "r1", None)
+        .await
+        .unwrap();
+
+    // The better score ranks first; the retake is not part of the challenge
+    let standings = db.get_challenge_standings(challenge.id).await.unwrap();
+    let ranked: Vec<(i32, i32, bool)> = standings
+        .iter()
+        .map(|s| (s.session_id, s.correct, s.is_creator))
+        .collect();
+    assert_eq!(ranked, vec![(rival, 1, false), (source, 0, true)]);
+    assert!(standings.iter().all(|s| s.is_complete));
+}
+
+#[tokio::test]
+async fn test_challenge_on_private_quiz_is_not_joinable() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(2), user_id).await;
+    let (_, session_id) = db
+        .create_session("source", quiz_id, 2, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let challenge_id = db
+        .get_or_create_challenge(session_id, user_id)
+        .await
+        .unwrap();
+
+    // The owner's private quiz must not leak to whoever has the link
+    let challenge = db.get_challenge(&challenge_id).await.unwrap().unwrap();
+    assert!(!challenge.is_joinable);
+
+    assert_eq!(
+        db.toggle_share(&public_id, user_id).await.unwrap(),
+        Some(true)
+    );
+    let challenge = db.get_challenge(&challenge_id).await.unwrap().unwrap();
+    assert!(challenge.is_joinable);
+}
+
+#[tokio::test]
+async fn test_only_completed_sessions_are_complete() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(2), user_id).await;
+    let (_, session_id) = db
+        .create_session("source", quiz_id, 2, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let question_ids = db.get_session_question_ids(session_id).await.unwrap();
+
+    // An in-progress session cannot be retaken
+    assert!(!db.is_session_complete(session_id).await.unwrap());
+    for (i, &question_id) in question_ids.iter().enumerate() {
+        let (correct, _) = option_ids(&db, question_id).await;
+        db.record_answer(session_id, question_id, &[correct], &format!("k{i}"), None)
+            .await
+            .unwrap();
+    }
+    assert!(db.is_session_complete(session_id).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_question_explanation_references_and_hints() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let questions: Vec<Question> = serde_json::from_str(
+        r#"[
+          {
+            "question": "Capital of France?",
+            "explanation": "Paris has been the capital since 987.",
+            "references": ["https://en.wikipedia.org/wiki/Paris", "Atlas, p. 12"],
+            "hints": ["It is on the Seine.", "It starts with P."],
+            "options": [
+              { "text": "Paris", "isAnswer": true },
+              { "text": "Lyon", "isAnswer": false }
+            ]
+          },
+          {
+            "question": "Plain question",
+            "options": [
+              { "text": "A", "isAnswer": true },
+              { "text": "B", "isAnswer": false }
+            ]
+          }
+        ]"#,
+    )
+    .unwrap();
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", questions, user_id).await;
+    let (_, session_id) = db
+        .create_session("s", quiz_id, 2, "random", "quiz", user_id)
+        .await
+        .unwrap();
+
+    let mut hinted_idx = None;
+    for idx in 0..2 {
+        let ctx = db
+            .get_question_context(session_id, quiz_id, idx)
+            .await
+            .unwrap();
+        if ctx.question == "Plain question" {
+            assert_eq!(ctx.explanation, None);
+            assert!(ctx.references.is_empty() && ctx.hints.is_empty());
+        } else {
+            assert_eq!(
+                ctx.explanation.as_deref(),
+                Some("Paris has been the capital since 987.")
+            );
+            assert_eq!(ctx.references.len(), 2);
+            assert_eq!(ctx.hints.len(), 2);
+            assert_eq!(ctx.hints_used, 0);
+            hinted_idx = Some((idx, ctx.question_id));
+        }
+    }
+    let (idx, question_id) = hinted_idx.unwrap();
+
+    // Hints are revealed one at a time and stop at the last one
+    assert_eq!(db.reveal_hint(session_id, idx).await.unwrap().1, 1);
+    assert_eq!(db.reveal_hint(session_id, idx).await.unwrap().1, 2);
+    assert_eq!(db.reveal_hint(session_id, idx).await.unwrap().1, 2);
+
+    let (correct, _) = option_ids(&db, question_id).await;
+    db.record_answer(session_id, question_id, &[correct], "h1", None)
+        .await
+        .unwrap();
+    let answers = db.get_answers(session_id).await.unwrap();
+    assert_eq!(answers.len(), 1);
+    assert_eq!(answers[0].hints_used, 2);
+}
+
+#[tokio::test]
+async fn test_session_review() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    let (_, session_id) = db
+        .create_session("s", quiz_id, 3, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, session_id).await;
+
+    let (correct, wrong) = option_ids(&db, ids[0]).await;
+    db.record_answer(session_id, ids[0], &[wrong], "r1", Some("sure"))
+        .await
+        .unwrap();
+    db.toggle_bookmark(user_id, ids[0]).await.unwrap();
+    let (correct_1, _) = option_ids(&db, ids[1]).await;
+    db.record_answer(session_id, ids[1], &[correct_1], "r2", None)
+        .await
+        .unwrap();
+
+    // Only answered questions are reviewed, in session order
+    let items = db.get_session_review(session_id).await.unwrap();
+    let reviewed: Vec<i32> = items.iter().map(|i| i.question_id).collect();
+    assert_eq!(reviewed, vec![ids[0], ids[1]]);
+
+    let missed = &items[0];
+    assert!(!missed.is_correct);
+    assert!(missed.is_bookmarked);
+    assert_eq!(missed.confidence.as_deref(), Some("sure"));
+    let selected: Vec<i32> = missed
+        .options
+        .iter()
+        .filter(|o| o.is_selected)
+        .map(|o| o.id)
+        .collect();
+    assert_eq!(selected, vec![wrong]);
+    assert!(missed
+        .options
+        .iter()
+        .any(|o| o.id == correct && o.is_answer));
+
+    // Options keep the order the learner saw
+    let shown: Vec<i32> = db
+        .get_session_options(session_id, ids[0])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|o| o.id)
+        .collect();
+    let reviewed_order: Vec<i32> = missed.options.iter().map(|o| o.id).collect();
+    assert_eq!(reviewed_order, shown);
+
+    assert!(items[1].is_correct);
+    assert!(!items[1].is_bookmarked);
+}
+
+#[tokio::test]
+async fn test_templated_questions_are_instantiated_per_session() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let questions: Vec<Question> = serde_json::from_str(
+        r#"[{
+          "question": "What is {a} × {b}?",
+          "variables": { "a": { "min": 2, "max": 12 }, "b": { "min": 2, "max": 12 } },
+          "explanation": "{a} × {b} = {a * b}",
+          "options": [
+            { "text": "{a * b}", "isAnswer": true },
+            { "text": "{a * b + a}", "isAnswer": false },
+            { "text": "{a * (b - 1)}", "isAnswer": false },
+            { "text": "{a + b}", "isAnswer": false }
+          ]
+        }]"#,
+    )
+    .unwrap();
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Times tables", questions, user_id).await;
+    let (_, session_id) = db
+        .create_session("s", quiz_id, 1, "random", "quiz", user_id)
+        .await
+        .unwrap();
+
+    let ctx = db
+        .get_question_context(session_id, quiz_id, 0)
+        .await
+        .unwrap();
+    let values = quizinart::template::parse_values(ctx.template_values.as_deref()).unwrap();
+    let (a, b) = (values["a"], values["b"]);
+    assert!((2..=12).contains(&a) && (2..=12).contains(&b));
+    assert_eq!(ctx.question, format!("What is {a} × {b}?"));
+    assert_eq!(ctx.explanation, Some(format!("{a} × {b} = {}", a * b)));
+
+    let options = db
+        .get_session_options(session_id, ctx.question_id)
+        .await
+        .unwrap();
+    let correct: Vec<&str> = options
+        .iter()
+        .filter(|o| o.is_answer)
+        .map(|o| o.option.as_str())
+        .collect();
+    assert_eq!(correct, vec![(a * b).to_string()]);
+    let texts: HashSet<&str> = options.iter().map(|o| o.option.as_str()).collect();
+    assert_eq!(texts.len(), 4, "distractors never repeat the answer");
+
+    // Resume and review show the same numbers; a retake with the same seed does too
+    let again = db
+        .get_question_context(session_id, quiz_id, 0)
+        .await
+        .unwrap();
+    assert_eq!(again.question, ctx.question);
+    let (correct_id, _) = option_ids(&db, ctx.question_id).await;
+    db.record_answer(session_id, ctx.question_id, &[correct_id], "t1", None)
+        .await
+        .unwrap();
+    let review = db.get_session_review(session_id).await.unwrap();
+    assert_eq!(review[0].question, ctx.question);
+    let answers = db.get_answers(session_id).await.unwrap();
+    assert_eq!(answers[0].question, ctx.question);
+
+    let seed = db.get_session_shuffle_seed(session_id).await.unwrap();
+    let token = db
+        .create_seeded_session(
+            "retake",
+            quiz_id,
+            &[ctx.question_id],
+            seed,
+            "retake",
+            "quiz",
+            user_id,
+            None,
+        )
+        .await
+        .unwrap();
+    let retake = db.get_session(&token).await.unwrap().id;
+    let retake_ctx = db.get_question_context(retake, quiz_id, 0).await.unwrap();
+    assert_eq!(retake_ctx.question, ctx.question);
+}
+
+#[tokio::test]
+async fn test_templated_question_with_unknown_variable_is_rejected() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let questions: Vec<Question> = serde_json::from_str(
+        r#"[{
+          "question": "What is {a} + {c}?",
+          "variables": { "a": { "min": 1, "max": 9 } },
+          "options": [{ "text": "{a}", "isAnswer": true }]
+        }]"#,
+    )
+    .unwrap();
+    assert!(db
+        .load_quiz("Broken".to_string(), questions, user_id)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_answer_distribution_counts_first_attempts() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(1), user_id).await;
+    let question_id = db.question_id_from_idx(quiz_id, 0).await.unwrap();
+    let (correct, wrong) = option_ids(&db, question_id).await;
+
+    let mut session_ids = Vec::new();
+    for (i, pick) in [correct, wrong, wrong].into_iter().enumerate() {
+        let (_, session_id) = db
+            .create_session(&format!("s{i}"), quiz_id, 1, "random", "cram", user_id)
+            .await
+            .unwrap();
+        db.record_answer(
+            session_id,
+            question_id,
+            &[pick],
+            &format!("{session_id}-{i}"),
+            None,
+        )
+        .await
+        .unwrap();
+        session_ids.push(session_id);
+        if pick == wrong {
+            // The cram re-queue is a second attempt and must not be counted again
+            db.record_answer(session_id, question_id, &[wrong], &format!("r{i}"), None)
+                .await
+                .unwrap();
+        }
+    }
+
+    let distribution = db.get_answer_distribution(question_id).await.unwrap();
+    assert_eq!(distribution.responses, 3);
+    assert_eq!(distribution.picks.get(&correct), Some(&1));
+    assert_eq!(distribution.picks.get(&wrong), Some(&2));
+
+    let analysis = db.get_distractor_analysis(quiz_id).await.unwrap();
+    assert_eq!(analysis.len(), 1);
+    assert_eq!(analysis[0].responses, 3);
+    let picked: Vec<(bool, i32)> = analysis[0]
+        .options
+        .iter()
+        .map(|o| (o.is_answer, o.picks))
+        .collect();
+    assert!(picked.contains(&(true, 1)) && picked.contains(&(false, 2)));
+
+    // Deleted sessions no longer count
+    db.delete_session(session_ids[2]).await.unwrap();
+    let distribution = db.get_answer_distribution(question_id).await.unwrap();
+    assert_eq!(distribution.responses, 2);
+    assert_eq!(distribution.picks.get(&wrong), Some(&1));
+}
+
+#[tokio::test]
+async fn test_item_analysis_covers_every_learner() {
+    let db = create_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(2), owner_id).await;
+
+    let mut learners = vec![owner_id];
+    for i in 0..3 {
+        let id = db
+            .create_user(&format!("learner{i}@example.com"), "password123", "Learner")
+            .await
+            .unwrap();
+        db.add_quiz_to_library(id, quiz_id).await.unwrap();
+        learners.push(id);
+    }
+
+    for (n, user_id) in learners.iter().enumerate() {
+        answer_session(&db, *user_id, quiz_id, &[n < 2; 2]).await;
+    }
+
+    let items = db.get_item_analysis(quiz_id).await.unwrap();
+    assert_eq!(items.len(), 2);
+    for item in &items {
+        assert_eq!(item.responses, 4);
+        assert_eq!(item.difficulty, Some(0.5));
+        // Top scorers answered everything correctly, bottom scorers nothing
+        assert_eq!(item.discrimination, Some(1.0));
+        assert!(!item.likely_miskeyed);
+        assert!(item.top_distractor.is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_pace_report_uses_timed_first_attempts() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(2), user_id).await;
+    let (_, session_id) = db
+        .create_session("s", quiz_id, 2, "random", "quiz", user_id)
+        .await
+        .unwrap();
+
+    // Only the first question is shown before answering, so only it is timed
+    db.mark_question_served(session_id, 0).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let ids = answer_questions(&db, session_id, &[true, true]).await;
+
+    let report = db.get_pace_report(quiz_id, user_id).await.unwrap();
+    assert_eq!(report.sessions.len(), 1);
+    assert_eq!(report.sessions[0].answered, 1);
+    assert!(report.sessions[0].median_ms >= 50);
+    assert_eq!(report.slowest.len(), 1);
+    assert_eq!(report.slowest[0].question_id, ids[0]);
+    assert_eq!(report.trend_ms_per_session, None);
+
+    let answers = db.get_answers(session_id).await.unwrap();
+    assert!(answers.iter().any(|a| a.active_ms >= 50));
+}
+
+#[tokio::test]
+async fn test_activity_summary_and_daily_goal() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    answer_session(&db, user_id, quiz_id, &[true, true]).await;
+
+    let summary = db.get_activity_summary(user_id, 7).await.unwrap();
+    assert_eq!(summary.days.len(), 7);
+    let today = summary.days.last().unwrap();
+    assert_eq!(today.days_ago, 0);
+    assert_eq!(today.questions, 2);
+    assert!(summary.days[..6].iter().all(|d| d.questions == 0));
+    assert_eq!((summary.current_streak, summary.longest_streak), (1, 1));
+    assert!(summary.goal.is_none());
+
+    let goal = DailyGoal {
+        kind: "questions".to_string(),
+        target: 20,
+    };
+    db.set_daily_goal(user_id, Some(&goal)).await.unwrap();
+    let saved = db
+        .get_activity_summary(user_id, 1)
+        .await
+        .unwrap()
+        .goal
+        .unwrap();
+    assert_eq!((saved.kind.as_str(), saved.target), ("questions", 20));
+
+    db.set_daily_goal(user_id, None).await.unwrap();
+    assert!(db
+        .get_activity_summary(user_id, 1)
+        .await
+        .unwrap()
+        .goal
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_readiness_from_own_first_attempts() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(6), user_id).await;
+    assert!(db.get_readiness(quiz_id, user_id).await.unwrap().is_none());
+
+    let pattern = [false, false, true, true, true, true];
+    let (_, ids) = answer_session(&db, user_id, quiz_id, &pattern).await;
+
+    let readiness = db.get_readiness(quiz_id, user_id).await.unwrap().unwrap();
+    assert!((readiness.coverage - 1.0).abs() < 1e-9);
+    assert!((readiness.recent_accuracy - 4.0 / 6.0).abs() < 1e-9);
+    assert_eq!(readiness.days_since_practice, 0);
+    assert!(readiness.low <= readiness.score && readiness.score <= readiness.high);
+    // The weakest category is one of the missed questions' ("Question n" is in
+    // "Category (n - 1) % 3", see make_questions)
+    let mut missed = Vec::new();
+    for question_id in &ids[..2] {
+        let question = db.get_question(*question_id).await.unwrap().question;
+        let n: usize = question.trim_start_matches("Question ").parse().unwrap();
+        missed.push(format!("Category {}", (n - 1) % 3));
+    }
+    assert!(missed.contains(&readiness.holding_back[0].category));
+}
+
+#[tokio::test]
+async fn test_session_comparison_finds_flipped_questions() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+
+    // Everything wrong the first time, all but one right the second
+    let (first, _) = answer_session(&db, user_id, quiz_id, &[false; 3]).await;
+    let (second, _) = answer_session(&db, user_id, quiz_id, &[false, true, true]).await;
+    let session_ids = vec![first, second];
+
+    // Another learner's session is left out
+    let other_id = db
+        .create_user("other@example.com", "password123", "Other")
+        .await
+        .unwrap();
+    let (_, other_session) = db
+        .create_session("o", quiz_id, 3, "random", "quiz", other_id)
+        .await
+        .unwrap();
+
+    let comparison = db
+        .get_session_comparison(
+            quiz_id,
+            user_id,
+            &[session_ids[1], other_session, session_ids[0]],
+        )
+        .await
+        .unwrap();
+    let ids: Vec<i32> = comparison.sessions.iter().map(|s| s.id).collect();
+    assert_eq!(ids, session_ids);
+    assert_eq!(comparison.sessions[0].score, 0);
+    assert_eq!(comparison.sessions[1].score, 2);
+    assert_eq!(comparison.categories.len(), 3);
+
+    assert_eq!(comparison.flipped.len(), 2);
+    assert!(comparison
+        .flipped
+        .iter()
+        .all(|q| !q.was_correct && q.now_correct && q.outcomes == vec![Some(false), Some(true)]));
+}
+
+#[tokio::test]
+async fn test_study_plan_inputs_and_threshold() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(4), user_id).await;
+    assert!(db
+        .get_study_plan_inputs(quiz_id, user_id)
+        .await
+        .unwrap()
+        .is_none());
+
+    assert!(db.is_past_date("2000-01-01").await.unwrap());
+    assert!(!db.is_past_date("2099-12-31").await.unwrap());
+
+    let settings = StudyPlanSettings {
+        exam_date: "2099-12-31".to_string(),
+        pass_threshold: 80,
+    };
+    assert!(db
+        .set_study_plan(quiz_id, user_id, Some(&settings))
+        .await
+        .unwrap());
+    // Not in the other learner's library
+    let other_id = db
+        .create_user("other@example.com", "password123", "Other")
+        .await
+        .unwrap();
+    assert!(!db
+        .set_study_plan(quiz_id, other_id, Some(&settings))
+        .await
+        .unwrap());
+    db.set_plan_daily_target(quiz_id, user_id, 12)
+        .await
+        .unwrap();
+
+    answer_session(&db, user_id, quiz_id, &[false, true, true]).await;
+
+    let inputs = db
+        .get_study_plan_inputs(quiz_id, user_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(inputs.exam_date, "2099-12-31");
+    assert!(inputs.days_left > 0);
+    assert_eq!(inputs.pass_threshold, 80);
+    assert_eq!((inputs.planned_daily, inputs.days_elapsed), (12, 0));
+    assert_eq!((inputs.unseen, inputs.to_review), (1, 1));
+    assert_eq!((inputs.answered_today, inputs.answered_since_start), (3, 3));
+
+    let readiness = db.get_readiness(quiz_id, user_id).await.unwrap().unwrap();
+    assert!((readiness.pass_mark - 0.8).abs() < 1e-9);
+
+    db.set_study_plan(quiz_id, user_id, None).await.unwrap();
+    assert!(db
+        .get_study_plan_inputs(quiz_id, user_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_answer_log_and_category_export() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), user_id).await;
+    let (session_id, ids) = answer_session(&db, user_id, quiz_id, &[true]).await;
+    let (_, wrong) = option_ids(&db, ids[1]).await;
+    db.record_answer(session_id, ids[1], &[wrong], "rated", Some("sure"))
+        .await
+        .unwrap();
+
+    let log = db.get_answer_log(quiz_id, user_id).await.unwrap();
+    assert_eq!(log.len(), 2);
+    assert_eq!(log[0].question_number, 1);
+    assert_eq!(log[0].question_id, ids[0]);
+    assert!(log[0].is_correct && !log[1].is_correct);
+    assert_eq!(log[0].selected, log[0].correct_options);
+    assert_ne!(log[1].selected, log[1].correct_options);
+    assert_eq!(log[1].confidence.as_deref(), Some("sure"));
+    assert!(log[0]
+        .answered_at
+        .as_deref()
+        .is_some_and(|t| t.ends_with('Z')));
+
+    let other_id = db
+        .create_user("other@example.com", "password123", "Other")
+        .await
+        .unwrap();
+    assert!(db
+        .get_answer_log(quiz_id, other_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let categories = db.get_category_export(quiz_id, user_id).await.unwrap();
+    assert_eq!(categories.len(), 3);
+    assert_eq!(categories.iter().map(|c| c.questions).sum::<i64>(), 3);
+    assert_eq!(categories.iter().map(|c| c.answered).sum::<i64>(), 2);
+    assert_eq!(categories.iter().map(|c| c.correct).sum::<i64>(), 1);
+    assert_eq!(
+        categories.iter().filter(|c| c.accuracy.is_none()).count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_weekly_digest() {
+    let db = create_test_db().await;
+    let user_id = create_test_user(&db).await;
+    assert!(!db.weekly_digest_enabled(user_id).await.unwrap());
+
+    db.set_weekly_digest(user_id, true, "ja").await.unwrap();
+    assert!(db.weekly_digest_enabled(user_id).await.unwrap());
+    // Opting in starts the week, so nobody is due yet
+    assert!(db.users_due_for_digest().await.unwrap().is_empty());
+
+    let (_public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(9), user_id).await;
+    let (_, session_id) = db
+        .create_session("s", quiz_id, 9, "random", "quiz", user_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, session_id).await;
+    for (i, question_id) in ids.iter().enumerate() {
+        let (correct, wrong) = option_ids(&db, *question_id).await;
+        let selected = if i < 2 { wrong } else { correct };
+        db.record_answer(
+            session_id,
+            *question_id,
+            &[selected],
+            &format!("k{question_id}"),
+            None,
+        )
+        .await
+        .unwrap();
+    }
+
+    let digest = db.get_weekly_digest(user_id).await.unwrap();
+    assert_eq!(digest.questions_answered, 9);
+    assert!(digest
+        .accuracy
+        .is_some_and(|a| (a - 700.0 / 9.0).abs() < 0.01));
+    assert_eq!(digest.previous_accuracy, None);
+    assert_eq!(digest.due_reviews, 2);
+    assert!(!digest.weakest.is_empty());
+    assert_eq!(digest.weakest[0].quiz_name, "Quiz");
+    assert_eq!(digest.weakest[0].total, 3);
+    assert!(digest.weakest[0].correct < 3);
+    assert!(digest
+        .weakest
+        .windows(2)
+        .all(|w| w[0].correct * w[1].total <= w[1].correct * w[0].total));
+
+    db.set_weekly_digest(user_id, false, "en").await.unwrap();
+    assert!(!db.weekly_digest_enabled(user_id).await.unwrap());
+    let other_id = db
+        .create_user("other@example.com", "password123", "Other")
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_weekly_digest(other_id).await.unwrap(),
+        WeeklyDigest::default()
+    );
+}
+
+#[tokio::test]
+async fn test_group_membership_and_progress() {
+    let db = create_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let (quiz_public_id, quiz_id) =
+        load_quiz_with_id(&db, "Quiz", make_questions(3), owner_id).await;
+    let member_id = db
+        .create_user("other@example.com", "password123", "Other")
+        .await
+        .unwrap();
+
+    let public_id = db.create_group("Cohort", owner_id).await.unwrap();
+    let group = db.get_group(&public_id).await.unwrap().unwrap();
+    assert_eq!(group.owner_name, "Test User");
+    assert_eq!(
+        db.get_group_by_invite(&group.invite_code)
+            .await
+            .unwrap()
+            .map(|g| g.id),
+        Some(group.id)
+    );
+
+    // Members joining before or after an assignment both get the quiz in their library
+    db.join_group(group.id, member_id).await.unwrap();
+    assert!(!db.user_has_quiz(member_id, quiz_id).await.unwrap());
+    db.assign_group_quiz(group.id, quiz_id).await.unwrap();
+    assert!(db.user_has_quiz(member_id, quiz_id).await.unwrap());
+    assert!(db.is_group_member(group.id, member_id).await.unwrap());
+    assert!(!db.is_group_member(group.id, owner_id).await.unwrap());
+
+    let quizzes = db.group_quizzes(group.id).await.unwrap();
+    assert_eq!(quizzes.len(), 1);
+    assert_eq!(quizzes[0].public_id, quiz_public_id);
+    assert_eq!(quizzes[0].question_count, 3);
+
+    let (_, session_id) = db
+        .create_session("s", quiz_id, 3, "random", "quiz", member_id)
+        .await
+        .unwrap();
+    let ids = get_session_question_ids(&db, session_id).await;
+    let (correct, _) = option_ids(&db, ids[0]).await;
+    db.record_answer(session_id, ids[0], &[correct], "k", None)
+        .await
+        .unwrap();
+
+    let rows = db.get_group_progress(group.id).await.unwrap();
+    assert_eq!(rows.len(), 3);
+    assert!(rows
+        .iter()
+        .all(|r| r.user_id == member_id && r.questions == 1));
+    assert_eq!(rows.iter().map(|r| r.seen).sum::<i64>(), 1);
+    assert_eq!(rows.iter().map(|r| r.correct).sum::<i64>(), 1);
+
+    let owner_groups = db.groups_for_user(owner_id).await.unwrap();
+    assert_eq!(owner_groups.len(), 1);
+    assert!(owner_groups[0].is_owner);
+    assert_eq!(owner_groups[0].member_count, 1);
+    let member_groups = db.groups_for_user(member_id).await.unwrap();
+    assert!(!member_groups[0].is_owner);
+
+    db.remove_group_member(group.id, member_id).await.unwrap();
+    assert!(db.get_group_progress(group.id).await.unwrap().is_empty());
+    assert!(db.user_has_quiz(member_id, quiz_id).await.unwrap());
+
+    db.delete_group(group.id).await.unwrap();
+    assert!(db.get_group(&public_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_assignment_completion() {
+    let db = create_test_db().await;
+    let teacher_id = create_test_user(&db).await;
+    let (_, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(2), teacher_id).await;
+    // Registered with a mixed-case address, looked up by the lowercased one
+    let learner_id = db
+        .create_user("Other@Example.com", "password123", "Other")
+        .await
+        .unwrap();
+
+    let mut settings = AssignmentSettings {
+        quiz_id,
+        due_date: "2000-01-01".to_string(),
+        question_count: 5,
+        selection_mode: "random".to_string(),
+        target_score: 100,
+    };
+    // Individual assignments only reach members of the instructor's groups
+    assert_eq!(
+        db.find_led_group_member(teacher_id, "other@example.com")
+            .await
+            .unwrap(),
+        None
+    );
+    let group_public_id = db.create_group("Class", teacher_id).await.unwrap();
+    let group = db.get_group(&group_public_id).await.unwrap().unwrap();
+    db.join_group(group.id, learner_id).await.unwrap();
+    assert_eq!(
+        db.find_led_group_member(teacher_id, "other@example.com")
+            .await
+            .unwrap(),
+        Some(learner_id)
+    );
+    assert_eq!(
+        db.find_led_group_member(teacher_id, "test@example.com")
+            .await
+            .unwrap(),
+        None
+    );
+
+    let target = AssignmentTarget::User(learner_id);
+    assert!(db
+        .create_assignment(teacher_id, &target, &settings)
+        .await
+        .unwrap()
+        .is_none());
+
+    settings.due_date = "2999-12-31".to_string();
+    let public_id = db
+        .create_assignment(teacher_id, &target, &settings)
+        .await
+        .unwrap()
+        .unwrap();
+    let assignment = db.get_assignment(&public_id).await.unwrap().unwrap();
+    assert_eq!(assignment.assignee_name.as_deref(), Some("Other"));
+    assert!(assignment.days_left > 0);
+    assert!(db.user_has_quiz(learner_id, quiz_id).await.unwrap());
+    assert!(db
+        .is_assignment_learner(assignment.id, learner_id)
+        .await
+        .unwrap());
+    assert!(!db
+        .is_assignment_learner(assignment.id, teacher_id)
+        .await
+        .unwrap());
+    assert_eq!(
+        db.assignments_for_learner(learner_id).await.unwrap().len(),
+        1
+    );
+    assert_eq!(
+        db.assignments_created_by(teacher_id).await.unwrap().len(),
+        1
+    );
+
+    // A finished attempt below the target does not complete the assignment
+    for all_correct in [false, true] {
+        let (_, session_id) = db
+            .create_assignment_session(&format!("a-{all_correct}"), &assignment, "quiz", learner_id)
+            .await
+            .unwrap();
+        for (i, question_id) in get_session_question_ids(&db, session_id)
+            .await
+            .into_iter()
+            .enumerate()
+        {
+            let (correct, wrong) = option_ids(&db, question_id).await;
+            let pick = if all_correct || i > 0 { correct } else { wrong };
+            db.record_answer(
+                session_id,
+                question_id,
+                &[pick],
+                &format!("{session_id}-{i}"),
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        let learners = db
+            .assignment_learners(&[assignment.id], None)
+            .await
+            .unwrap();
+        assert_eq!(learners.len(), 1);
+        assert_eq!(learners[0].user_id, learner_id);
+        if all_correct {
+            assert_eq!(learners[0].attempts, 2);
+            assert_eq!(learners[0].best_score, Some(100));
+            assert!(learners[0].completed_on.is_some());
+        } else {
+            assert_eq!(learners[0].best_score, Some(50));
+            assert!(learners[0].completed_on.is_none());
+        }
+    }
+
+    db.delete_assignment(assignment.id).await.unwrap();
+    assert!(db.get_assignment(&public_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_invitations_reach_mixed_case_accounts() {
+    let db = create_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let (public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(1), owner_id).await;
+    let alice_id = db
+        .create_user("Alice@Example.com", "password123", "Alice")
+        .await
+        .unwrap();
+
+    // Invited emails are lowercased; the account keeps the case it registered with
+    db.invite_quiz_collaborator(quiz_id, "alice@example.com", "editor", owner_id)
+        .await
+        .unwrap();
+    let received = db.received_invitations(alice_id).await.unwrap();
+    assert_eq!(received.len(), 1);
+    db.decline_quiz_invitation(received[0].id, alice_id)
+        .await
+        .unwrap();
+    assert!(db.quiz_invitations(quiz_id).await.unwrap().is_empty());
+
+    db.invite_quiz_collaborator(quiz_id, "alice@example.com", "editor", owner_id)
+        .await
+        .unwrap();
+    let received = db.received_invitations(alice_id).await.unwrap();
+    assert!(db
+        .accept_quiz_invitation(received[0].id, alice_id)
+        .await
+        .unwrap());
+    assert_eq!(
+        db.quiz_access(&public_id, alice_id).await.unwrap(),
+        Some(QuizAccess::Editor)
+    );
+}
+
+#[tokio::test]
+async fn test_quiz_collaborators_and_change_history() {
+    let db = create_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let (public_id, quiz_id) = load_quiz_with_id(&db, "Quiz", make_questions(3), owner_id).await;
+    let collaborator_id = db
+        .create_user("other@example.com", "password123", "Other")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        db.quiz_access(&public_id, owner_id).await.unwrap(),
+        Some(QuizAccess::Owner)
+    );
+    assert_eq!(
+        db.quiz_access(&public_id, collaborator_id).await.unwrap(),
+        None
+    );
+
+    // Invitations grant nothing until the invitee accepts them
+    db.invite_quiz_collaborator(quiz_id, "nobody@example.com", "editor", owner_id)
+        .await
+        .unwrap();
+    db.invite_quiz_collaborator(quiz_id, "other@example.com", "sharing", owner_id)
+        .await
+        .unwrap();
+    let pending = db.quiz_invitations(quiz_id).await.unwrap();
+    let emails: Vec<&str> = pending.iter().map(|i| i.email.as_str()).collect();
+    assert_eq!(emails, vec!["nobody@example.com", "other@example.com"]);
+    assert_eq!(
+        db.quiz_access(&public_id, collaborator_id).await.unwrap(),
+        None
+    );
+    assert!(!db.user_has_quiz(collaborator_id, quiz_id).await.unwrap());
+    assert!(db.received_invitations(owner_id).await.unwrap().is_empty());
+    let received = db.received_invitations(collaborator_id).await.unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].invited_by, "Test User");
+    assert!(!db
+        .accept_quiz_invitation(received[0].id, owner_id)
+        .await
+        .unwrap());
+
+    // Sharing managers share the quiz but cannot change it
+    assert!(db
+        .accept_quiz_invitation(received[0].id, collaborator_id)
+        .await
+        .unwrap());
+    assert_eq!(db.quiz_invitations(quiz_id).await.unwrap().len(), 1);
+    assert_eq!(
+        db.quiz_access(&public_id, collaborator_id).await.unwrap(),
+        Some(QuizAccess::SharingManager)
+    );
+    assert!(db.user_has_quiz(collaborator_id, quiz_id).await.unwrap());
+    assert!(!db
+        .rename_quiz(&public_id, "Sharer name", collaborator_id)
+        .await
+        .unwrap());
+    assert_eq!(db.quiz_name(quiz_id).await.unwrap(), "Quiz");
+    assert_eq!(
+        db.toggle_share(&public_id, collaborator_id).await.unwrap(),
+        Some(true)
+    );
+
+    // Editors change the quiz but not its sharing
+    db.invite_quiz_collaborator(quiz_id, "other@example.com", "editor", owner_id)
+        .await
+        .unwrap();
+    let received = db.received_invitations(collaborator_id).await.unwrap();
+    assert!(db
+        .accept_quiz_invitation(received[0].id, collaborator_id)
+        .await
+        .unwrap());
+    let library = db.quizzes(collaborator_id).await.unwrap();
+    assert!(library[0].can_edit && !library[0].can_share && !library[0].is_owner);
+    assert!(db
+        .rename_quiz(&public_id, "Edited", collaborator_id)
+        .await
+        .unwrap());
+    assert_eq!(db.quiz_name(quiz_id).await.unwrap(), "Edited");
+    assert_eq!(
+        db.toggle_share(&public_id, collaborator_id).await.unwrap(),
+        None
+    );
+
+    let collaborators = db.quiz_collaborators(quiz_id).await.unwrap();
+    assert_eq!(collaborators.len(), 1);
+    assert_eq!(collaborators[0].role, "editor");
+
+    // Collaborators do not keep the owner from deleting the quiz
+    assert!(!db.quiz_has_other_users(&public_id, owner_id).await.unwrap());
+
+    let pending = db.quiz_invitations(quiz_id).await.unwrap();
+    db.cancel_quiz_invitation(quiz_id, pending[0].id)
+        .await
+        .unwrap();
+    assert!(db.quiz_invitations(quiz_id).await.unwrap().is_empty());
+
+    db.remove_quiz_collaborator(quiz_id, collaborator_id, owner_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        db.quiz_access(&public_id, collaborator_id).await.unwrap(),
+        None
+    );
+    assert!(!db
+        .rename_quiz(&public_id, "Revoked", collaborator_id)
+        .await
+        .unwrap());
+
+    let changes = db.quiz_changes(quiz_id).await.unwrap();
+    let actions: Vec<&str> = changes.iter().map(|c| c.action.as_str()).collect();
+    assert_eq!(
+        actions,
+        vec![
+            "collaborator_removed",
+            "rename",
+            "collaborator_editor",
+            "share",
+            "collaborator_sharing"
+        ]
+    );
+    assert_eq!(changes[1].display_name.as_deref(), Some("Other"));
+    assert_eq!(changes[1].detail, "Edited");
+    assert_eq!(changes[0].display_name.as_deref(), Some("Test User"));
+}
+
 // --- User tests ---
 
 #[tokio::test]